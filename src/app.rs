@@ -1,31 +1,275 @@
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use notify::{Event as NotifyEvent, RecursiveMode, Result as NotifyResult, Watcher};
 use parking_lot::RwLock;
+use ratatui::text::Line;
 use rayon::prelude::*;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::{collections::HashMap, fs, io};
+use std::time::{Duration, Instant};
+use std::{fs, io};
+use toml_edit::{table, value, Array, ArrayOfTables, Item};
 
-use crate::config::find_and_load_config;
-use crate::utils::{apply_substitution_partial, is_text_file};
+use crate::config::{
+    edit_config, find_and_load_config, preset_table, BehaviorConfig, Config, DisplayConfig,
+    FilesConfig, PerformanceConfig, Preset, ThemeConfig, DEFAULT_DETECT_SAMPLE_BYTES,
+    DEFAULT_MATCH_TIMEOUT_MS, DEFAULT_MAX_FILES, DEFAULT_POLL_MS, DEFAULT_PREVIEW_KB,
+    DEFAULT_TAB_WIDTH, MAX_POLL_MS, MIN_POLL_MS,
+};
+use crate::edits::{substitution_edits, EditSet, FileEdits};
+use crate::encoding::{detect_file_info, FileInfo};
+use crate::external::{copy_to_clipboard, open_directory, pipe_through};
+use crate::history::{History, HistorySearch};
+use crate::hunk::{
+    apply_hunks, change_lines, changed_rows, collapse_rows, collapsed_row, diff_row, hunks,
+    step_wrapping, DiffRow, Hunk,
+};
+use crate::patch::{apply_patch, FilePatch};
+use crate::report::{format_lines, ApplyReport};
+use crate::results::{match_results, MatchResult, ResultsView};
+use crate::script::Rule;
+use crate::size::{parse_size_filter, SizeRange};
+use crate::template::parse_template;
+use crate::theme::DiffTheme;
+use crate::tree::{group_rows, ListRow};
+use crate::utils::{
+    added_trailing_whitespace, already_applied, apply_regex_substitution, apply_substitution_with,
+    byte_delta, changed_lines, collapse_diff_lines, copy_target, effective_pattern,
+    expand_path_tokens, expand_path_tokens_escaped, file_set_delta, first_changed_line,
+    first_match_captures, format_byte_delta, format_duration, highlight_diff_lines_with,
+    is_text_file, match_summary, read_hex_preview, read_preview, regex_error_message,
+    substitution_is_match, substitution_lines, unescape_replacement, MatchSummary, Preview,
+    SubstitutionOptions,
+};
+use crate::watch::RecentWrites;
 
-type FilterCache = (String, String, Vec<String>);
-type FileCache = HashMap<String, String>;
+const TYPE_AHEAD_TIMEOUT: Duration = Duration::from_millis(1000);
+const RESULT_CONTEXT_LINES: usize = 2;
+const DIFF_CONTEXT_LINES: usize = 3;
+pub const CONFLICT_MARKER_PATTERN: &str = r"(?m)^(?:<{7}|>{7})(?:[ \t]|\r?$)";
+
+fn default_threads() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
+
+type FilterCache = (FilterKey, Vec<String>);
+type ChangeCache = (ChangeKey, ChangeSummary);
+type PreviewCache = (PreviewKey, Arc<PreviewChanges>);
+
+#[derive(Default)]
+struct ScanCache {
+    filtered: Option<FilterCache>,
+    changes: Option<ChangeCache>,
+    preview: Option<PreviewCache>,
+}
+
+#[derive(Default)]
+struct FileCache {
+    contents: HashMap<String, String>,
+    infos: HashMap<String, FileInfo>,
+    text: HashMap<String, bool>,
+}
+
+impl FileCache {
+    fn remove(&mut self, path: &str) {
+        self.contents.remove(path);
+        self.infos.remove(path);
+        self.text.remove(path);
+    }
+
+    fn clear(&mut self) {
+        *self = FileCache::default();
+    }
+}
+type HunkKey = (String, String, SubstitutionOptions);
+
+#[derive(PartialEq, Eq, Clone)]
+struct FilterKey {
+    filter: String,
+    from: String,
+    content_filter: String,
+    options: SubstitutionOptions,
+    only_matching: bool,
+    conflicts_only: bool,
+    show_match_lines: bool,
+    inline_to: Option<String>,
+    applied_to: Option<String>,
+    path_regex: bool,
+    rules: Vec<Rule>,
+}
+
+#[derive(PartialEq, Eq, Clone)]
+struct ChangeKey {
+    files: Vec<String>,
+    from: String,
+    to: String,
+    options: SubstitutionOptions,
+    rules: Vec<Rule>,
+}
+
+#[derive(Clone, Copy)]
+struct ChangeSummary {
+    any_matching: bool,
+    changed_files: usize,
+    byte_delta: isize,
+}
+
+#[derive(PartialEq, Eq, Clone)]
+struct PreviewKey {
+    path: String,
+    from: String,
+    to: String,
+    options: SubstitutionOptions,
+    rules: Vec<Rule>,
+    full_diff: bool,
+}
+
+struct PreviewChanges {
+    original: String,
+    replaced: String,
+    hunks: Vec<Hunk>,
+    changes: Vec<usize>,
+    trailing_whitespace: usize,
+    match_count: Option<usize>,
+}
+
+#[derive(PartialEq, Eq, Clone)]
+struct DiffKey {
+    path: String,
+    from: String,
+    to: String,
+    options: SubstitutionOptions,
+    rules: Vec<Rule>,
+    compare: Option<(String, String)>,
+    full_diff: bool,
+    content: String,
+}
+
+type DiffCache = (DiffKey, Vec<Line<'static>>);
+
+#[derive(Clone)]
+struct UndoFile {
+    path: String,
+    original: Vec<u8>,
+    written: String,
+}
 
 #[derive(PartialEq, Eq, Clone, Copy)]
 pub enum Focus {
     FileList,
     FilePathFilter,
+    ContentFilter,
     DiffView,
     From,
     To,
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum LayoutMode {
+    Normal,
+    Review,
+}
+
 #[derive(Clone)]
 pub enum ConfirmState {
     None,
     Confirming(String),
     ConfirmingAll(Vec<String>),
+    ConfirmingFrom(Vec<String>),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum NoChangeReason {
+    NoMatchingFiles,
+    NoEffectiveChange,
+}
+
+impl NoChangeReason {
+    pub fn message(&self) -> &'static str {
+        match self {
+            NoChangeReason::NoMatchingFiles => "From pattern matches no files",
+            NoChangeReason::NoEffectiveChange => "From matches, but To leaves every file unchanged",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SavedState {
+    pub filter_input: String,
+    pub filter_cursor: usize,
+    pub content_filter_input: String,
+    pub content_filter_cursor: usize,
+    pub from_input: String,
+    pub from_cursor: usize,
+    pub to_input: String,
+    pub to_cursor: usize,
+    pub options: SubstitutionOptions,
+}
+
+enum PathSet {
+    Glob(globset::GlobSet),
+    Regex(regex::RegexSet),
+}
+
+impl PathSet {
+    fn build(patterns: &[&str], regex: bool) -> Option<Self> {
+        if regex {
+            let valid = patterns
+                .iter()
+                .filter(|p| regex::Regex::new(p).is_ok())
+                .collect::<Vec<_>>();
+            regex::RegexSet::new(valid).ok().map(PathSet::Regex)
+        } else {
+            let mut builder = globset::GlobSetBuilder::new();
+            for pattern in patterns {
+                if let Ok(glob) = globset::Glob::new(pattern) {
+                    builder.add(glob);
+                }
+            }
+            builder.build().ok().map(PathSet::Glob)
+        }
+    }
+
+    fn is_match(&self, path: &str) -> bool {
+        match self {
+            PathSet::Glob(set) => set.is_match(path),
+            PathSet::Regex(set) => set.is_match(path),
+        }
+    }
+}
+
+struct FilterPatterns<'a> {
+    includes: Vec<&'a str>,
+    excludes: Vec<&'a str>,
+    sizes: Vec<(&'a str, SizeRange)>,
+}
+
+impl<'a> FilterPatterns<'a> {
+    fn parse(input: &'a str) -> Self {
+        let mut patterns = FilterPatterns {
+            includes: Vec::new(),
+            excludes: Vec::new(),
+            sizes: Vec::new(),
+        };
+        for pattern in input.split(',').map(str::trim) {
+            if pattern.is_empty() || pattern.starts_with('#') {
+                continue;
+            }
+            if pattern.starts_with("size:") {
+                patterns
+                    .sizes
+                    .extend(parse_size_filter(pattern).map(|range| (pattern, range)));
+            } else if let Some(exclude) = pattern.strip_prefix('!') {
+                patterns.excludes.push(exclude);
+            } else {
+                patterns.includes.push(pattern);
+            }
+        }
+        patterns
+    }
 }
 
 pub struct App {
@@ -36,6 +280,10 @@ pub struct App {
     pub filter_cursor: usize,
     pub filter_view_offset: usize,
     pub filter_field_width: usize,
+    pub content_filter_input: String,
+    pub content_filter_cursor: usize,
+    pub content_filter_view_offset: usize,
+    pub content_filter_field_width: usize,
     pub from_input: String,
     pub from_cursor: usize,
     pub from_view_offset: usize,
@@ -44,56 +292,108 @@ pub struct App {
     pub to_cursor: usize,
     pub to_view_offset: usize,
     pub to_field_width: usize,
+    pub options: SubstitutionOptions,
     pub focus: Focus,
+    pub layout: LayoutMode,
+    pub collapse_inputs: bool,
+    pub match_view: bool,
     pub diff_scroll: usize,
     pub confirm: ConfirmState,
+    pub confirm_scroll: usize,
+    pub deselected: HashSet<String>,
+    pub only_matching: bool,
+    pub conflicts_only: bool,
+    pub changed_only: bool,
+    pub show_match_lines: bool,
+    pub show_inline_preview: bool,
+    pub path_regex: bool,
+    pub group_by_dir: bool,
+    pub emphasize_basename: bool,
+    pub collapsed_dirs: HashSet<String>,
     pub is_loading: bool,
+    pub rescan_requested: bool,
     pub spinner: char,
+    pub poll_interval: Duration,
+    pub follow_symlinks: bool,
+    pub skip_applied: bool,
+    pub preview_limit: usize,
+    pub match_timeout: Duration,
+    thread_pool: Arc<rayon::ThreadPool>,
+    pub detect_sample_bytes: usize,
+    pub text_extensions: Vec<String>,
+    pub binary_extensions: Vec<String>,
+    pub max_files: usize,
+    pub load_everything: bool,
+    pub load_limit_hit: Option<usize>,
+    pub mouse_capture: bool,
+    pub tab_width: usize,
+    pub hex_preview: bool,
+    pub wrap_diff: bool,
+    pub scroll_to_first_change: bool,
+    pub show_file_info: bool,
+    pub full_diff: bool,
+    pub show_timing: bool,
+    pub load_duration: Option<Duration>,
+    scrolled_file: Option<String>,
+    root: PathBuf,
+    pub staged_files: Option<Vec<String>>,
+    pub report_path: Option<PathBuf>,
+    pub edits_path: Option<PathBuf>,
+    pub patches: Vec<FilePatch>,
+    pub read_only: bool,
+    pub from_history: History,
+    pub to_history: History,
+    pub history_search: Option<HistorySearch>,
+    pub config_path: Option<PathBuf>,
+    pub status_message: Option<String>,
+    pub last_report: Option<ApplyReport>,
+    pub pipe_command: Option<String>,
+    pub pipe_output: Option<String>,
+    pub pager_request: Option<String>,
+    pub from_error: Option<String>,
+    from_error_key: Option<(String, SubstitutionOptions)>,
+    pub pipe_scroll: usize,
+    pub presets: Vec<Preset>,
+    pub preset_picker: Option<usize>,
+    pub rules_view: Option<usize>,
+    pub export_prompt: Option<String>,
+    pub scratch: Option<String>,
+    pub compare_with: Option<String>,
+    pub results_view: Option<ResultsView>,
+    pub diff_theme: DiffTheme,
+    pub hunk_cursor: usize,
+    pub change_cursor: Option<usize>,
+    pub rejected_hunks: HashMap<String, HashSet<usize>>,
+    hunk_key: HunkKey,
+    pub saved_states: Vec<SavedState>,
+    undo_stack: Vec<Vec<UndoFile>>,
+    pub rules: Vec<Rule>,
+    pending_selection: Option<String>,
+    type_ahead: String,
+    type_ahead_at: Instant,
     file_cache: Arc<RwLock<FileCache>>,
-    filtered_files_cache: Arc<RwLock<Option<FilterCache>>>,
-    #[allow(dead_code)]
+    file_sizes: Arc<RwLock<HashMap<String, u64>>>,
+    scan_cache: Arc<RwLock<ScanCache>>,
+    last_filter: Option<FilterCache>,
     file_watcher: Option<notify::RecommendedWatcher>,
+    recent_writes: Arc<RecentWrites>,
     regex_cache: Arc<RwLock<HashMap<String, regex::Regex>>>,
+    match_summaries: Arc<RwLock<HashMap<String, MatchSummary>>>,
+    inline_previews: Arc<RwLock<HashMap<String, (String, String)>>>,
+    modified_files: Arc<RwLock<HashSet<String>>>,
+    scan_timed_out: Arc<AtomicBool>,
+    filter_duration: Arc<RwLock<Option<Duration>>>,
+    diff_cache: Option<DiffCache>,
 }
 
-impl Clone for App {
-    fn clone(&self) -> Self {
-        Self {
-            files: self.files.clone(),
-            selected: self.selected,
-            offset: self.offset,
-            filter_input: self.filter_input.clone(),
-            filter_cursor: self.filter_cursor,
-            filter_view_offset: self.filter_view_offset,
-            filter_field_width: self.filter_field_width,
-            from_input: self.from_input.clone(),
-            from_cursor: self.from_cursor,
-            from_view_offset: self.from_view_offset,
-            from_field_width: self.from_field_width,
-            to_input: self.to_input.clone(),
-            to_cursor: self.to_cursor,
-            to_view_offset: self.to_view_offset,
-            to_field_width: self.to_field_width,
-            focus: self.focus,
-            diff_scroll: self.diff_scroll,
-            confirm: self.confirm.clone(),
-            is_loading: self.is_loading,
-            spinner: self.spinner,
-            file_cache: self.file_cache.clone(),
-            filtered_files_cache: self.filtered_files_cache.clone(),
-            file_watcher: None,
-            regex_cache: self.regex_cache.clone(),
-        }
-    }
-}
-
-impl Default for App {
-    fn default() -> Self {
-        Self::new()
-    }
+struct InputField<'a> {
+    text: &'a mut String,
+    cursor: &'a mut usize,
+    view_offset: &'a mut usize,
+    width: usize,
 }
 
-impl App {
+impl InputField<'_> {
     fn update_view_offset_for_cursor(
         cursor: usize,
         view_offset: &mut usize,
@@ -130,15 +430,260 @@ impl App {
         }
     }
 
-    pub fn update_field_widths(&mut self, filter_width: usize, from_width: usize, to_width: usize) {
+    fn char_count(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    fn update_view_offset(&mut self) {
+        let text_len = self.char_count();
+        Self::update_view_offset_for_cursor(*self.cursor, self.view_offset, text_len, self.width);
+    }
+
+    fn insert(&mut self, c: char) {
+        let byte_pos = self
+            .text
+            .char_indices()
+            .nth(*self.cursor)
+            .map(|(byte_pos, _)| byte_pos)
+            .unwrap_or(self.text.len());
+        self.text.insert(byte_pos, c);
+        *self.cursor += 1;
+        self.update_view_offset();
+    }
+
+    fn backspace(&mut self) {
+        if *self.cursor == 0 {
+            return;
+        }
+        if let Some((byte_pos, _)) = self.text.char_indices().nth(*self.cursor - 1) {
+            self.text.remove(byte_pos);
+        }
+        *self.cursor -= 1;
+        self.update_view_offset();
+    }
+
+    fn move_left(&mut self) {
+        if *self.cursor > 0 {
+            *self.cursor -= 1;
+            self.update_view_offset();
+        } else {
+            Self::scroll_view_left(self.view_offset, self.char_count(), self.width);
+        }
+    }
+
+    fn set(&mut self, value: &str) {
+        self.set_with_cursor(value, value.chars().count());
+    }
+
+    fn set_with_cursor(&mut self, value: &str, cursor: usize) {
+        *self.text = value.to_string();
+        *self.cursor = cursor.min(self.char_count());
+        *self.view_offset = 0;
+        self.update_view_offset();
+    }
+
+    fn move_right(&mut self) {
+        if *self.cursor < self.char_count() {
+            *self.cursor += 1;
+            self.update_view_offset();
+        } else {
+            Self::scroll_view_right(self.view_offset, self.char_count(), self.width);
+        }
+    }
+}
+
+impl Clone for App {
+    fn clone(&self) -> Self {
+        Self {
+            files: self.files.clone(),
+            selected: self.selected,
+            offset: self.offset,
+            filter_input: self.filter_input.clone(),
+            filter_cursor: self.filter_cursor,
+            filter_view_offset: self.filter_view_offset,
+            filter_field_width: self.filter_field_width,
+            content_filter_input: self.content_filter_input.clone(),
+            content_filter_cursor: self.content_filter_cursor,
+            content_filter_view_offset: self.content_filter_view_offset,
+            content_filter_field_width: self.content_filter_field_width,
+            from_input: self.from_input.clone(),
+            from_cursor: self.from_cursor,
+            from_view_offset: self.from_view_offset,
+            from_field_width: self.from_field_width,
+            to_input: self.to_input.clone(),
+            to_cursor: self.to_cursor,
+            to_view_offset: self.to_view_offset,
+            to_field_width: self.to_field_width,
+            options: self.options,
+            focus: self.focus,
+            layout: self.layout,
+            collapse_inputs: self.collapse_inputs,
+            match_view: self.match_view,
+            diff_scroll: self.diff_scroll,
+            confirm: self.confirm.clone(),
+            confirm_scroll: self.confirm_scroll,
+            deselected: self.deselected.clone(),
+            only_matching: self.only_matching,
+            conflicts_only: self.conflicts_only,
+            changed_only: self.changed_only,
+            show_match_lines: self.show_match_lines,
+            show_inline_preview: self.show_inline_preview,
+            path_regex: self.path_regex,
+            group_by_dir: self.group_by_dir,
+            emphasize_basename: self.emphasize_basename,
+            collapsed_dirs: self.collapsed_dirs.clone(),
+            is_loading: self.is_loading,
+            rescan_requested: self.rescan_requested,
+            spinner: self.spinner,
+            poll_interval: self.poll_interval,
+            follow_symlinks: self.follow_symlinks,
+            skip_applied: self.skip_applied,
+            preview_limit: self.preview_limit,
+            match_timeout: self.match_timeout,
+            thread_pool: self.thread_pool.clone(),
+            detect_sample_bytes: self.detect_sample_bytes,
+            text_extensions: self.text_extensions.clone(),
+            binary_extensions: self.binary_extensions.clone(),
+            max_files: self.max_files,
+            load_everything: self.load_everything,
+            load_limit_hit: self.load_limit_hit,
+            mouse_capture: self.mouse_capture,
+            tab_width: self.tab_width,
+            hex_preview: self.hex_preview,
+            wrap_diff: self.wrap_diff,
+            scroll_to_first_change: self.scroll_to_first_change,
+            show_file_info: self.show_file_info,
+            full_diff: self.full_diff,
+            show_timing: self.show_timing,
+            load_duration: self.load_duration,
+            scrolled_file: self.scrolled_file.clone(),
+            root: self.root.clone(),
+            staged_files: self.staged_files.clone(),
+            report_path: self.report_path.clone(),
+            edits_path: self.edits_path.clone(),
+            patches: self.patches.clone(),
+            read_only: self.read_only,
+            from_history: self.from_history.clone(),
+            to_history: self.to_history.clone(),
+            history_search: self.history_search.clone(),
+            config_path: self.config_path.clone(),
+            status_message: self.status_message.clone(),
+            last_report: self.last_report.clone(),
+            pipe_command: self.pipe_command.clone(),
+            pipe_output: self.pipe_output.clone(),
+            pager_request: self.pager_request.clone(),
+            from_error: self.from_error.clone(),
+            from_error_key: self.from_error_key.clone(),
+            pipe_scroll: self.pipe_scroll,
+            presets: self.presets.clone(),
+            preset_picker: self.preset_picker,
+            rules_view: self.rules_view,
+            export_prompt: self.export_prompt.clone(),
+            scratch: self.scratch.clone(),
+            compare_with: self.compare_with.clone(),
+            results_view: self.results_view.clone(),
+            diff_theme: self.diff_theme.clone(),
+            hunk_cursor: self.hunk_cursor,
+            change_cursor: self.change_cursor,
+            rejected_hunks: self.rejected_hunks.clone(),
+            hunk_key: self.hunk_key.clone(),
+            saved_states: self.saved_states.clone(),
+            undo_stack: self.undo_stack.clone(),
+            rules: self.rules.clone(),
+            pending_selection: self.pending_selection.clone(),
+            type_ahead: self.type_ahead.clone(),
+            type_ahead_at: self.type_ahead_at,
+            file_cache: self.file_cache.clone(),
+            file_sizes: self.file_sizes.clone(),
+            scan_cache: self.scan_cache.clone(),
+            last_filter: self.last_filter.clone(),
+            file_watcher: None,
+            recent_writes: self.recent_writes.clone(),
+            regex_cache: self.regex_cache.clone(),
+            match_summaries: self.match_summaries.clone(),
+            inline_previews: self.inline_previews.clone(),
+            modified_files: self.modified_files.clone(),
+            scan_timed_out: self.scan_timed_out.clone(),
+            filter_duration: self.filter_duration.clone(),
+            diff_cache: self.diff_cache.clone(),
+        }
+    }
+}
+
+impl Default for App {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl App {
+    pub fn update_field_widths(
+        &mut self,
+        filter_width: usize,
+        content_filter_width: usize,
+        from_width: usize,
+        to_width: usize,
+    ) {
         self.filter_field_width = filter_width;
+        self.content_filter_field_width = content_filter_width;
         self.from_field_width = from_width;
         self.to_field_width = to_width;
     }
 
     pub fn new() -> Self {
-        let config = find_and_load_config();
+        let (config_path, config) = find_and_load_config().unzip();
+        let mut app = Self::with_config(config);
+        app.config_path = config_path;
+        app
+    }
+
+    pub fn effective_config(&self) -> Config {
+        Config {
+            files: Some(FilesConfig {
+                glob_filter: Some(
+                    self.filter_input
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|pattern| !pattern.is_empty())
+                        .map(String::from)
+                        .collect(),
+                ),
+                detect_sample_bytes: Some(self.detect_sample_bytes),
+                max_files: Some(self.max_files),
+                text_extensions: Some(self.text_extensions.clone()),
+                binary_extensions: Some(self.binary_extensions.clone()),
+            }),
+            behavior: Some(BehaviorConfig {
+                poll_ms: Some(self.poll_interval.as_millis() as u64),
+                follow_symlinks: Some(self.follow_symlinks),
+                preview_kb: Some(self.preview_limit / 1024),
+                match_timeout_ms: Some(self.match_timeout.as_millis() as u64),
+                mouse_capture: Some(self.mouse_capture),
+                strip_trailing_whitespace: Some(self.options.strip_trailing_whitespace),
+                skip_applied: Some(self.skip_applied),
+            }),
+            display: Some(DisplayConfig {
+                tab_width: Some(self.tab_width),
+                hex_preview: Some(self.hex_preview),
+                group_by_dir: Some(self.group_by_dir),
+                emphasize_basename: Some(self.emphasize_basename),
+                wrap_diff: Some(self.wrap_diff),
+                scroll_to_first_change: Some(self.scroll_to_first_change),
+                file_info: Some(self.show_file_info),
+                full_diff: Some(self.full_diff),
+                timing: Some(self.show_timing),
+            }),
+            performance: Some(PerformanceConfig {
+                threads: Some(self.thread_count()),
+            }),
+            presets: (!self.presets.is_empty()).then(|| self.presets.clone()),
+            theme: Some(ThemeConfig {
+                diff: Some(self.diff_theme.to_config()),
+            }),
+        }
+    }
 
+    pub fn with_config(config: Option<Config>) -> Self {
         let filter_input = config
             .as_ref()
             .and_then(|c| c.files.as_ref())
@@ -146,34 +691,163 @@ impl App {
             .map(|patterns| patterns.join(","))
             .unwrap_or_default();
 
-        let file_cache = Arc::new(RwLock::new(HashMap::new()));
-        let filtered_files_cache = Arc::new(RwLock::new(None));
+        let detect_sample_bytes = config
+            .as_ref()
+            .and_then(|c| c.files.as_ref())
+            .and_then(|f| f.detect_sample_bytes)
+            .unwrap_or(DEFAULT_DETECT_SAMPLE_BYTES)
+            .max(1);
 
-        let file_cache_clone = file_cache.clone();
-        let filtered_files_cache_clone = filtered_files_cache.clone();
+        let extensions = |list: Option<&Vec<String>>| {
+            list.into_iter()
+                .flatten()
+                .map(|ext| ext.trim().trim_start_matches('.').to_lowercase())
+                .filter(|ext| !ext.is_empty())
+                .collect::<Vec<_>>()
+        };
+        let files_config = config.as_ref().and_then(|c| c.files.as_ref());
+        let text_extensions = extensions(files_config.and_then(|f| f.text_extensions.as_ref()));
+        let binary_extensions = extensions(files_config.and_then(|f| f.binary_extensions.as_ref()));
 
-        let mut watcher = notify::recommended_watcher(move |res: NotifyResult<NotifyEvent>| {
-            if let Ok(event) = res {
-                match event.kind {
-                    notify::EventKind::Create(_) | notify::EventKind::Modify(_) => {
-                        if let Some(path) = event.paths.first() {
-                            if let Some(path_str) = path.to_str() {
-                                let mut cache = file_cache_clone.write();
-                                cache.remove(path_str);
-                                let mut filtered_cache = filtered_files_cache_clone.write();
-                                *filtered_cache = None;
-                            }
-                        }
-                    }
-                    _ => {}
-                }
-            }
-        })
-        .ok();
+        let max_files = config
+            .as_ref()
+            .and_then(|c| c.files.as_ref())
+            .and_then(|f| f.max_files)
+            .unwrap_or(DEFAULT_MAX_FILES);
 
-        if let Some(w) = &mut watcher {
-            let _ = w.watch(Path::new("."), RecursiveMode::Recursive);
-        }
+        let poll_ms = config
+            .as_ref()
+            .and_then(|c| c.behavior.as_ref())
+            .and_then(|b| b.poll_ms)
+            .unwrap_or(DEFAULT_POLL_MS)
+            .clamp(MIN_POLL_MS, MAX_POLL_MS);
+
+        let follow_symlinks = config
+            .as_ref()
+            .and_then(|c| c.behavior.as_ref())
+            .and_then(|b| b.follow_symlinks)
+            .unwrap_or(false);
+
+        let mouse_capture = config
+            .as_ref()
+            .and_then(|c| c.behavior.as_ref())
+            .and_then(|b| b.mouse_capture)
+            .unwrap_or(true);
+
+        let strip_trailing_whitespace = config
+            .as_ref()
+            .and_then(|c| c.behavior.as_ref())
+            .and_then(|b| b.strip_trailing_whitespace)
+            .unwrap_or(false);
+
+        let skip_applied = config
+            .as_ref()
+            .and_then(|c| c.behavior.as_ref())
+            .and_then(|b| b.skip_applied)
+            .unwrap_or(false);
+
+        let preview_kb = config
+            .as_ref()
+            .and_then(|c| c.behavior.as_ref())
+            .and_then(|b| b.preview_kb)
+            .unwrap_or(DEFAULT_PREVIEW_KB)
+            .max(1);
+
+        let match_timeout_ms = config
+            .as_ref()
+            .and_then(|c| c.behavior.as_ref())
+            .and_then(|b| b.match_timeout_ms)
+            .unwrap_or(DEFAULT_MATCH_TIMEOUT_MS);
+
+        let threads = config
+            .as_ref()
+            .and_then(|c| c.performance.as_ref())
+            .and_then(|p| p.threads)
+            .filter(|&threads| threads > 0)
+            .unwrap_or_else(default_threads);
+        let thread_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .expect("failed to build the scan thread pool");
+
+        let tab_width = config
+            .as_ref()
+            .and_then(|c| c.display.as_ref())
+            .and_then(|d| d.tab_width)
+            .unwrap_or(DEFAULT_TAB_WIDTH)
+            .max(1);
+
+        let hex_preview = config
+            .as_ref()
+            .and_then(|c| c.display.as_ref())
+            .and_then(|d| d.hex_preview)
+            .unwrap_or(false);
+
+        let group_by_dir = config
+            .as_ref()
+            .and_then(|c| c.display.as_ref())
+            .and_then(|d| d.group_by_dir)
+            .unwrap_or(false);
+
+        let emphasize_basename = config
+            .as_ref()
+            .and_then(|c| c.display.as_ref())
+            .and_then(|d| d.emphasize_basename)
+            .unwrap_or(false);
+
+        let wrap_diff = config
+            .as_ref()
+            .and_then(|c| c.display.as_ref())
+            .and_then(|d| d.wrap_diff)
+            .unwrap_or(false);
+
+        let scroll_to_first_change = config
+            .as_ref()
+            .and_then(|c| c.display.as_ref())
+            .and_then(|d| d.scroll_to_first_change)
+            .unwrap_or(false);
+
+        let show_file_info = config
+            .as_ref()
+            .and_then(|c| c.display.as_ref())
+            .and_then(|d| d.file_info)
+            .unwrap_or(false);
+
+        let full_diff = config
+            .as_ref()
+            .and_then(|c| c.display.as_ref())
+            .and_then(|d| d.full_diff)
+            .unwrap_or(false);
+
+        let show_timing = config
+            .as_ref()
+            .and_then(|c| c.display.as_ref())
+            .and_then(|d| d.timing)
+            .unwrap_or(false);
+
+        let presets = config
+            .as_ref()
+            .and_then(|c| c.presets.clone())
+            .unwrap_or_default();
+
+        let diff_theme = config
+            .as_ref()
+            .and_then(|c| c.theme.as_ref())
+            .and_then(|t| t.diff.as_ref())
+            .map(DiffTheme::from_config)
+            .unwrap_or_default();
+
+        let file_cache = Arc::new(RwLock::new(FileCache::default()));
+        let file_sizes = Arc::new(RwLock::new(HashMap::new()));
+        let scan_cache = Arc::new(RwLock::new(ScanCache::default()));
+
+        let recent_writes = Arc::new(RecentWrites::default());
+        let watcher = Self::create_watcher(
+            Path::new("."),
+            file_cache.clone(),
+            scan_cache.clone(),
+            recent_writes.clone(),
+        );
 
         let spinner = '|';
 
@@ -185,6 +859,10 @@ impl App {
             filter_cursor: 0,
             filter_view_offset: 0,
             filter_field_width: 40,
+            content_filter_input: String::new(),
+            content_filter_cursor: 0,
+            content_filter_view_offset: 0,
+            content_filter_field_width: 40,
             from_input: String::new(),
             from_cursor: 0,
             from_view_offset: 0,
@@ -193,178 +871,2348 @@ impl App {
             to_cursor: 0,
             to_view_offset: 0,
             to_field_width: 40,
+            options: SubstitutionOptions {
+                strip_trailing_whitespace,
+                ..SubstitutionOptions::default()
+            },
             focus: Focus::FileList,
+            layout: LayoutMode::Normal,
+            collapse_inputs: false,
+            match_view: false,
             diff_scroll: 0,
             confirm: ConfirmState::None,
+            confirm_scroll: 0,
+            deselected: HashSet::new(),
+            only_matching: true,
+            conflicts_only: false,
+            changed_only: false,
+            show_match_lines: false,
+            show_inline_preview: false,
+            path_regex: false,
+            group_by_dir,
+            emphasize_basename,
+            collapsed_dirs: HashSet::new(),
             is_loading: true,
+            rescan_requested: false,
             spinner,
+            poll_interval: Duration::from_millis(poll_ms),
+            follow_symlinks,
+            skip_applied,
+            preview_limit: preview_kb * 1024,
+            match_timeout: Duration::from_millis(match_timeout_ms),
+            thread_pool: Arc::new(thread_pool),
+            detect_sample_bytes,
+            text_extensions,
+            binary_extensions,
+            max_files,
+            load_everything: false,
+            load_limit_hit: None,
+            mouse_capture,
+            tab_width,
+            hex_preview,
+            wrap_diff,
+            scroll_to_first_change,
+            show_file_info,
+            full_diff,
+            show_timing,
+            load_duration: None,
+            scrolled_file: None,
+            root: PathBuf::from("."),
+            staged_files: None,
+            report_path: None,
+            edits_path: None,
+            patches: Vec::new(),
+            read_only: false,
+            from_history: History::default(),
+            to_history: History::default(),
+            history_search: None,
+            config_path: None,
+            status_message: None,
+            last_report: None,
+            pipe_command: None,
+            pipe_output: None,
+            pager_request: None,
+            from_error: None,
+            from_error_key: None,
+            pipe_scroll: 0,
+            presets,
+            preset_picker: None,
+            rules_view: None,
+            export_prompt: None,
+            scratch: None,
+            compare_with: None,
+            results_view: None,
+            diff_theme,
+            hunk_cursor: 0,
+            change_cursor: None,
+            rejected_hunks: HashMap::new(),
+            hunk_key: HunkKey::default(),
+            saved_states: Vec::new(),
+            undo_stack: Vec::new(),
+            rules: Vec::new(),
+            pending_selection: None,
+            type_ahead: String::new(),
+            type_ahead_at: Instant::now(),
             file_cache,
-            filtered_files_cache,
+            file_sizes,
+            scan_cache,
+            last_filter: None,
             file_watcher: watcher,
+            recent_writes,
             regex_cache: Arc::new(RwLock::new(HashMap::new())),
+            match_summaries: Arc::new(RwLock::new(HashMap::new())),
+            inline_previews: Arc::new(RwLock::new(HashMap::new())),
+            modified_files: Arc::new(RwLock::new(HashSet::new())),
+            scan_timed_out: Arc::new(AtomicBool::new(false)),
+            filter_duration: Arc::new(RwLock::new(None)),
+            diff_cache: None,
         }
     }
 
-    pub async fn load_files(&mut self) {
-        self.files = walkdir::WalkDir::new(".")
-            .into_iter()
-            .par_bridge()
-            .filter_map(|e| e.ok())
-            .filter(|e| e.file_type().is_file())
-            .filter(|e| is_text_file(e.path()))
-            .map(|e| e.path().display().to_string())
-            .collect();
-        self.is_loading = false;
-        {
-            let mut cache = self.filtered_files_cache.write();
-            *cache = None;
-        }
-    }
-
-    pub fn filter_files(&self) -> Vec<String> {
-        use globset::{Glob, GlobSetBuilder};
+    fn create_watcher(
+        root: &Path,
+        file_cache: Arc<RwLock<FileCache>>,
+        scan_cache: Arc<RwLock<ScanCache>>,
+        recent_writes: Arc<RecentWrites>,
+    ) -> Option<notify::RecommendedWatcher> {
+        let mut watcher = notify::recommended_watcher(move |res: NotifyResult<NotifyEvent>| {
+            if let Ok(event) = res {
+                Self::invalidate_for_event(&event, &file_cache, &scan_cache, &recent_writes);
+            }
+        })
+        .ok();
 
-        if self.filter_input.trim().is_empty() && self.from_input.trim().is_empty() {
-            return self.files.clone();
+        if let Some(w) = &mut watcher {
+            let _ = w.watch(root, RecursiveMode::Recursive);
         }
 
-        {
-            let cache = self.filtered_files_cache.read();
-            if let Some((cached_filter, cached_from, cached_files)) = &*cache {
-                if *cached_filter == self.filter_input && *cached_from == self.from_input {
-                    return cached_files.clone();
+        watcher
+    }
+
+    fn invalidate_for_event(
+        event: &NotifyEvent,
+        file_cache: &RwLock<FileCache>,
+        scan_cache: &RwLock<ScanCache>,
+        recent_writes: &RecentWrites,
+    ) {
+        match event.kind {
+            notify::EventKind::Create(_) | notify::EventKind::Modify(_) => {
+                if let Some(path) = event.paths.first() {
+                    if recent_writes.contains(path) {
+                        return;
+                    }
+                    let mut cache = file_cache.write();
+                    for key in Self::cache_keys_for(path) {
+                        cache.remove(&key);
+                    }
+                    *scan_cache.write() = ScanCache::default();
                 }
             }
+            _ => {}
         }
+    }
 
-        let patterns: Vec<_> = self
-            .filter_input
-            .split(',')
-            .map(str::trim)
-            .filter(|p| !p.is_empty())
+    pub fn handle_watch_event(&self, event: &NotifyEvent) {
+        Self::invalidate_for_event(
+            event,
+            &self.file_cache,
+            &self.scan_cache,
+            &self.recent_writes,
+        );
+    }
+
+    fn cache_keys_for(path: &Path) -> Vec<String> {
+        let normalized: PathBuf = path.components().collect();
+        let mut keys = vec![normalized.display().to_string()];
+        if let Some(relative) = std::env::current_dir()
+            .ok()
+            .and_then(|cwd| normalized.strip_prefix(cwd).ok().map(Path::to_path_buf))
+        {
+            keys.push(Path::new(".").join(relative).display().to_string());
+        }
+        keys
+    }
+
+    pub fn recent_writes(&self) -> &RecentWrites {
+        &self.recent_writes
+    }
+
+    pub fn is_watching(&self) -> bool {
+        self.file_watcher.is_some()
+    }
+
+    pub fn toggle_watcher(&mut self) {
+        if self.file_watcher.take().is_none() {
+            self.file_watcher = Self::create_watcher(
+                &self.root,
+                self.file_cache.clone(),
+                self.scan_cache.clone(),
+                self.recent_writes.clone(),
+            );
+        }
+    }
+
+    pub fn set_root(&mut self, root: impl Into<PathBuf>) {
+        self.root = root.into();
+        if self.is_watching() {
+            self.file_watcher = None;
+            self.toggle_watcher();
+        }
+    }
+
+    pub fn set_loaded_files(&mut self, files: Vec<String>) {
+        self.files = files;
+        self.is_loading = false;
+        self.file_cache.write().clear();
+        *self.scan_cache.write() = ScanCache::default();
+        self.diff_cache = None;
+
+        if let Some(path) = self.pending_selection.take() {
+            let filtered_files = self.filter_files();
+            self.selected = self
+                .row_position(&filtered_files, |f| f == path)
+                .unwrap_or_else(|| {
+                    self.selected
+                        .min(self.list_len(&filtered_files).saturating_sub(1))
+                });
+        }
+    }
+
+    pub fn clamp_selection(&mut self, filtered_files: &[String]) {
+        let last = self.list_len(filtered_files).saturating_sub(1);
+        self.selected = self.selected.min(last);
+        self.offset = self.offset.min(self.selected);
+    }
+
+    pub fn follow_selection(&mut self, filtered_files: &[String]) {
+        let selected = self.selected_file(filtered_files);
+        if selected == self.scrolled_file {
+            return;
+        }
+        self.scrolled_file = selected.clone();
+        if let Some(path) = selected.filter(|_| self.scroll_to_first_change) {
+            self.diff_scroll = self.file_changes(&path).first().copied().unwrap_or(0);
+            self.change_cursor = None;
+        }
+    }
+
+    pub fn request_rescan(&mut self, filtered_files: &[String]) {
+        if self.is_loading {
+            return;
+        }
+        self.pending_selection = self.selected_file(filtered_files);
+        self.rescan_requested = true;
+        self.is_loading = true;
+    }
+
+    pub fn thread_count(&self) -> usize {
+        self.thread_pool.current_num_threads()
+    }
+
+    pub async fn load_files(&mut self) {
+        let started = Instant::now();
+        let pool = self.thread_pool.clone();
+        let (files, limit_hit) = pool.install(|| self.walk_files());
+        self.load_limit_hit = limit_hit;
+        *self.file_sizes.write() = pool.install(|| {
+            files
+                .par_iter()
+                .filter_map(|f| fs::metadata(f).ok().map(|m| (f.clone(), m.len())))
+                .collect()
+        });
+        self.set_loaded_files(files);
+        self.load_duration = Some(started.elapsed());
+    }
+
+    fn walk_files(&self) -> (Vec<String>, Option<usize>) {
+        if let Some(staged) = &self.staged_files {
+            let files = staged
+                .par_iter()
+                .filter(|f| self.is_text(Path::new(f)))
+                .cloned()
+                .collect();
+            return (files, None);
+        }
+
+        let limit = (self.max_files > 0 && !self.load_everything).then_some(self.max_files);
+        let mut walked = 0;
+        let mut files: Vec<String> = walkdir::WalkDir::new(&self.root)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .take(limit.map_or(usize::MAX, |limit| limit + 1))
+            .inspect(|_| walked += 1)
+            .par_bridge()
+            .filter(|e| self.hex_preview || self.is_text(e.path()))
+            .map(|e| e.path().display().to_string())
             .collect();
 
-        let mut include_builder = GlobSetBuilder::new();
-        let mut exclude_builder = GlobSetBuilder::new();
-        let mut has_include = false;
+        match limit {
+            Some(limit) if walked > limit => {
+                files.truncate(limit);
+                (files, Some(limit))
+            }
+            _ => (files, None),
+        }
+    }
+
+    pub fn filter_files(&self) -> Vec<String> {
+        if self.filter_input.trim().is_empty()
+            && self.from_input.trim().is_empty()
+            && self.content_filter_input.trim().is_empty()
+            && self.rules.is_empty()
+            && !self.conflicts_only
+        {
+            self.scan_timed_out.store(false, Ordering::Relaxed);
+            return self.files.clone();
+        }
 
-        for pat in &patterns {
-            if let Some(stripped) = pat.strip_prefix('!') {
-                if let Ok(glob) = Glob::new(stripped) {
-                    exclude_builder.add(glob);
-                }
-            } else {
-                has_include = true;
-                if let Ok(glob) = Glob::new(pat) {
-                    include_builder.add(glob);
+        let key = self.filter_key();
+        {
+            let cache = self.scan_cache.read();
+            if let Some((cached_key, cached_files)) = &cache.filtered {
+                if *cached_key == key {
+                    self.scan_timed_out.store(false, Ordering::Relaxed);
+                    return cached_files.clone();
                 }
             }
         }
 
-        let include_set = include_builder.build().ok();
-        let exclude_set = exclude_builder.build().ok();
+        let started = Instant::now();
+        let patterns = FilterPatterns::parse(&self.filter_input);
+        let size_ranges: Vec<SizeRange> = patterns.sizes.iter().map(|(_, range)| *range).collect();
+        let has_include = !patterns.includes.is_empty();
 
-        let from_re = if !self.from_input.is_empty() {
-            {
-                let cache = self.regex_cache.read();
-                cache.get(&self.from_input).cloned()
+        let include_set = PathSet::build(&patterns.includes, self.path_regex);
+        let exclude_set = PathSet::build(&patterns.excludes, self.path_regex);
+
+        let substitution_res = if self.only_matching {
+            self.substitution_regexes()
+        } else {
+            Vec::new()
+        };
+        let content_filter_res = self
+            .content_filter_patterns()
+            .into_iter()
+            .filter_map(|pattern| self.compiled_regex(pattern))
+            .collect::<Vec<_>>();
+        let conflict_re = if self.conflicts_only {
+            self.compiled_regex(CONFLICT_MARKER_PATTERN)
+        } else {
+            None
+        };
+
+        let applied_re = if self.skip_applied && !self.from_input.is_empty() {
+            self.compiled_from_regex()
+        } else {
+            None
+        };
+
+        let summary_re = if self.show_match_lines && !self.from_input.is_empty() {
+            self.compiled_from_regex()
+        } else {
+            None
+        };
+        let summaries = RwLock::new(HashMap::new());
+        let previews_changes = self.show_inline_preview && self.has_substitution();
+        let previews = RwLock::new(HashMap::new());
+
+        let scans_content = !substitution_res.is_empty()
+            || !content_filter_res.is_empty()
+            || conflict_re.is_some()
+            || applied_re.is_some();
+        let deadline = Instant::now() + self.match_timeout;
+        let timed_out = AtomicBool::new(false);
+
+        let filtered_files: Vec<String> = self.thread_pool.install(|| {
+            self.files
+                .par_iter()
+                .filter(|f| {
+                    let included = if has_include {
+                        include_set
+                            .as_ref()
+                            .map(|set| set.is_match(f))
+                            .unwrap_or(false)
+                    } else {
+                        true
+                    };
+
+                    let excluded = exclude_set
+                        .as_ref()
+                        .map(|set| set.is_match(f))
+                        .unwrap_or(false);
+
+                    if !included || excluded {
+                        return false;
+                    }
+                    if !size_ranges.is_empty() {
+                        let size = self.file_size(f);
+                        if !size_ranges.iter().all(|range| range.contains(size)) {
+                            return false;
+                        }
+                    }
+                    if scans_content && Instant::now() > deadline {
+                        timed_out.store(true, Ordering::Relaxed);
+                        return false;
+                    }
+
+                    let matches_from = substitution_res.is_empty()
+                        || self.substitution_matches(f, &substitution_res);
+
+                    let matches_content_filter = content_filter_res.is_empty()
+                        || content_filter_res
+                            .iter()
+                            .any(|re| self.content_matches(f, re));
+                    let has_conflicts = conflict_re
+                        .as_ref()
+                        .is_none_or(|re| self.content_matches(f, re));
+
+                    let applied = applied_re.as_ref().is_some_and(|re| {
+                        self.cached_content(f).is_some_and(|content| {
+                            already_applied(&content, re, &self.replacement_for(f), &self.options)
+                        })
+                    });
+
+                    let keep = matches_from && matches_content_filter && has_conflicts && !applied;
+
+                    if keep {
+                        if let Some(re) = &summary_re {
+                            if let Some(summary) =
+                                self.cached_content(f).and_then(|c| match_summary(&c, re))
+                            {
+                                summaries.write().insert(f.to_string(), summary);
+                            }
+                        }
+                        if previews_changes {
+                            if let Some(change) = self
+                                .cached_content(f)
+                                .and_then(|c| first_changed_line(&c, &self.substitute(&c, f)))
+                            {
+                                previews.write().insert(f.to_string(), change);
+                            }
+                        }
+                    }
+
+                    keep
+                })
+                .cloned()
+                .collect()
+        });
+
+        let timed_out = timed_out.into_inner();
+        if !timed_out {
+            let mut cache = self.scan_cache.write();
+            cache.filtered = Some((key, filtered_files.clone()));
+        }
+        *self.match_summaries.write() = summaries.into_inner();
+        *self.inline_previews.write() = previews.into_inner();
+        self.scan_timed_out.store(timed_out, Ordering::Relaxed);
+        *self.filter_duration.write() = Some(started.elapsed());
+
+        filtered_files
+    }
+
+    pub fn content_filter_patterns(&self) -> Vec<&str> {
+        self.content_filter_input
+            .split("||")
+            .map(str::trim)
+            .filter(|pattern| !pattern.is_empty())
+            .collect()
+    }
+
+    fn file_size(&self, path: &str) -> u64 {
+        let cached = self.file_sizes.read().get(path).copied();
+        cached.unwrap_or_else(|| fs::metadata(path).map(|m| m.len()).unwrap_or(0))
+    }
+
+    pub fn size_filters(&self) -> Vec<&str> {
+        FilterPatterns::parse(&self.filter_input)
+            .sizes
+            .into_iter()
+            .map(|(pattern, _)| pattern)
+            .collect()
+    }
+
+    pub fn filter_hint(&self, filtered_files: &[String]) -> Option<String> {
+        if self.is_loading || !filtered_files.is_empty() || self.files.is_empty() {
+            return None;
+        }
+        let patterns = FilterPatterns::parse(&self.filter_input);
+        let matches_all = |pattern: &str| {
+            PathSet::build(&[pattern], self.path_regex)
+                .is_some_and(|set| self.files.iter().all(|f| set.is_match(f)))
+        };
+
+        if let Some(exclude) = patterns.excludes.iter().find(|p| matches_all(p)) {
+            return Some(format!("Exclude pattern !{} matches every file", exclude));
+        }
+        if let Some((pattern, _)) = patterns
+            .sizes
+            .iter()
+            .find(|(_, range)| !self.files.iter().any(|f| range.contains(self.file_size(f))))
+        {
+            return Some(format!("Size filter {} matches no files", pattern));
+        }
+
+        let included = match PathSet::build(&patterns.includes, self.path_regex) {
+            Some(set) if !patterns.includes.is_empty() => {
+                self.files.iter().filter(|f| set.is_match(f)).collect()
+            }
+            _ => self.files.iter().collect::<Vec<_>>(),
+        };
+        if included.is_empty() {
+            return Some(format!(
+                "Include patterns {} match no files",
+                patterns.includes.join(",")
+            ));
+        }
+        let exclude_set = PathSet::build(&patterns.excludes, self.path_regex);
+        if !patterns.excludes.is_empty()
+            && exclude_set.is_some_and(|set| included.iter().all(|f| set.is_match(f)))
+        {
+            return Some(
+                "Exclude patterns remove every file the include patterns match".to_string(),
+            );
+        }
+        None
+    }
+
+    pub fn scan_timed_out(&self) -> bool {
+        self.scan_timed_out.load(Ordering::Relaxed)
+    }
+
+    pub fn filter_duration(&self) -> Option<Duration> {
+        *self.filter_duration.read()
+    }
+
+    pub fn timing_status(&self) -> Option<String> {
+        if !self.show_timing {
+            return None;
+        }
+        let load = self.load_duration?;
+        Some(match self.filter_duration() {
+            Some(filter) => format!(
+                "Loaded in {}, filtered in {}",
+                format_duration(load),
+                format_duration(filter)
+            ),
+            None => format!("Loaded in {}", format_duration(load)),
+        })
+    }
+
+    fn compiled_regex(&self, pattern: &str) -> Option<regex::Regex> {
+        let cached = {
+            let cache = self.regex_cache.read();
+            cache.get(pattern).cloned()
+        };
+
+        cached.or_else(|| {
+            regex::Regex::new(pattern).ok().inspect(|re| {
+                self.regex_cache
+                    .write()
+                    .insert(pattern.to_string(), re.clone());
+            })
+        })
+    }
+
+    pub fn from_regex(&self) -> Option<regex::Regex> {
+        if self.from_input.is_empty() {
+            return None;
+        }
+        self.compiled_from_regex()
+    }
+
+    fn compiled_from_regex(&self) -> Option<regex::Regex> {
+        self.compiled_regex(&effective_pattern(&self.from_input, &self.options))
+    }
+
+    pub fn update_from_error(&mut self) {
+        if matches!(&self.from_error_key, Some((from, options))
+            if *from == self.from_input && *options == self.options)
+        {
+            return;
+        }
+        self.from_error_key = Some((self.from_input.clone(), self.options));
+        self.from_error = if self.from_input.is_empty() || self.compiled_from_regex().is_some() {
+            None
+        } else {
+            regex::Regex::new(&effective_pattern(&self.from_input, &self.options))
+                .err()
+                .map(|err| regex_error_message(&err))
+        };
+    }
+
+    pub fn from_group_count(&self) -> Option<usize> {
+        if self.from_input.is_empty() {
+            return None;
+        }
+        self.compiled_from_regex().map(|re| re.captures_len() - 1)
+    }
+
+    fn substitution_regexes(&self) -> Vec<(regex::Regex, Option<&Rule>)> {
+        let from_re = if self.from_input.is_empty() {
+            None
+        } else {
+            self.compiled_from_regex()
+        };
+        from_re
+            .map(|re| (re, None))
+            .into_iter()
+            .chain(self.rules.iter().filter_map(|rule| {
+                self.compiled_regex(&rule.pattern())
+                    .map(|re| (re, Some(rule)))
+            }))
+            .collect()
+    }
+
+    fn substitution_matches(&self, path: &str, regexes: &[(regex::Regex, Option<&Rule>)]) -> bool {
+        regexes.iter().any(|(re, rule)| match rule {
+            Some(rule) => rule.applies_to(path) && self.content_matches(path, re),
+            None => self.substitution_content_matches(path, re),
+        })
+    }
+
+    fn has_substitution(&self) -> bool {
+        !self.from_input.is_empty() || !self.rules.is_empty() || !self.patches.is_empty()
+    }
+
+    pub fn note_filter_change(&mut self, filtered_files: &[String]) {
+        if self.is_loading {
+            return;
+        }
+        let key = self.filter_key();
+        if let Some((last_key, last_files)) = &self.last_filter {
+            if last_files.as_slice() == filtered_files {
+                return;
+            }
+            if *last_key == key {
+                self.last_filter = Some((key, filtered_files.to_vec()));
+                return;
+            }
+            let (removed, added) = file_set_delta(last_files, filtered_files);
+            if self.status_message.is_none() && (removed > 0 || added > 0) {
+                self.status_message = Some(format!("-{} +{} files", removed, added));
+            }
+        }
+        self.last_filter = Some((key, filtered_files.to_vec()));
+    }
+
+    fn filter_key(&self) -> FilterKey {
+        FilterKey {
+            filter: self.filter_input.clone(),
+            from: self.from_input.clone(),
+            content_filter: self.content_filter_input.clone(),
+            options: self.options,
+            only_matching: self.only_matching,
+            conflicts_only: self.conflicts_only,
+            show_match_lines: self.show_match_lines,
+            inline_to: self.show_inline_preview.then(|| self.to_input.clone()),
+            applied_to: self.skip_applied.then(|| self.to_input.clone()),
+            path_regex: self.path_regex,
+            rules: self.rules.clone(),
+        }
+    }
+
+    pub fn match_summary(&self, path: &str) -> Option<MatchSummary> {
+        self.match_summaries.read().get(path).copied()
+    }
+
+    pub fn inline_preview(&self, path: &str) -> Option<(String, String)> {
+        self.inline_previews.read().get(path).cloned()
+    }
+
+    pub fn is_modified(&self, path: &str) -> bool {
+        self.modified_files.read().contains(path)
+    }
+
+    pub fn modified_count(&self) -> usize {
+        self.modified_files.read().len()
+    }
+
+    pub fn cached_file_content(&self, path: &str) -> Option<String> {
+        let cache = self.file_cache.read();
+        cache.contents.get(path).cloned()
+    }
+
+    fn cached_content(&self, path: &str) -> Option<String> {
+        self.cached_file_content(path).or_else(|| {
+            std::fs::read_to_string(path).ok().inspect(|content| {
+                let mut cache = self.file_cache.write();
+                cache.contents.insert(path.to_string(), content.clone());
+            })
+        })
+    }
+
+    pub fn is_text(&self, path: &Path) -> bool {
+        let extension = path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase());
+        if let Some(extension) = extension {
+            if self.text_extensions.contains(&extension) {
+                return true;
+            }
+            if self.binary_extensions.contains(&extension) {
+                return false;
+            }
+        }
+        is_text_file(path, self.detect_sample_bytes)
+    }
+
+    fn is_hex_only(&self, path: &str) -> bool {
+        if !self.hex_preview {
+            return false;
+        }
+        if let Some(text) = self.file_cache.read().text.get(path) {
+            return !text;
+        }
+        let text = self.is_text(Path::new(path));
+        self.file_cache.write().text.insert(path.to_string(), text);
+        !text
+    }
+
+    pub fn preview(&self, path: &str) -> Option<Preview> {
+        if self.is_hex_only(path) {
+            read_hex_preview(path, self.preview_limit)
+        } else {
+            read_preview(path, self.preview_limit)
+        }
+    }
+
+    pub fn file_info(&self, path: &str) -> Option<FileInfo> {
+        use std::io::Read;
+
+        if let Some(info) = self.file_cache.read().infos.get(path) {
+            return Some(*info);
+        }
+        let info = match self.cached_file_content(path) {
+            Some(content) => {
+                let bytes = content.as_bytes();
+                detect_file_info(&bytes[..bytes.len().min(self.preview_limit)])
+            }
+            None => {
+                let mut bytes = Vec::new();
+                fs::File::open(path)
+                    .ok()?
+                    .take(self.preview_limit as u64)
+                    .read_to_end(&mut bytes)
+                    .ok()?;
+                detect_file_info(&bytes)
+            }
+        };
+        self.file_cache.write().infos.insert(path.to_string(), info);
+        Some(info)
+    }
+
+    fn content_matches(&self, path: &str, re: &regex::Regex) -> bool {
+        if self.is_hex_only(path) {
+            return false;
+        }
+        self.cached_content(path)
+            .map(|content| re.is_match(&content))
+            .unwrap_or(false)
+    }
+
+    fn substitution_content_matches(&self, path: &str, re: &regex::Regex) -> bool {
+        if self.is_hex_only(path) {
+            return false;
+        }
+        self.cached_content(path)
+            .is_some_and(|content| substitution_is_match(&content, re, &self.options))
+    }
+
+    pub fn from_matches_empty(&self) -> bool {
+        !self.from_input.is_empty() && self.compiled_from_regex().is_some_and(|re| re.is_match(""))
+    }
+
+    fn change_summary(&self, filtered_files: &[String]) -> ChangeSummary {
+        let key = ChangeKey {
+            files: filtered_files.to_vec(),
+            from: self.from_input.clone(),
+            to: self.to_input.clone(),
+            options: self.options,
+            rules: self.rules.clone(),
+        };
+        if let Some((cached_key, summary)) = &self.scan_cache.read().changes {
+            if *cached_key == key {
+                return *summary;
+            }
+        }
+
+        let regexes = self.substitution_regexes();
+        let any_matching = self.thread_pool.install(|| {
+            filtered_files
+                .par_iter()
+                .any(|f| self.substitution_matches(f, &regexes))
+        });
+        let deltas: Vec<isize> = self.thread_pool.install(|| {
+            filtered_files
+                .par_iter()
+                .filter(|f| !self.is_hex_only(f))
+                .filter_map(|f| {
+                    let content = self.cached_content(f)?;
+                    let replaced = self.substitute(&content, f);
+                    (replaced != content).then(|| byte_delta(&content, &replaced))
+                })
+                .collect()
+        });
+
+        let summary = ChangeSummary {
+            any_matching,
+            changed_files: deltas.len(),
+            byte_delta: deltas.iter().sum(),
+        };
+        self.scan_cache.write().changes = Some((key, summary));
+        summary
+    }
+
+    pub fn no_change_reason(&self, filtered_files: &[String]) -> Option<NoChangeReason> {
+        if self.is_loading || !self.has_substitution() || self.substitution_regexes().is_empty() {
+            return None;
+        }
+
+        let summary = self.change_summary(filtered_files);
+        if !summary.any_matching {
+            Some(NoChangeReason::NoMatchingFiles)
+        } else if summary.changed_files > 0 {
+            None
+        } else {
+            Some(NoChangeReason::NoEffectiveChange)
+        }
+    }
+
+    pub fn affected_files(&self, files: &[String]) -> Vec<String> {
+        let regexes = self.substitution_regexes();
+        if regexes.is_empty() {
+            return Vec::new();
+        }
+
+        self.thread_pool.install(|| {
+            files
+                .par_iter()
+                .filter(|f| self.substitution_matches(f, &regexes))
+                .cloned()
+                .collect()
+        })
+    }
+
+    pub fn batch_files(&self, filtered_files: &[String]) -> Vec<String> {
+        self.affected_files(filtered_files)
+            .into_iter()
+            .filter(|f| !self.deselected.contains(f))
+            .collect()
+    }
+
+    fn confirm_all(&mut self, filtered_files: &[String]) {
+        if self.focus != Focus::FileList
+            || self.block_read_only()
+            || self.block_empty_from()
+            || self.block_incomplete_scan()
+        {
+            return;
+        }
+        self.confirm = ConfirmState::ConfirmingAll(self.batch_files(filtered_files));
+        self.confirm_scroll = 0;
+    }
+
+    fn confirm_from_selected(&mut self, filtered_files: &[String]) {
+        if self.focus != Focus::FileList
+            || self.block_read_only()
+            || self.block_empty_from()
+            || self.block_incomplete_scan()
+        {
+            return;
+        }
+        let Some(file) = self.selected_file(filtered_files) else {
+            return;
+        };
+        let start = filtered_files.iter().position(|f| *f == file).unwrap_or(0);
+        self.confirm = ConfirmState::ConfirmingFrom(self.batch_files(&filtered_files[start..]));
+        self.confirm_scroll = 0;
+    }
+
+    fn history_for(&self, field: Focus) -> Option<&History> {
+        match field {
+            Focus::From => Some(&self.from_history),
+            Focus::To => Some(&self.to_history),
+            _ => None,
+        }
+    }
+
+    fn start_history_search(&mut self) {
+        if self.history_for(self.focus).is_some() {
+            self.history_search = Some(HistorySearch::default());
+        }
+    }
+
+    pub fn history_search_view(&self, field: Focus) -> Option<(String, String)> {
+        let search = self
+            .history_search
+            .as_ref()
+            .filter(|_| self.focus == field)?;
+        let history = self.history_for(field)?;
+        Some(match history.search(&search.query, search.skip) {
+            Some(entry) => (format!("search: {}", search.query), entry.to_string()),
+            None => {
+                let current = match field {
+                    Focus::From => &self.from_input,
+                    _ => &self.to_input,
+                };
+                (format!("failed search: {}", search.query), current.clone())
+            }
+        })
+    }
+
+    fn handle_history_search_key(&mut self, key: KeyEvent) {
+        let Some(mut search) = self.history_search.take() else {
+            return;
+        };
+        let Some(history) = self.history_for(self.focus).cloned() else {
+            return;
+        };
+
+        match key {
+            KeyEvent {
+                code: KeyCode::Char('r'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => search.next(&history),
+            KeyEvent {
+                code: KeyCode::Enter,
+                ..
+            } => {
+                if let Some(entry) = history.search(&search.query, search.skip) {
+                    let entry = entry.to_string();
+                    if let Some(mut field) = self.focused_field() {
+                        field.set(&entry);
+                    }
+                }
+                return;
+            }
+            KeyEvent {
+                code: KeyCode::Esc, ..
+            } => return,
+            KeyEvent {
+                code: KeyCode::Backspace,
+                ..
+            } => {
+                search.query.pop();
+                search.skip = 0;
+            }
+            KeyEvent {
+                code: KeyCode::Char(c),
+                modifiers: KeyModifiers::NONE | KeyModifiers::SHIFT,
+                ..
+            } => {
+                search.query.push(c);
+                search.skip = 0;
+            }
+            _ => {}
+        }
+        self.history_search = Some(search);
+    }
+
+    fn open_export_prompt(&mut self) {
+        if !self.block_read_only() {
+            self.export_prompt = Some("ised.config.toml".to_string());
+        }
+    }
+
+    fn block_read_only(&mut self) -> bool {
+        if self.read_only {
+            self.status_message = Some("Read-only mode: files are never written".to_string());
+            return true;
+        }
+        false
+    }
+
+    fn block_empty_from(&mut self) -> bool {
+        if !self.has_substitution() {
+            self.status_message =
+                Some("From is empty; enter a pattern before applying".to_string());
+            return true;
+        }
+        false
+    }
+
+    fn block_incomplete_scan(&mut self) -> bool {
+        if self.scan_timed_out() {
+            self.status_message = Some(
+                "Pattern timed out; the file list is incomplete, so batch apply is disabled"
+                    .to_string(),
+            );
+            return true;
+        }
+        false
+    }
+
+    fn confirm_apply(&mut self, force: bool) {
+        if self.block_read_only() {
+            self.confirm = ConfirmState::None;
+            return;
+        }
+        if self.from_matches_empty() && !force {
+            self.status_message =
+                Some("From matches the empty string; press Y to apply anyway".to_string());
+            return;
+        }
+        if !matches!(self.confirm, ConfirmState::None) {
+            self.from_history.push(&self.from_input);
+            self.to_history.push(&self.to_input);
+        }
+
+        if let Some(edits_path) = self.edits_path.clone() {
+            let paths = match std::mem::replace(&mut self.confirm, ConfirmState::None) {
+                ConfirmState::Confirming(path) => vec![path],
+                ConfirmState::ConfirmingAll(paths) | ConfirmState::ConfirmingFrom(paths) => paths,
+                ConfirmState::None => return,
+            };
+            self.status_message = Some(self.write_edits(&paths, &edits_path));
+            return;
+        }
+
+        match std::mem::replace(&mut self.confirm, ConfirmState::None) {
+            ConfirmState::Confirming(path) => {
+                self.status_message = Some(match self.apply_substitution(&path) {
+                    Ok(Some((lines, written))) => {
+                        self.undo_stack.push(vec![written]);
+                        match format_lines(&lines) {
+                            Some(lines) => format!("Changed {} at {}", path, lines),
+                            None => format!("Changed {}", path),
+                        }
+                    }
+                    Ok(None) => format!("No changes to {}", path),
+                    Err(err) => format!("Failed to apply to {}: {}", path, err),
+                });
+            }
+            ConfirmState::ConfirmingAll(paths) | ConfirmState::ConfirmingFrom(paths) => {
+                let (report, written) = self.apply_batch(&paths);
+                if !written.is_empty() {
+                    self.undo_stack.push(written);
+                }
+                let summary = match report.line_log() {
+                    Some(log) => format!("{} | {}", report.summary(), log),
+                    None => report.summary(),
+                };
+                self.status_message = Some(match &self.report_path {
+                    Some(report_path) => match report.write_json(report_path) {
+                        Ok(()) => {
+                            format!("{} (report written to {})", summary, report_path.display())
+                        }
+                        Err(err) => format!("{} (report failed: {})", summary, err),
+                    },
+                    None => summary,
+                });
+                self.last_report = Some(report);
+            }
+            ConfirmState::None => return,
+        }
+        self.rejected_hunks.clear();
+        self.hunk_cursor = 0;
+    }
+
+    fn current_hunk_key(&self) -> HunkKey {
+        (self.from_input.clone(), self.to_input.clone(), self.options)
+    }
+
+    pub fn rejected_hunks_for(&self, path: &str) -> Option<&HashSet<usize>> {
+        if self.hunk_key != self.current_hunk_key() {
+            return None;
+        }
+        self.rejected_hunks.get(path)
+    }
+
+    fn substituted_file(&self, path: &str) -> Option<(String, String)> {
+        if !self.has_substitution() || self.is_hex_only(path) {
+            return None;
+        }
+        self.cached_content(path).map(|content| {
+            let replaced = self.substitute(&content, path);
+            (content, replaced)
+        })
+    }
+
+    fn preview_changes(&self, path: &str) -> Option<Arc<PreviewChanges>> {
+        if !self.has_substitution() || self.is_hex_only(path) {
+            return None;
+        }
+        let key = PreviewKey {
+            path: path.to_string(),
+            from: self.from_input.clone(),
+            to: self.to_input.clone(),
+            options: self.options,
+            rules: self.rules.clone(),
+            full_diff: self.full_diff,
+        };
+        if let Some((cached_key, changes)) = &self.scan_cache.read().preview {
+            if *cached_key == key {
+                return Some(changes.clone());
+            }
+        }
+
+        let original = read_preview(path, self.preview_limit)?.content;
+        let replaced = self.substitute(&original, path);
+        let rows = self.collapsed_diff_rows(&original, &replaced);
+        let display_row = |row: usize| rows.as_ref().map_or(row, |rows| collapsed_row(rows, row));
+        let headers = self.diff_theme.hunk_headers;
+        let changes = Arc::new(PreviewChanges {
+            hunks: hunks(&original, &replaced, headers)
+                .into_iter()
+                .map(|hunk| Hunk {
+                    line: display_row(hunk.line),
+                    ..hunk
+                })
+                .collect(),
+            changes: change_lines(&original, &replaced, headers)
+                .into_iter()
+                .map(display_row)
+                .collect(),
+            trailing_whitespace: added_trailing_whitespace(&original, &replaced).len(),
+            match_count: self.from_regex().map(|re| re.find_iter(&original).count()),
+            original,
+            replaced,
+        });
+        self.scan_cache.write().preview = Some((key, changes.clone()));
+        Some(changes)
+    }
+
+    fn collapsed_diff_rows(&self, original: &str, replaced: &str) -> Option<Vec<DiffRow>> {
+        if self.full_diff {
+            return None;
+        }
+        let changed = changed_rows(original, replaced, self.diff_theme.hunk_headers);
+        changed
+            .contains(&true)
+            .then(|| collapse_rows(&changed, DIFF_CONTEXT_LINES))
+    }
+
+    fn display_row(&self, original: &str, replaced: &str, row: usize) -> usize {
+        self.collapsed_diff_rows(original, replaced)
+            .map_or(row, |rows| collapsed_row(&rows, row))
+    }
+
+    pub fn file_hunks(&self, path: &str) -> Vec<Hunk> {
+        self.preview_changes(path)
+            .map(|changes| changes.hunks.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn file_changes(&self, path: &str) -> Vec<usize> {
+        self.preview_changes(path)
+            .map(|changes| changes.changes.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn trailing_whitespace_status(&self, path: &str) -> Option<String> {
+        match self.preview_changes(path)?.trailing_whitespace {
+            0 => None,
+            1 => Some("1 line adds trailing whitespace".to_string()),
+            lines => Some(format!("{} lines add trailing whitespace", lines)),
+        }
+    }
+
+    pub fn byte_delta_status(&self, path: &str) -> Option<String> {
+        let changes = self.preview_changes(path)?;
+        (changes.original != changes.replaced)
+            .then(|| format_byte_delta(byte_delta(&changes.original, &changes.replaced)))
+    }
+
+    pub fn total_byte_delta(&self, filtered_files: &[String]) -> Option<String> {
+        if self.is_loading || !self.has_substitution() {
+            return None;
+        }
+        let summary = self.change_summary(filtered_files);
+        (summary.changed_files > 0).then(|| {
+            let unit = if summary.changed_files == 1 {
+                "file"
+            } else {
+                "files"
+            };
+            format!(
+                "Net {} across {} changed {}",
+                format_byte_delta(summary.byte_delta),
+                summary.changed_files,
+                unit
+            )
+        })
+    }
+
+    pub fn match_count_status(&self, path: &str) -> Option<String> {
+        Some(match self.preview_changes(path)?.match_count? {
+            1 => "1 match".to_string(),
+            count => format!("{} matches", count),
+        })
+    }
+
+    pub fn change_status(&self, path: &str) -> Option<String> {
+        let total = self.file_changes(path).len();
+        match self.change_cursor.filter(|&i| i < total) {
+            Some(current) => Some(format!("change {}/{}", current + 1, total)),
+            None if total > 0 => Some(format!("{} changes", total)),
+            None => None,
+        }
+    }
+
+    fn move_change(&mut self, filtered_files: &[String], forward: bool) {
+        let Some(path) = self.selected_file(filtered_files) else {
+            return;
+        };
+        let changes = self.file_changes(&path);
+        self.change_cursor = step_wrapping(self.change_cursor, changes.len(), forward);
+        if let Some(index) = self.change_cursor {
+            self.diff_scroll = changes[index];
+        }
+    }
+
+    pub fn hunk_status(&self, path: &str) -> Option<String> {
+        let total = self.file_hunks(path).len();
+        if total == 0 {
+            return None;
+        }
+        let current = self.hunk_cursor.min(total - 1) + 1;
+        Some(match self.rejected_hunks_for(path).map_or(0, |r| r.len()) {
+            0 => format!("hunk {}/{}", current, total),
+            skipped => format!("hunk {}/{}, {} skipped", current, total, skipped),
+        })
+    }
+
+    fn move_hunk(&mut self, filtered_files: &[String], forward: bool) {
+        let Some(path) = self.selected_file(filtered_files) else {
+            return;
+        };
+        let file_hunks = self.file_hunks(&path);
+        if file_hunks.is_empty() {
+            return;
+        }
+        let current = self.hunk_cursor.min(file_hunks.len() - 1);
+        self.hunk_cursor = if forward {
+            (current + 1).min(file_hunks.len() - 1)
+        } else {
+            current.saturating_sub(1)
+        };
+        self.diff_scroll = file_hunks[self.hunk_cursor].line;
+    }
+
+    fn toggle_hunk(&mut self, filtered_files: &[String]) {
+        let Some(path) = self.selected_file(filtered_files) else {
+            return;
+        };
+        let total = self.file_hunks(&path).len();
+        if total == 0 {
+            return;
+        }
+        let key = self.current_hunk_key();
+        if self.hunk_key != key {
+            self.rejected_hunks.clear();
+            self.hunk_key = key;
+        }
+        let hunk = self.hunk_cursor.min(total - 1);
+        let rejected = self.rejected_hunks.entry(path).or_default();
+        if !rejected.remove(&hunk) {
+            rejected.insert(hunk);
+        }
+    }
+
+    fn copy_report_paths(&mut self, changed_only: bool) {
+        let Some(report) = &self.last_report else {
+            self.status_message = Some("Nothing applied yet".to_string());
+            return;
+        };
+        let paths = if changed_only {
+            report.changed_paths()
+        } else {
+            report.paths()
+        };
+        let count = paths.len();
+        self.status_message = Some(match copy_to_clipboard(&paths.join("\n")) {
+            Ok(()) => format!("Copied {} paths to the clipboard", count),
+            Err(err) => format!("Failed to copy to the clipboard: {}", err),
+        });
+    }
+
+    pub fn list_rows(&self, filtered_files: &[String]) -> Vec<ListRow> {
+        if self.group_by_dir {
+            group_rows(filtered_files, &self.collapsed_dirs)
+        } else {
+            filtered_files.iter().cloned().map(ListRow::File).collect()
+        }
+    }
+
+    fn list_len(&self, filtered_files: &[String]) -> usize {
+        if self.group_by_dir {
+            self.list_rows(filtered_files).len()
+        } else {
+            filtered_files.len()
+        }
+    }
+
+    fn row_position(
+        &self,
+        filtered_files: &[String],
+        matches: impl Fn(&str) -> bool,
+    ) -> Option<usize> {
+        if self.group_by_dir {
+            self.list_rows(filtered_files)
+                .iter()
+                .position(|row| row.file().is_some_and(&matches))
+        } else {
+            filtered_files.iter().position(|f| matches(f))
+        }
+    }
+
+    pub fn selected_file(&self, filtered_files: &[String]) -> Option<String> {
+        if !self.group_by_dir {
+            return filtered_files.get(self.selected).cloned();
+        }
+        match self
+            .list_rows(filtered_files)
+            .into_iter()
+            .nth(self.selected)
+        {
+            Some(ListRow::File(path)) => Some(path),
+            _ => None,
+        }
+    }
+
+    fn selected_dir(&self, filtered_files: &[String]) -> Option<String> {
+        if !self.group_by_dir {
+            return None;
+        }
+        match self
+            .list_rows(filtered_files)
+            .into_iter()
+            .nth(self.selected)
+        {
+            Some(ListRow::Dir { path, .. }) => Some(path),
+            _ => None,
+        }
+    }
+
+    pub fn toggle_grouping(&mut self, filtered_files: &[String]) {
+        let current = self.selected_file(filtered_files);
+        self.group_by_dir = !self.group_by_dir;
+        self.selected = current
+            .and_then(|path| self.row_position(filtered_files, |f| f == path))
+            .unwrap_or(0);
+        self.offset = 0;
+    }
+
+    fn toggle_collapsed(&mut self, dir: String) {
+        if !self.collapsed_dirs.remove(&dir) {
+            self.collapsed_dirs.insert(dir);
+        }
+    }
+
+    fn open_selected_directory(&mut self, filtered_files: &[String]) {
+        let Some(file) = self.selected_file(filtered_files) else {
+            return;
+        };
+        let dir = Path::new(&file)
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        self.status_message = Some(match open_directory(dir) {
+            Ok(()) => format!("Opened {}", dir.display()),
+            Err(err) => format!("Failed to open {}: {}", dir.display(), err),
+        });
+    }
+
+    pub fn type_ahead(&self) -> Option<&str> {
+        if self.type_ahead.is_empty() || self.type_ahead_at.elapsed() > TYPE_AHEAD_TIMEOUT {
+            None
+        } else {
+            Some(&self.type_ahead)
+        }
+    }
+
+    fn jump_to_typed(&mut self, c: char, filtered_files: &[String]) {
+        if self.type_ahead().is_none() {
+            self.type_ahead.clear();
+        }
+        self.type_ahead.push(c);
+        self.type_ahead_at = Instant::now();
+
+        let typed = self.type_ahead.clone();
+        if let Some(index) = self.row_position(filtered_files, |f| f.contains(&typed)) {
+            self.selected = index;
+            self.diff_scroll = 0;
+        }
+    }
+
+    fn toggle_deselected(&mut self, filtered_files: &[String]) {
+        if let Some(dir) = self.selected_dir(filtered_files) {
+            self.toggle_collapsed(dir);
+        } else if let Some(file) = self.selected_file(filtered_files) {
+            if !self.deselected.remove(&file) {
+                self.deselected.insert(file);
+            }
+        }
+    }
+
+    fn open_preset_picker(&mut self) {
+        if self.presets.is_empty() {
+            self.status_message = Some("No presets defined in config".to_string());
+        } else {
+            self.preset_picker = Some(0);
+        }
+    }
+
+    pub fn save_state(&mut self) {
+        self.saved_states.push(SavedState {
+            filter_input: self.filter_input.clone(),
+            filter_cursor: self.filter_cursor,
+            content_filter_input: self.content_filter_input.clone(),
+            content_filter_cursor: self.content_filter_cursor,
+            from_input: self.from_input.clone(),
+            from_cursor: self.from_cursor,
+            to_input: self.to_input.clone(),
+            to_cursor: self.to_cursor,
+            options: self.options,
+        });
+        self.status_message = Some(format!("Saved state {}", self.saved_states.len()));
+    }
+
+    pub fn restore_state(&mut self) {
+        let Some(state) = self.saved_states.pop() else {
+            self.status_message = Some("No saved states".to_string());
+            return;
+        };
+        for (focus, text, cursor) in [
+            (
+                Focus::FilePathFilter,
+                &state.filter_input,
+                state.filter_cursor,
+            ),
+            (
+                Focus::ContentFilter,
+                &state.content_filter_input,
+                state.content_filter_cursor,
+            ),
+            (Focus::From, &state.from_input, state.from_cursor),
+            (Focus::To, &state.to_input, state.to_cursor),
+        ] {
+            if let Some(mut field) = self.field(focus) {
+                field.set_with_cursor(text, cursor);
+            }
+        }
+        self.options = state.options;
+        self.selected = 0;
+        self.offset = 0;
+        self.diff_scroll = 0;
+        self.status_message = Some(format!("Restored state ({} left)", self.saved_states.len()));
+    }
+
+    pub fn load_preset(&mut self, index: usize) {
+        let Some(preset) = self.presets.get(index).cloned() else {
+            return;
+        };
+        if let Some(mut field) = self.field(Focus::From) {
+            field.set(&preset.from);
+        }
+        if let Some(mut field) = self.field(Focus::To) {
+            field.set(&preset.to);
+        }
+        if let Some(filter) = &preset.filter {
+            if let Some(mut field) = self.field(Focus::FilePathFilter) {
+                field.set(filter);
+            }
+        }
+        if let Some(content_filter) = &preset.content_filter {
+            if let Some(mut field) = self.field(Focus::ContentFilter) {
+                field.set(content_filter);
+            }
+        }
+        for (flag, value) in [
+            (&mut self.options.literal, preset.literal),
+            (&mut self.options.anchor_start, preset.anchor_start),
+            (&mut self.options.anchor_end, preset.anchor_end),
+            (&mut self.options.keep_indent, preset.keep_indent),
+            (&mut self.options.case_insensitive, preset.case_insensitive),
+            (&mut self.options.multiline, preset.multiline),
+            (&mut self.options.dotall, preset.dotall),
+        ] {
+            if let Some(value) = value {
+                *flag = value;
+            }
+        }
+        self.selected = 0;
+        self.offset = 0;
+        self.diff_scroll = 0;
+        self.status_message = Some(format!("Loaded preset {}", preset.name));
+    }
+
+    fn copy_to_other_field(&mut self) {
+        let (text, cursor, target) = match self.focus {
+            Focus::From => (self.from_input.clone(), self.from_cursor, Focus::To),
+            Focus::To => (self.to_input.clone(), self.to_cursor, Focus::From),
+            _ => {
+                self.status_message =
+                    Some("Focus From or To to copy it into the other field".to_string());
+                return;
+            }
+        };
+        if let Some(mut field) = self.field(target) {
+            field.set_with_cursor(&text, cursor);
+        }
+        self.focus = target;
+        self.status_message = Some(if target == Focus::To {
+            "Copied From into To".to_string()
+        } else if regex::Regex::new(&effective_pattern(&self.from_input, &self.options)).is_err() {
+            "Copied To into From, but it isn't a valid regex (Alt+L matches it literally)"
+                .to_string()
+        } else {
+            "Copied To into From".to_string()
+        });
+    }
+
+    fn expand_template(&mut self) {
+        match parse_template(&self.from_input) {
+            Ok((from, to)) => {
+                if let Some(mut field) = self.field(Focus::From) {
+                    field.set(&from);
+                }
+                if let Some(mut field) = self.field(Focus::To) {
+                    field.set(&to);
+                }
+                self.options.literal = false;
+                self.status_message = Some("Expanded template into From and To".to_string());
+            }
+            Err(err) => self.status_message = Some(format!("Template error: {}", err)),
+        }
+    }
+
+    fn session_preset(&self, name: &str) -> Preset {
+        let non_empty = |text: &str| (!text.is_empty()).then(|| text.to_string());
+        Preset {
+            name: name.to_string(),
+            from: self.from_input.clone(),
+            to: self.to_input.clone(),
+            filter: non_empty(&self.filter_input),
+            content_filter: non_empty(&self.content_filter_input),
+            literal: Some(self.options.literal),
+            anchor_start: Some(self.options.anchor_start),
+            anchor_end: Some(self.options.anchor_end),
+            keep_indent: Some(self.options.keep_indent),
+            case_insensitive: Some(self.options.case_insensitive),
+            multiline: Some(self.options.multiline),
+            dotall: Some(self.options.dotall),
+        }
+    }
+
+    pub fn export_config(&self, target: &str) -> io::Result<String> {
+        let (path, name) = match target.rsplit_once('#') {
+            Some((path, name)) if !name.trim().is_empty() => (path.trim(), Some(name.trim())),
+            _ => (target.trim(), None),
+        };
+        let path = if path.is_empty() {
+            "ised.config.toml"
+        } else {
+            path
+        };
+
+        let preset = (name.is_some() || !self.from_input.is_empty())
+            .then(|| preset_table(&self.session_preset(name.unwrap_or("session"))))
+            .transpose()?;
+        edit_config(Path::new(path), |document| {
+            if name.is_none() {
+                for section in ["files", "display"] {
+                    document.entry(section).or_insert(table());
+                }
+                document["files"]["glob_filter"] = value(
+                    self.filter_input
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|pattern| !pattern.is_empty())
+                        .collect::<Array>(),
+                );
+                document["display"]["group_by_dir"] = value(self.group_by_dir);
+                document["display"]["hex_preview"] = value(self.hex_preview);
+            }
+            if let Some(preset) = preset {
+                let presets = document
+                    .entry("presets")
+                    .or_insert(Item::ArrayOfTables(ArrayOfTables::new()))
+                    .as_array_of_tables_mut()
+                    .ok_or_else(|| {
+                        io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            "presets is not a list of tables",
+                        )
+                    })?;
+                let name = preset.get("name").and_then(|name| name.as_str());
+                let existing = presets
+                    .iter()
+                    .position(|existing| existing.get("name").and_then(|n| n.as_str()) == name);
+                match existing.and_then(|index| presets.get_mut(index)) {
+                    Some(existing) => {
+                        existing.retain(|key, _| preset.contains_key(key));
+                        for (key, item) in preset.iter() {
+                            existing[key] = item.clone();
+                        }
+                    }
+                    None => presets.push(preset),
+                }
+            }
+            Ok(())
+        })?;
+
+        Ok(match name {
+            Some(name) => format!("Exported preset {} to {}", name, path),
+            None => format!("Exported session to {}", path),
+        })
+    }
+
+    fn handle_load_limit_key(&mut self, key: KeyEvent, filtered_files: &[String]) -> bool {
+        let Some(limit) = self.load_limit_hit else {
+            return false;
+        };
+        if self.focus != Focus::FileList
+            || key
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+        {
+            return false;
+        }
+        match key.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => {
+                self.load_limit_hit = None;
+                self.load_everything = true;
+                self.request_rescan(filtered_files);
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                self.load_limit_hit = None;
+                self.status_message = Some(format!(
+                    "Showing only the first {} files found; raise files.max_files to list more",
+                    limit
+                ));
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    fn handle_export_prompt_key(&mut self, key: KeyEvent) {
+        let Some(target) = self.export_prompt.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Char(c) => target.push(c),
+            KeyCode::Backspace => {
+                target.pop();
+            }
+            KeyCode::Enter => {
+                let target = self.export_prompt.take().unwrap_or_default();
+                self.status_message = Some(match self.export_config(&target) {
+                    Ok(message) => message,
+                    Err(err) => format!("Export failed: {}", err),
+                });
+            }
+            KeyCode::Esc => self.export_prompt = None,
+            _ => {}
+        }
+    }
+
+    pub fn scratch_result(&self) -> Option<String> {
+        let input = self.scratch.as_ref()?;
+        if self.from_input.is_empty() {
+            return Some(input.clone());
+        }
+        Some(apply_substitution_with(
+            input,
+            &self.from_input,
+            &self.to_input,
+            &self.options,
+        ))
+    }
+
+    fn handle_scratch_key(&mut self, key: KeyEvent) {
+        let Some(input) = self.scratch.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Char(c) => input.push(c),
+            KeyCode::Tab => input.push('\t'),
+            KeyCode::Enter => input.push('\n'),
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Esc => self.scratch = None,
+            _ => {}
+        }
+    }
+
+    pub fn collect_results(&self, filtered_files: &[String]) -> Vec<MatchResult> {
+        let Some(re) = self.from_regex() else {
+            return Vec::new();
+        };
+        self.thread_pool.install(|| {
+            filtered_files
+                .par_iter()
+                .filter(|path| !self.is_hex_only(path))
+                .flat_map_iter(|path| {
+                    self.cached_content(path)
+                        .map(|content| match_results(path, &content, &re, RESULT_CONTEXT_LINES))
+                        .unwrap_or_default()
+                })
+                .collect()
+        })
+    }
+
+    fn open_results(&mut self, filtered_files: &[String]) {
+        if self.from_input.is_empty() {
+            self.status_message = Some("Enter a From pattern to list its matches".to_string());
+            return;
+        }
+        let results = self.collect_results(filtered_files);
+        if results.is_empty() {
+            self.status_message = Some("From matches nothing in the listed files".to_string());
+        } else {
+            self.results_view = Some(ResultsView {
+                results,
+                selected: 0,
+            });
+        }
+    }
+
+    pub fn jump_to_result(&mut self, filtered_files: &[String], result: &MatchResult) {
+        let Some(row) = self.row_position(filtered_files, |f| f == result.path) else {
+            self.status_message = Some(format!("{} is no longer listed", result.path));
+            return;
+        };
+        self.selected = row;
+        self.scrolled_file = Some(result.path.clone());
+        self.focus = Focus::DiffView;
+        self.change_cursor = None;
+        self.diff_scroll = self
+            .preview_changes(&result.path)
+            .map_or(result.line, |changes| {
+                let row = diff_row(
+                    &changes.original,
+                    &changes.replaced,
+                    self.diff_theme.hunk_headers,
+                    result.line,
+                );
+                self.display_row(&changes.original, &changes.replaced, row)
+            });
+    }
+
+    fn handle_results_key(&mut self, key: KeyEvent, filtered_files: &[String]) {
+        let Some(view) = self.results_view.as_mut() else {
+            return;
+        };
+        let last = view.results.len().saturating_sub(1);
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => view.selected = view.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => view.selected = (view.selected + 1).min(last),
+            KeyCode::PageUp => view.selected = view.selected.saturating_sub(10),
+            KeyCode::PageDown => view.selected = (view.selected + 10).min(last),
+            KeyCode::Enter => {
+                let result = view.results.get(view.selected).cloned();
+                self.results_view = None;
+                if let Some(result) = result {
+                    self.jump_to_result(filtered_files, &result);
+                }
+            }
+            KeyCode::Esc => self.results_view = None,
+            _ => {}
+        }
+    }
+
+    fn handle_preset_picker_key(&mut self, key: KeyEvent, index: usize) {
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.preset_picker = Some(index.saturating_sub(1));
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.preset_picker = Some((index + 1).min(self.presets.len().saturating_sub(1)));
+            }
+            KeyCode::Enter => {
+                self.preset_picker = None;
+                self.load_preset(index);
+            }
+            KeyCode::Esc => self.preset_picker = None,
+            _ => {}
+        }
+    }
+
+    fn open_rules_view(&mut self) {
+        if self.rules.is_empty() {
+            self.status_message = Some("No rules loaded (use --script FILE)".to_string());
+        } else {
+            self.rules_view = Some(0);
+        }
+    }
+
+    pub fn move_rule(&mut self, index: usize, up: bool) -> usize {
+        let target = if up {
+            index.saturating_sub(1)
+        } else {
+            (index + 1).min(self.rules.len().saturating_sub(1))
+        };
+        if target != index {
+            self.rules.swap(index, target);
+            self.diff_cache = None;
+        }
+        target
+    }
+
+    fn handle_rules_view_key(&mut self, key: KeyEvent, index: usize) {
+        let last = self.rules.len().saturating_sub(1);
+        self.rules_view = match key.code {
+            KeyCode::Up | KeyCode::Char('k') => Some(index.saturating_sub(1)),
+            KeyCode::Down | KeyCode::Char('j') => Some((index + 1).min(last)),
+            KeyCode::Char('K') => Some(self.move_rule(index, true)),
+            KeyCode::Char('J') => Some(self.move_rule(index, false)),
+            KeyCode::Esc | KeyCode::Enter => None,
+            _ => Some(index),
+        };
+    }
+
+    pub fn substituted_content(&self, paths: &[String]) -> String {
+        paths
+            .iter()
+            .filter(|path| !self.is_hex_only(path))
+            .filter_map(|path| {
+                self.cached_content(path)
+                    .map(|content| self.substitute(&content, path))
+            })
+            .collect()
+    }
+
+    pub fn pipe_files(&mut self, paths: &[String]) {
+        let Some(command) = self.pipe_command.clone() else {
+            self.status_message = Some("No pipe command set (use --pipe CMD)".to_string());
+            return;
+        };
+        if paths.is_empty() {
+            return;
+        }
+        match pipe_through(&command, &self.substituted_content(paths)) {
+            Ok(output) => {
+                self.pipe_output = Some(output);
+                self.pipe_scroll = 0;
+            }
+            Err(err) => self.status_message = Some(format!("Failed to run {}: {}", command, err)),
+        }
+    }
+
+    pub fn open_in_pager(&mut self, filtered_files: &[String]) {
+        let Some(path) = self.selected_file(filtered_files) else {
+            return;
+        };
+        if self.is_hex_only(&path) {
+            self.status_message = Some(format!("{} is binary; nothing to page", path));
+            return;
+        }
+        match self.cached_content(&path) {
+            Some(content) => self.pager_request = Some(self.substitute(&content, &path)),
+            None => self.status_message = Some(format!("Failed to read {}", path)),
+        }
+    }
+
+    fn handle_pipe_output_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.pipe_scroll = self.pipe_scroll.saturating_sub(1);
+            }
+            KeyCode::Down | KeyCode::Char('j') => self.pipe_scroll += 1,
+            KeyCode::Esc | KeyCode::Enter => self.pipe_output = None,
+            _ => {}
+        }
+    }
+
+    fn toggle_review(&mut self) {
+        self.layout = match self.layout {
+            LayoutMode::Normal => LayoutMode::Review,
+            LayoutMode::Review => LayoutMode::Normal,
+        };
+        self.diff_scroll = 0;
+    }
+
+    pub fn changes_file(&self, path: &str) -> bool {
+        self.substituted_file(path)
+            .is_some_and(|(content, replaced)| content != replaced)
+    }
+
+    fn changed_only_active(&self) -> bool {
+        self.changed_only && self.has_substitution()
+    }
+
+    fn next_file_row(&self, filtered_files: &[String], forward: bool) -> Option<usize> {
+        let rows = self.list_rows(filtered_files);
+        let changed_only = self.changed_only_active();
+        let eligible = |i: &usize| {
+            rows[*i]
+                .file()
+                .is_some_and(|f| !changed_only || self.changes_file(f))
+        };
+        if forward {
+            (self.selected + 1..rows.len()).find(eligible)
+        } else {
+            (0..self.selected.min(rows.len())).rev().find(eligible)
+        }
+    }
+
+    fn step_changed_file(&mut self, filtered_files: &[String], forward: bool) {
+        if let Some(index) = self.next_file_row(filtered_files, forward) {
+            self.selected = index;
+        }
+    }
+
+    fn toggle_changed_only(&mut self, filtered_files: &[String]) {
+        self.changed_only = !self.changed_only;
+        if !self.changed_only {
+            self.status_message = Some("Moving through all listed files".to_string());
+            return;
+        }
+        self.status_message = Some("Moving through changed files only".to_string());
+        let on_changed = self
+            .selected_file(filtered_files)
+            .is_some_and(|f| self.changes_file(&f));
+        if self.changed_only_active() && !on_changed {
+            if let Some(index) = self.row_position(filtered_files, |f| self.changes_file(f)) {
+                self.selected = index;
+                self.diff_scroll = 0;
+            }
+        }
+    }
+
+    fn step_review_file(&mut self, filtered_files: &[String], forward: bool) {
+        if let Some(index) = self.next_file_row(filtered_files, forward) {
+            self.selected = index;
+            self.diff_scroll = 0;
+            self.hunk_cursor = 0;
+            self.change_cursor = None;
+        }
+    }
+
+    fn handle_review_key(&mut self, key: KeyEvent, filtered_files: &[String]) -> bool {
+        if key
+            .modifiers
+            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+            || !matches!(self.confirm, ConfirmState::None)
+        {
+            return false;
+        }
+
+        match key.code {
+            KeyCode::Char('j') => self.step_review_file(filtered_files, true),
+            KeyCode::Char('k') => self.step_review_file(filtered_files, false),
+            KeyCode::Down => self.diff_scroll += 1,
+            KeyCode::Up => self.diff_scroll = self.diff_scroll.saturating_sub(1),
+            KeyCode::Char('n') => self.move_change(filtered_files, true),
+            KeyCode::Char('N') => self.move_change(filtered_files, false),
+            KeyCode::Char(']') => self.move_hunk(filtered_files, true),
+            KeyCode::Char('[') => self.move_hunk(filtered_files, false),
+            KeyCode::Char(' ') => self.toggle_hunk(filtered_files),
+            KeyCode::Enter => {
+                if !self.block_read_only() && !self.block_empty_from() {
+                    if let Some(file) = self.selected_file(filtered_files) {
+                        self.confirm = ConfirmState::Confirming(file);
+                    }
+                }
+            }
+            KeyCode::Esc => self.toggle_review(),
+            KeyCode::Char(_) | KeyCode::Backspace | KeyCode::Left | KeyCode::Right => {}
+            _ => return false,
+        }
+        true
+    }
+
+    pub fn handle_key_event(
+        &mut self,
+        key: KeyEvent,
+        filtered_files: &[String],
+    ) -> io::Result<bool> {
+        self.status_message = None;
+
+        if let Some(index) = self.preset_picker {
+            self.handle_preset_picker_key(key, index);
+            return Ok(false);
+        }
+
+        if let Some(index) = self.rules_view {
+            self.handle_rules_view_key(key, index);
+            return Ok(false);
+        }
+
+        if self.pipe_output.is_some() {
+            self.handle_pipe_output_key(key);
+            return Ok(false);
+        }
+
+        if self.handle_load_limit_key(key, filtered_files) {
+            return Ok(false);
+        }
+
+        if self.export_prompt.is_some() {
+            self.handle_export_prompt_key(key);
+            return Ok(false);
+        }
+
+        if self.scratch.is_some() {
+            self.handle_scratch_key(key);
+            return Ok(false);
+        }
+
+        if self.results_view.is_some() {
+            self.handle_results_key(key, filtered_files);
+            return Ok(false);
+        }
+
+        if self.history_search.is_some() {
+            self.handle_history_search_key(key);
+            return Ok(false);
+        }
+
+        if self.layout == LayoutMode::Review && self.handle_review_key(key, filtered_files) {
+            return Ok(false);
+        }
+
+        match key {
+            KeyEvent {
+                code: KeyCode::Char('c'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => return Ok(true),
+
+            KeyEvent {
+                code: KeyCode::Char('l'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => {
+                self.focus = Focus::FileList;
+            }
+
+            KeyEvent {
+                code: KeyCode::Char('g'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => {
+                self.focus = Focus::FilePathFilter;
+            }
+
+            KeyEvent {
+                code: KeyCode::Char('n'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => {
+                self.focus = Focus::ContentFilter;
+            }
+
+            KeyEvent {
+                code: KeyCode::Char('w'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => {
+                self.toggle_watcher();
+                self.status_message = Some(if self.is_watching() {
+                    "File watcher on".to_string()
+                } else {
+                    "File watcher off".to_string()
+                });
+            }
+
+            KeyEvent {
+                code: KeyCode::F(5),
+                ..
+            } => self.request_rescan(filtered_files),
+
+            KeyEvent {
+                code: KeyCode::F(3),
+                ..
+            } => self.open_in_pager(filtered_files),
+
+            KeyEvent {
+                code: KeyCode::Char('g'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => self.toggle_grouping(filtered_files),
+
+            KeyEvent {
+                code: KeyCode::Char('p'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => {
+                self.path_regex = !self.path_regex;
+                self.selected = 0;
+                self.offset = 0;
+            }
+
+            KeyEvent {
+                code: KeyCode::Char('d'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => {
+                if self.block_read_only() {
+                    return Ok(false);
+                }
+                if let Some(path) = self.selected_file(filtered_files) {
+                    self.status_message = Some(match self.write_copy(&path) {
+                        Ok(target) => format!("Wrote result to {}", target.display()),
+                        Err(err) => format!("Failed to write a copy of {}: {}", path, err),
+                    });
+                }
+            }
+
+            KeyEvent {
+                code: KeyCode::Char('e'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => self.expand_template(),
+
+            KeyEvent {
+                code: KeyCode::Char('h'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => self.options.anchor_start = !self.options.anchor_start,
+
+            KeyEvent {
+                code: KeyCode::Char('t'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => self.options.anchor_end = !self.options.anchor_end,
+
+            KeyEvent {
+                code: KeyCode::Char('k'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => self.options.keep_indent = !self.options.keep_indent,
+
+            KeyEvent {
+                code: KeyCode::Char('v'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => self.match_view = !self.match_view,
+
+            KeyEvent {
+                code: KeyCode::Char('u'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => {
+                self.mouse_capture = !self.mouse_capture;
+                self.status_message = Some(if self.mouse_capture {
+                    "Mouse capture on".to_string()
+                } else {
+                    "Mouse capture off: the terminal's own text selection works again".to_string()
+                });
+            }
+
+            KeyEvent {
+                code: KeyCode::Char('z'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => self.toggle_review(),
+
+            KeyEvent {
+                code: KeyCode::Char('='),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => self.copy_to_other_field(),
+
+            KeyEvent {
+                code: KeyCode::Char('i'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => self.options.case_insensitive = !self.options.case_insensitive,
+
+            KeyEvent {
+                code: KeyCode::Char('m'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => self.options.multiline = !self.options.multiline,
+
+            KeyEvent {
+                code: KeyCode::Char('s'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => self.options.dotall = !self.options.dotall,
+
+            KeyEvent {
+                code: KeyCode::Char('y'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => self.collapse_inputs = !self.collapse_inputs,
+
+            KeyEvent {
+                code: KeyCode::Char('x'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => {
+                let selected = self.selected_file(filtered_files);
+                self.pipe_files(selected.as_slice());
+            }
+
+            KeyEvent {
+                code: KeyCode::Char('x'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => self.pipe_files(filtered_files),
+
+            KeyEvent {
+                code: KeyCode::Char('c'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => self.copy_report_paths(true),
+
+            KeyEvent {
+                code: KeyCode::Char('a'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => self.copy_report_paths(false),
+
+            KeyEvent {
+                code: KeyCode::Char('l'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => {
+                self.options.literal = !self.options.literal;
+                self.status_message = Some(if self.options.literal {
+                    "Literal mode on".to_string()
+                } else {
+                    "Literal mode off".to_string()
+                });
+            }
+
+            KeyEvent {
+                code: KeyCode::Char('o'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => {
+                self.only_matching = !self.only_matching;
+                self.selected = 0;
+                self.offset = 0;
+            }
+
+            KeyEvent {
+                code: KeyCode::Char('j'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => self.toggle_changed_only(filtered_files),
+
+            KeyEvent {
+                code: KeyCode::Char('k'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => {
+                self.conflicts_only = !self.conflicts_only;
+                self.selected = 0;
+                self.offset = 0;
+            }
+
+            KeyEvent {
+                code: KeyCode::Char('n'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => {
+                self.show_match_lines = !self.show_match_lines;
+            }
+
+            KeyEvent {
+                code: KeyCode::Char('q'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => {
+                self.show_inline_preview = !self.show_inline_preview;
             }
-        } else {
-            None
-        };
 
-        let from_re = from_re.or_else(|| {
-            regex::Regex::new(&self.from_input).ok().inspect(|re| {
-                self.regex_cache
-                    .write()
-                    .insert(self.from_input.clone(), re.clone());
-            })
-        });
+            KeyEvent {
+                code: KeyCode::Char('w'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => {
+                self.wrap_diff = !self.wrap_diff;
+            }
 
-        let filtered_files: Vec<String> = self
-            .files
-            .par_iter()
-            .filter(|f| {
-                let included = if has_include {
-                    include_set
-                        .as_ref()
-                        .map(|set| set.is_match(f))
-                        .unwrap_or(false)
+            KeyEvent {
+                code: KeyCode::Char('f'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => {
+                self.scroll_to_first_change = !self.scroll_to_first_change;
+                self.scrolled_file = None;
+                self.status_message = Some(if self.scroll_to_first_change {
+                    "Diff opens at the first change".to_string()
                 } else {
-                    true
-                };
+                    "Diff opens at the top".to_string()
+                });
+            }
 
-                let excluded = exclude_set
-                    .as_ref()
-                    .map(|set| set.is_match(f))
-                    .unwrap_or(false);
+            KeyEvent {
+                code: KeyCode::Char('o'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => self.open_selected_directory(filtered_files),
 
-                let matches_from = if let Some(re) = &from_re {
-                    let content = {
-                        let cache = self.file_cache.read();
-                        cache.get(*f).cloned()
-                    };
+            KeyEvent {
+                code: KeyCode::Char('p'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => self.open_preset_picker(),
 
-                    if let Some(content) = content {
-                        re.is_match(&content)
-                    } else {
-                        std::fs::read_to_string(f)
-                            .map(|content| {
-                                let mut cache = self.file_cache.write();
-                                cache.insert(f.to_string(), content.clone());
-                                re.is_match(&content)
-                            })
-                            .unwrap_or(false)
-                    }
-                } else {
-                    true
-                };
+            KeyEvent {
+                code: KeyCode::Char('u'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => self.open_rules_view(),
 
-                included && !excluded && matches_from
-            })
-            .cloned()
-            .collect();
+            KeyEvent {
+                code: KeyCode::Char('s'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => self.open_export_prompt(),
 
-        {
-            let mut cache = self.filtered_files_cache.write();
-            *cache = Some((
-                self.filter_input.clone(),
-                self.from_input.clone(),
-                filtered_files.clone(),
-            ));
-        }
+            KeyEvent {
+                code: KeyCode::Char('r'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => self.start_history_search(),
 
-        filtered_files
-    }
+            KeyEvent {
+                code: KeyCode::Char('e'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => self.open_results(filtered_files),
 
-    pub fn handle_key_event(
-        &mut self,
-        key: KeyEvent,
-        filtered_files: &[String],
-    ) -> io::Result<bool> {
-        match key {
             KeyEvent {
-                code: KeyCode::Char('c'),
+                code: KeyCode::Char('y'),
                 modifiers: KeyModifiers::CONTROL,
                 ..
-            } => return Ok(true),
+            } => self.scratch = Some(String::new()),
 
             KeyEvent {
-                code: KeyCode::Char('l'),
+                code: KeyCode::Char('b'),
                 modifiers: KeyModifiers::CONTROL,
                 ..
-            } => {
-                self.focus = Focus::FileList;
-            }
+            } => self.toggle_compare(filtered_files),
 
             KeyEvent {
-                code: KeyCode::Char('g'),
+                code: KeyCode::Char('v'),
                 modifiers: KeyModifiers::CONTROL,
                 ..
             } => {
-                self.focus = Focus::FilePathFilter;
+                self.full_diff = !self.full_diff;
+                self.diff_scroll = 0;
+                self.change_cursor = None;
             }
 
+            KeyEvent {
+                code: KeyCode::Char('z'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => self.undo(),
+
+            KeyEvent {
+                code: KeyCode::Char('b'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => self.save_state(),
+
+            KeyEvent {
+                code: KeyCode::Char('r'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => self.restore_state(),
+
             KeyEvent {
                 code: KeyCode::Char('d'),
                 modifiers: KeyModifiers::CONTROL,
@@ -394,7 +3242,8 @@ impl App {
             } => {
                 self.focus = match self.focus {
                     Focus::FileList => Focus::FilePathFilter,
-                    Focus::FilePathFilter => Focus::DiffView,
+                    Focus::FilePathFilter => Focus::ContentFilter,
+                    Focus::ContentFilter => Focus::DiffView,
                     Focus::DiffView => Focus::From,
                     Focus::From => Focus::To,
                     Focus::To => Focus::FileList,
@@ -405,64 +3254,82 @@ impl App {
                 code: KeyCode::Char('a'),
                 modifiers: KeyModifiers::CONTROL,
                 ..
-            } => {
-                if self.focus == Focus::FileList {
-                    self.confirm = ConfirmState::ConfirmingAll(filtered_files.to_vec());
-                }
-            }
+            } => self.confirm_all(filtered_files),
+
+            KeyEvent {
+                code: KeyCode::Char('q'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => self.confirm_from_selected(filtered_files),
 
             KeyEvent {
                 code: KeyCode::Enter,
                 ..
-            } => {
-                if self.focus == Focus::FileList {
-                    if let Some(file) = filtered_files.get(self.selected) {
-                        self.confirm = ConfirmState::Confirming(file.clone());
-                    }
+            } if self.focus == Focus::FileList => {
+                if let Some(dir) = self.selected_dir(filtered_files) {
+                    self.toggle_collapsed(dir);
+                } else if self.block_read_only() || self.block_empty_from() {
+                    return Ok(false);
+                } else if let Some(file) = self.selected_file(filtered_files) {
+                    self.confirm = ConfirmState::Confirming(file);
                 }
             }
 
             KeyEvent {
-                code: KeyCode::Char('y'),
+                code: KeyCode::Up | KeyCode::Char('k'),
                 ..
-            } => match &self.confirm {
-                ConfirmState::Confirming(path) => {
-                    self.apply_substitution(path)?;
-                    self.confirm = ConfirmState::None;
-                }
-                ConfirmState::ConfirmingAll(paths) => {
-                    for path in paths {
-                        let _ = self.apply_substitution(path);
+            } if matches!(
+                self.confirm,
+                ConfirmState::ConfirmingAll(_) | ConfirmState::ConfirmingFrom(_)
+            ) =>
+            {
+                self.confirm_scroll = self.confirm_scroll.saturating_sub(1);
+            }
+
+            KeyEvent {
+                code: KeyCode::Down | KeyCode::Char('j'),
+                ..
+            } if matches!(
+                self.confirm,
+                ConfirmState::ConfirmingAll(_) | ConfirmState::ConfirmingFrom(_)
+            ) =>
+            {
+                if let ConfirmState::ConfirmingAll(paths) | ConfirmState::ConfirmingFrom(paths) =
+                    &self.confirm
+                {
+                    if self.confirm_scroll + 1 < paths.len() {
+                        self.confirm_scroll += 1;
                     }
-                    self.confirm = ConfirmState::None;
                 }
-                ConfirmState::None => self.push_input('y'),
-            },
+            }
+
+            KeyEvent {
+                code: KeyCode::Char(c @ ('y' | 'Y')),
+                ..
+            } if !matches!(self.confirm, ConfirmState::None) => self.confirm_apply(c == 'Y'),
 
             KeyEvent {
                 code: KeyCode::Char('n'),
                 ..
-            } => {
-                if !matches!(self.confirm, ConfirmState::None) {
-                    self.confirm = ConfirmState::None;
-                } else {
-                    self.push_input('n');
-                }
+            } if !matches!(self.confirm, ConfirmState::None) => {
+                self.confirm = ConfirmState::None;
             }
 
             KeyEvent {
                 code: KeyCode::Esc, ..
             } => {
                 self.confirm = ConfirmState::None;
+                self.type_ahead.clear();
             }
 
             KeyEvent {
                 code: KeyCode::Up, ..
             } => match self.focus {
-                Focus::FileList => {
-                    if self.selected > 0 {
-                        self.selected -= 1;
-                    }
+                Focus::FileList if self.changed_only_active() => {
+                    self.step_changed_file(filtered_files, false);
+                }
+                Focus::FileList if self.selected > 0 => {
+                    self.selected -= 1;
                 }
                 Focus::DiffView => {
                     self.diff_scroll = self.diff_scroll.saturating_sub(1);
@@ -474,10 +3341,11 @@ impl App {
                 code: KeyCode::Down,
                 ..
             } => match self.focus {
-                Focus::FileList => {
-                    if self.selected + 1 < filtered_files.len() {
-                        self.selected += 1;
-                    }
+                Focus::FileList if self.changed_only_active() => {
+                    self.step_changed_file(filtered_files, true);
+                }
+                Focus::FileList if self.selected + 1 < self.list_len(filtered_files) => {
+                    self.selected += 1;
                 }
                 Focus::DiffView => {
                     self.diff_scroll += 1;
@@ -488,126 +3356,31 @@ impl App {
             KeyEvent {
                 code: KeyCode::Left,
                 ..
-            } => match self.focus {
-                Focus::FilePathFilter => {
-                    if self.filter_cursor > 0 {
-                        self.filter_cursor -= 1;
-                        Self::update_view_offset_for_cursor(
-                            self.filter_cursor,
-                            &mut self.filter_view_offset,
-                            self.filter_input.chars().count(),
-                            self.filter_field_width,
-                        );
-                    } else {
-                        Self::scroll_view_left(
-                            &mut self.filter_view_offset,
-                            self.filter_input.chars().count(),
-                            self.filter_field_width,
-                        );
-                    }
-                }
-                Focus::From => {
-                    if self.from_cursor > 0 {
-                        self.from_cursor -= 1;
-                        Self::update_view_offset_for_cursor(
-                            self.from_cursor,
-                            &mut self.from_view_offset,
-                            self.from_input.chars().count(),
-                            self.from_field_width,
-                        );
-                    } else {
-                        Self::scroll_view_left(
-                            &mut self.from_view_offset,
-                            self.from_input.chars().count(),
-                            self.from_field_width,
-                        );
-                    }
-                }
-                Focus::To => {
-                    if self.to_cursor > 0 {
-                        self.to_cursor -= 1;
-                        Self::update_view_offset_for_cursor(
-                            self.to_cursor,
-                            &mut self.to_view_offset,
-                            self.to_input.chars().count(),
-                            self.to_field_width,
-                        );
-                    } else {
-                        Self::scroll_view_left(
-                            &mut self.to_view_offset,
-                            self.to_input.chars().count(),
-                            self.to_field_width,
-                        );
-                    }
+            } => {
+                if let Some(mut field) = self.focused_field() {
+                    field.move_left();
                 }
-                _ => {}
-            },
+            }
 
             KeyEvent {
                 code: KeyCode::Right,
                 ..
-            } => match self.focus {
-                Focus::FilePathFilter => {
-                    if self.filter_cursor < self.filter_input.chars().count() {
-                        self.filter_cursor += 1;
-                        Self::update_view_offset_for_cursor(
-                            self.filter_cursor,
-                            &mut self.filter_view_offset,
-                            self.filter_input.chars().count(),
-                            self.filter_field_width,
-                        );
-                    } else {
-                        Self::scroll_view_right(
-                            &mut self.filter_view_offset,
-                            self.filter_input.len(),
-                            self.filter_field_width,
-                        );
-                    }
-                }
-                Focus::From => {
-                    if self.from_cursor < self.from_input.chars().count() {
-                        self.from_cursor += 1;
-                        Self::update_view_offset_for_cursor(
-                            self.from_cursor,
-                            &mut self.from_view_offset,
-                            self.from_input.chars().count(),
-                            self.from_field_width,
-                        );
-                    } else {
-                        Self::scroll_view_right(
-                            &mut self.from_view_offset,
-                            self.from_input.len(),
-                            self.from_field_width,
-                        );
-                    }
-                }
-                Focus::To => {
-                    if self.to_cursor < self.to_input.chars().count() {
-                        self.to_cursor += 1;
-                        Self::update_view_offset_for_cursor(
-                            self.to_cursor,
-                            &mut self.to_view_offset,
-                            self.to_input.chars().count(),
-                            self.to_field_width,
-                        );
-                    } else {
-                        Self::scroll_view_right(
-                            &mut self.to_view_offset,
-                            self.to_input.len(),
-                            self.to_field_width,
-                        );
-                    }
+            } => {
+                if let Some(mut field) = self.focused_field() {
+                    field.move_right();
                 }
-                _ => {}
-            },
+            }
 
             KeyEvent {
                 code: KeyCode::Char(c),
                 ..
             } => match c {
                 'j' => match self.focus {
+                    Focus::FileList if self.changed_only_active() => {
+                        self.step_changed_file(filtered_files, true)
+                    }
                     Focus::FileList => {
-                        if self.selected + 1 < filtered_files.len() {
+                        if self.selected + 1 < self.list_len(filtered_files) {
                             self.selected += 1;
                         }
                     }
@@ -615,147 +3388,427 @@ impl App {
                     _ => self.push_input('j'),
                 },
                 'k' => match self.focus {
+                    Focus::FileList if self.changed_only_active() => {
+                        self.step_changed_file(filtered_files, false)
+                    }
                     Focus::FileList => self.selected = self.selected.saturating_sub(1),
                     Focus::DiffView => self.diff_scroll = self.diff_scroll.saturating_sub(1),
                     _ => self.push_input('k'),
                 },
-                _ => self.push_input(c),
+                ' ' => match self.focus {
+                    Focus::FileList => self.toggle_deselected(filtered_files),
+                    Focus::DiffView => self.toggle_hunk(filtered_files),
+                    _ => self.push_input(' '),
+                },
+                'n' if self.focus == Focus::DiffView => self.move_change(filtered_files, true),
+                'N' if self.focus == Focus::DiffView => self.move_change(filtered_files, false),
+                ']' if self.focus == Focus::DiffView => self.move_hunk(filtered_files, true),
+                '[' if self.focus == Focus::DiffView => self.move_hunk(filtered_files, false),
+                _ => match self.focus {
+                    Focus::FileList => self.jump_to_typed(c, filtered_files),
+                    _ => self.push_input(c),
+                },
             },
 
             KeyEvent {
                 code: KeyCode::Backspace,
                 ..
-            } => match self.focus {
-                Focus::FilePathFilter => {
-                    if self.filter_cursor > 0 {
-                        let char_indices: Vec<(usize, char)> =
-                            self.filter_input.char_indices().collect();
-                        if let Some(&(byte_pos, _)) = char_indices.get(self.filter_cursor - 1) {
-                            self.filter_input.remove(byte_pos);
-                        }
-                        self.filter_cursor -= 1;
-                        Self::update_view_offset_for_cursor(
-                            self.filter_cursor,
-                            &mut self.filter_view_offset,
-                            self.filter_input.chars().count(),
-                            self.filter_field_width,
-                        );
-                    }
-                    self.selected = 0;
-                    self.offset = 0;
-                }
-                Focus::From => {
-                    if self.from_cursor > 0 {
-                        let char_indices: Vec<(usize, char)> =
-                            self.from_input.char_indices().collect();
-                        if let Some(&(byte_pos, _)) = char_indices.get(self.from_cursor - 1) {
-                            self.from_input.remove(byte_pos);
-                        }
-                        self.from_cursor -= 1;
-                        Self::update_view_offset_for_cursor(
-                            self.from_cursor,
-                            &mut self.from_view_offset,
-                            self.from_input.chars().count(),
-                            self.from_field_width,
-                        );
-                    }
-                }
-                Focus::To => {
-                    if self.to_cursor > 0 {
-                        let char_indices: Vec<(usize, char)> =
-                            self.to_input.char_indices().collect();
-                        if let Some(&(byte_pos, _)) = char_indices.get(self.to_cursor - 1) {
-                            self.to_input.remove(byte_pos);
-                        }
-                        self.to_cursor -= 1;
-                        Self::update_view_offset_for_cursor(
-                            self.to_cursor,
-                            &mut self.to_view_offset,
-                            self.to_input.chars().count(),
-                            self.to_field_width,
-                        );
-                    }
+            } => {
+                if let Some(mut field) = self.focused_field() {
+                    field.backspace();
                 }
-                _ => {}
-            },
+                self.reset_selection_on_filter_edit();
+            }
 
             _ => {}
         }
         Ok(false)
     }
 
+    fn focused_field(&mut self) -> Option<InputField<'_>> {
+        self.field(self.focus)
+    }
+
+    fn field(&mut self, focus: Focus) -> Option<InputField<'_>> {
+        let (text, cursor, view_offset, width) = match focus {
+            Focus::FilePathFilter => (
+                &mut self.filter_input,
+                &mut self.filter_cursor,
+                &mut self.filter_view_offset,
+                self.filter_field_width,
+            ),
+            Focus::ContentFilter => (
+                &mut self.content_filter_input,
+                &mut self.content_filter_cursor,
+                &mut self.content_filter_view_offset,
+                self.content_filter_field_width,
+            ),
+            Focus::From => (
+                &mut self.from_input,
+                &mut self.from_cursor,
+                &mut self.from_view_offset,
+                self.from_field_width,
+            ),
+            Focus::To => (
+                &mut self.to_input,
+                &mut self.to_cursor,
+                &mut self.to_view_offset,
+                self.to_field_width,
+            ),
+            _ => return None,
+        };
+
+        Some(InputField {
+            text,
+            cursor,
+            view_offset,
+            width,
+        })
+    }
+
+    fn reset_selection_on_filter_edit(&mut self) {
+        if matches!(self.focus, Focus::FilePathFilter | Focus::ContentFilter) {
+            self.selected = 0;
+            self.offset = 0;
+        }
+    }
+
     fn push_input(&mut self, c: char) {
-        match self.focus {
-            Focus::FilePathFilter => {
-                let char_indices: Vec<(usize, char)> = self.filter_input.char_indices().collect();
-                let byte_pos = if self.filter_cursor >= char_indices.len() {
-                    self.filter_input.len()
-                } else {
-                    char_indices[self.filter_cursor].0
-                };
-                self.filter_input.insert(byte_pos, c);
-                self.filter_cursor += 1;
-                Self::update_view_offset_for_cursor(
-                    self.filter_cursor,
-                    &mut self.filter_view_offset,
-                    self.filter_input.chars().count(),
-                    self.filter_field_width,
-                );
-                self.selected = 0;
-                self.offset = 0;
+        if let Some(mut field) = self.focused_field() {
+            field.insert(c);
+        }
+        self.reset_selection_on_filter_edit();
+    }
+
+    pub fn collect_edits(&self, paths: &[String]) -> Result<EditSet, String> {
+        if self.options.keep_indent
+            || self.options.strip_trailing_whitespace
+            || !self.rules.is_empty()
+            || !self.patches.is_empty()
+            || paths
+                .iter()
+                .any(|path| self.rejected_hunks_for(path).is_some())
+        {
+            return Err(
+                "edits can't express keep indent, trailing whitespace stripping, \
+                 --script rules, --patch hunks or skipped hunks"
+                    .to_string(),
+            );
+        }
+
+        let mut edit_set = EditSet::default();
+        for path in paths {
+            let content = fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))?;
+            let edits = substitution_edits(
+                &content,
+                &self.from_input,
+                &self.replacement_for(path),
+                &self.options,
+            );
+            if !edits.is_empty() {
+                edit_set.files.push(FileEdits {
+                    path: path.clone(),
+                    edits,
+                });
             }
-            Focus::From => {
-                let char_indices: Vec<(usize, char)> = self.from_input.char_indices().collect();
-                let byte_pos = if self.from_cursor >= char_indices.len() {
-                    self.from_input.len()
-                } else {
-                    char_indices[self.from_cursor].0
-                };
-                self.from_input.insert(byte_pos, c);
-                self.from_cursor += 1;
-                Self::update_view_offset_for_cursor(
-                    self.from_cursor,
-                    &mut self.from_view_offset,
-                    self.from_input.chars().count(),
-                    self.from_field_width,
-                );
+        }
+        Ok(edit_set)
+    }
+
+    fn write_edits(&self, paths: &[String], edits_path: &Path) -> String {
+        let written = self.collect_edits(paths).and_then(|edit_set| {
+            edit_set
+                .write_json(edits_path)
+                .map(|()| edit_set)
+                .map_err(|err| err.to_string())
+        });
+        match written {
+            Ok(edit_set) => format!(
+                "Wrote {} edits in {} files to {} (no files modified)",
+                edit_set.edit_count(),
+                edit_set.files.len(),
+                edits_path.display()
+            ),
+            Err(err) => format!("Failed to write edits: {}", err),
+        }
+    }
+
+    pub fn apply_all(&self, paths: &[String]) -> ApplyReport {
+        self.apply_batch(paths).0
+    }
+
+    fn apply_batch(&self, paths: &[String]) -> (ApplyReport, Vec<UndoFile>) {
+        let mut report = ApplyReport::default();
+        let mut written = Vec::new();
+        for path in paths {
+            let result = self.apply_substitution(path).map(|applied| {
+                applied.map(|(lines, file)| {
+                    written.push(file);
+                    lines
+                })
+            });
+            report.push(path, result);
+        }
+        (report, written)
+    }
+
+    pub fn undo(&mut self) {
+        if self.block_read_only() {
+            return;
+        }
+        let Some(files) = self.undo_stack.pop() else {
+            self.status_message = Some("Nothing to undo".to_string());
+            return;
+        };
+
+        let mut restored = 0;
+        let mut failures = Vec::new();
+        for file in files.iter().rev() {
+            let result = match fs::read(&file.path) {
+                Ok(current) if current != file.written.as_bytes() => {
+                    Err(io::Error::other("changed since it was applied"))
+                }
+                Ok(_) => fs::write(&file.path, &file.original)
+                    .inspect(|_| self.recent_writes.record(Path::new(&file.path))),
+                Err(err) => Err(err),
+            };
+            match result {
+                Ok(()) => {
+                    restored += 1;
+                    self.file_cache.write().remove(&file.path);
+                    self.modified_files.write().remove(&file.path);
+                }
+                Err(err) => failures.push(format!("{}: {}", file.path, err)),
             }
-            Focus::To => {
-                let char_indices: Vec<(usize, char)> = self.to_input.char_indices().collect();
-                let byte_pos = if self.to_cursor >= char_indices.len() {
-                    self.to_input.len()
-                } else {
-                    char_indices[self.to_cursor].0
-                };
-                self.to_input.insert(byte_pos, c);
-                self.to_cursor += 1;
-                Self::update_view_offset_for_cursor(
-                    self.to_cursor,
-                    &mut self.to_view_offset,
-                    self.to_input.chars().count(),
-                    self.to_field_width,
-                );
+        }
+        *self.scan_cache.write() = ScanCache::default();
+        self.diff_cache = None;
+
+        self.status_message = Some(if failures.is_empty() {
+            format!("Undid changes to {} files", restored)
+        } else {
+            format!(
+                "Undid changes to {} files, {} failed ({})",
+                restored,
+                failures.len(),
+                failures.join(", ")
+            )
+        });
+    }
+
+    pub fn diff_lines(&mut self, path: &str, content: String) -> &[Line<'static>] {
+        let key = DiffKey {
+            path: path.to_string(),
+            from: self.from_input.clone(),
+            to: self.replacement_for(path),
+            options: self.options,
+            rules: self.rules.clone(),
+            compare: self.compare_target(path).map(|other| {
+                (
+                    other.to_string(),
+                    self.cached_content(other).unwrap_or_default(),
+                )
+            }),
+            full_diff: self.full_diff,
+            content,
+        };
+
+        let is_cached = matches!(&self.diff_cache, Some((cached_key, _)) if *cached_key == key);
+        if !is_cached {
+            let replaced = match (&key.compare, self.preview_changes(path)) {
+                (Some((_, other)), _) => other.clone(),
+                (None, Some(changes)) if changes.original == key.content => {
+                    changes.replaced.clone()
+                }
+                (None, _) => self.substitute(&key.content, path),
+            };
+            let rows = self.collapsed_diff_rows(&key.content, &replaced);
+            let lines = highlight_diff_lines_with(key.content.clone(), replaced, &self.diff_theme);
+            let lines = match rows {
+                Some(rows) => collapse_diff_lines(lines, &rows),
+                None => lines,
+            };
+            self.diff_cache = Some((key, lines));
+        }
+
+        self.diff_cache
+            .as_ref()
+            .map(|(_, lines)| lines.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn compare_target(&self, path: &str) -> Option<&str> {
+        self.compare_with.as_deref().filter(|other| *other != path)
+    }
+
+    fn toggle_compare(&mut self, filtered_files: &[String]) {
+        if self.compare_with.take().is_some() {
+            self.status_message = Some("Comparison off".to_string());
+            return;
+        }
+        if let Some(path) = self.selected_file(filtered_files) {
+            self.status_message = Some(format!(
+                "Comparing other files against {} (Ctrl+B to stop)",
+                path
+            ));
+            self.compare_with = Some(path);
+            self.diff_scroll = 0;
+        }
+    }
+
+    fn patch_for(&self, path: &str) -> Option<&FilePatch> {
+        let path = path.strip_prefix("./").unwrap_or(path);
+        self.patches
+            .iter()
+            .find(|patch| patch.path.strip_prefix("./").unwrap_or(&patch.path) == path)
+    }
+
+    pub fn patch_error(&self, path: &str) -> Option<String> {
+        let patch = self.patch_for(path)?;
+        let content = self.cached_content(path)?;
+        apply_patch(&content, patch).err()
+    }
+
+    pub fn substitute(&self, content: &str, path: &str) -> String {
+        let patched = self
+            .patch_for(path)
+            .and_then(|patch| apply_patch(content, patch).ok());
+        let content = patched.as_deref().unwrap_or(content);
+        let replaced = match self.from_regex() {
+            Some(re) => {
+                apply_regex_substitution(content, &re, &self.replacement_for(path), &self.options)
             }
-            _ => {}
+            None => content.to_string(),
+        };
+        self.rules
+            .iter()
+            .filter(|rule| rule.applies_to(path))
+            .fold(replaced, |content, rule| {
+                match self.compiled_regex(&rule.pattern()) {
+                    Some(re) => rule.apply_regex(&re, &content),
+                    None => content,
+                }
+            })
+    }
+
+    pub fn capture_summary(&self, filtered_files: &[String]) -> Option<String> {
+        if !matches!(self.focus, Focus::From | Focus::To) {
+            return None;
+        }
+        let path = self.selected_file(filtered_files)?;
+        let re = self.compiled_from_regex()?;
+        let content = self.cached_content(&path)?;
+        first_match_captures(&content, &re).map(|captures| format!("First match: {}", captures))
+    }
+
+    pub fn to_escape_warning(&self) -> Option<String> {
+        if self.options.literal {
+            return None;
+        }
+        let (_, invalid) = unescape_replacement(&self.to_input);
+        (!invalid.is_empty()).then(|| {
+            format!(
+                "Invalid escape {} in To is inserted as written",
+                invalid.join(", ")
+            )
+        })
+    }
+
+    pub fn replacement_for(&self, path: &str) -> String {
+        if self.options.literal {
+            expand_path_tokens(&self.to_input, path)
+        } else {
+            expand_path_tokens_escaped(&self.to_input, path)
+        }
+    }
+
+    fn accepted_substitution(&self, content: &str, path: &str) -> String {
+        let replaced = self.substitute(content, path);
+        match self.rejected_hunks_for(path) {
+            Some(rejected) => apply_hunks(content, &replaced, rejected),
+            None => replaced,
+        }
+    }
+
+    pub fn write_copy(&self, path: &str) -> io::Result<PathBuf> {
+        if self.read_only {
+            return Err(io::Error::other("read-only mode"));
+        }
+        if self.is_hex_only(path) {
+            return Err(io::Error::other("binary files can only be previewed"));
         }
+        let content = fs::read_to_string(path)?;
+        let target = copy_target(path);
+        fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&target)
+            .and_then(|mut file| {
+                io::Write::write_all(
+                    &mut file,
+                    self.accepted_substitution(&content, path).as_bytes(),
+                )
+            })?;
+        Ok(target)
     }
 
-    fn apply_substitution(&self, path: &str) -> io::Result<()> {
+    fn apply_substitution(&self, path: &str) -> io::Result<Option<(Vec<usize>, UndoFile)>> {
+        if self.read_only {
+            return Err(io::Error::other("read-only mode"));
+        }
+        if !self.has_substitution() {
+            return Ok(None);
+        }
+        if self.is_hex_only(path) {
+            return Err(io::Error::other("binary files can only be previewed"));
+        }
+        if !self.follow_symlinks && fs::symlink_metadata(path)?.file_type().is_symlink() {
+            return Err(io::Error::other(
+                "refusing to write through a symlink (set behavior.follow_symlinks to allow)",
+            ));
+        }
         let content = fs::read_to_string(path)?;
-        let replaced = apply_substitution_partial(&content, &self.from_input, &self.to_input);
-        fs::write(path, replaced)?;
+        if let Some(err) = self
+            .patch_for(path)
+            .and_then(|patch| apply_patch(&content, patch).err())
+        {
+            return Err(io::Error::other(err));
+        }
+        let replaced = self.accepted_substitution(&content, path);
+        if replaced == content {
+            return Ok(None);
+        }
+        fs::write(path, &replaced)?;
+        self.recent_writes.record(Path::new(path));
+        let lines = match self.from_regex() {
+            Some(re)
+                if self.rules.is_empty()
+                    && self.patch_for(path).is_none()
+                    && self.rejected_hunks_for(path).is_none() =>
+            {
+                substitution_lines(&content, &re, &self.replacement_for(path), &self.options)
+            }
+            _ => changed_lines(&content, &replaced),
+        };
 
         {
             let mut cache = self.file_cache.write();
             cache.remove(path);
+            cache.contents.insert(path.to_string(), replaced.clone());
         }
 
-        {
-            let mut cache = self.filtered_files_cache.write();
-            *cache = None;
-        }
+        self.modified_files.write().insert(path.to_string());
 
-        Ok(())
+        *self.scan_cache.write() = ScanCache::default();
+
+        Ok(Some((
+            lines,
+            UndoFile {
+                path: path.to_string(),
+                original: content.into_bytes(),
+                written: replaced,
+            },
+        )))
     }
 
     pub fn spin(&mut self) {
@@ -0,0 +1,57 @@
+use std::path::PathBuf;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Args {
+    pub staged: bool,
+    pub read_only: bool,
+    pub report: Option<PathBuf>,
+    pub edits: Option<PathBuf>,
+    pub patch: Option<PathBuf>,
+    pub script: Option<PathBuf>,
+    pub pipe: Option<String>,
+    pub print_config: bool,
+    pub timing: bool,
+}
+
+impl Args {
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self, String> {
+        let mut parsed = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--staged" => parsed.staged = true,
+                "--read-only" => parsed.read_only = true,
+                "--print-config" => parsed.print_config = true,
+                "--timing" => parsed.timing = true,
+                "--report" => {
+                    let path = args.next().ok_or("--report requires a file path")?;
+                    parsed.report = Some(PathBuf::from(path));
+                }
+                "--edits" => {
+                    let path = args.next().ok_or("--edits requires a file path")?;
+                    parsed.edits = Some(PathBuf::from(path));
+                }
+                "--patch" => {
+                    let path = args.next().ok_or("--patch requires a file path")?;
+                    parsed.patch = Some(PathBuf::from(path));
+                }
+                "--script" => {
+                    let path = args.next().ok_or("--script requires a file path")?;
+                    parsed.script = Some(PathBuf::from(path));
+                }
+                "--pipe" => {
+                    let command = args.next().ok_or("--pipe requires a command")?;
+                    parsed.pipe = Some(command);
+                }
+                _ => return Err(format!("unknown argument: {}", arg)),
+            }
+        }
+
+        if parsed.staged && parsed.patch.is_some() {
+            return Err("--patch can't be combined with --staged".to_string());
+        }
+
+        Ok(parsed)
+    }
+}
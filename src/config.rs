@@ -1,28 +1,153 @@
-use serde::Deserialize;
-use std::fs;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+use toml_edit::{DocumentMut, Table};
 
-#[derive(Debug, Deserialize)]
+pub const DEFAULT_POLL_MS: u64 = 200;
+pub const MIN_POLL_MS: u64 = 16;
+pub const MAX_POLL_MS: u64 = 1000;
+pub const DEFAULT_PREVIEW_KB: usize = 512;
+pub const DEFAULT_TAB_WIDTH: usize = 4;
+pub const DEFAULT_MATCH_TIMEOUT_MS: u64 = 5000;
+pub const DEFAULT_DETECT_SAMPLE_BYTES: usize = 4096;
+pub const DEFAULT_MAX_FILES: usize = 50_000;
+
+#[derive(Debug, Default, Deserialize, Serialize)]
 pub struct Config {
     pub files: Option<FilesConfig>,
+    pub behavior: Option<BehaviorConfig>,
+    pub display: Option<DisplayConfig>,
+    pub performance: Option<PerformanceConfig>,
+    pub presets: Option<Vec<Preset>>,
+    pub theme: Option<ThemeConfig>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize, Serialize)]
 pub struct FilesConfig {
     pub glob_filter: Option<Vec<String>>,
+    pub detect_sample_bytes: Option<usize>,
+    pub max_files: Option<usize>,
+    pub text_extensions: Option<Vec<String>>,
+    pub binary_extensions: Option<Vec<String>>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct BehaviorConfig {
+    pub poll_ms: Option<u64>,
+    pub follow_symlinks: Option<bool>,
+    pub preview_kb: Option<usize>,
+    pub match_timeout_ms: Option<u64>,
+    pub mouse_capture: Option<bool>,
+    pub strip_trailing_whitespace: Option<bool>,
+    pub skip_applied: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct DisplayConfig {
+    pub tab_width: Option<usize>,
+    pub hex_preview: Option<bool>,
+    pub group_by_dir: Option<bool>,
+    pub emphasize_basename: Option<bool>,
+    pub wrap_diff: Option<bool>,
+    pub scroll_to_first_change: Option<bool>,
+    pub file_info: Option<bool>,
+    pub full_diff: Option<bool>,
+    pub timing: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct PerformanceConfig {
+    pub threads: Option<usize>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct ThemeConfig {
+    pub diff: Option<DiffThemeConfig>,
 }
 
-pub fn find_and_load_config() -> Option<Config> {
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct DiffThemeConfig {
+    pub removed_marker: Option<String>,
+    pub added_marker: Option<String>,
+    pub removed_color: Option<String>,
+    pub added_color: Option<String>,
+    pub hunk_headers: Option<bool>,
+    pub hunk_color: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Preset {
+    pub name: String,
+    pub from: String,
+    pub to: String,
+    pub filter: Option<String>,
+    pub content_filter: Option<String>,
+    pub literal: Option<bool>,
+    pub anchor_start: Option<bool>,
+    pub anchor_end: Option<bool>,
+    pub keep_indent: Option<bool>,
+    pub case_insensitive: Option<bool>,
+    pub multiline: Option<bool>,
+    pub dotall: Option<bool>,
+}
+
+pub fn find_and_load_config() -> Option<(PathBuf, Config)> {
     let current_dir = std::env::current_dir().ok()?;
+    find_config_from(&current_dir)
+}
 
-    for dir in current_dir.ancestors() {
+pub fn find_config_from(start: &Path) -> Option<(PathBuf, Config)> {
+    for dir in start.ancestors() {
         for name in &["ised.config.toml", ".ised.config.toml"] {
             let candidate = dir.join(name);
             if candidate.exists() {
                 let content = fs::read_to_string(&candidate).ok()?;
-                return toml::from_str(&content).ok();
+                return toml::from_str(&content)
+                    .ok()
+                    .map(|config| (candidate, config));
             }
         }
     }
 
     None
 }
+
+pub fn read_config(path: &Path) -> io::Result<Config> {
+    if !path.exists() {
+        return Ok(Config::default());
+    }
+    let content = fs::read_to_string(path)?;
+    toml::from_str(&content).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+pub fn edit_config(
+    path: &Path,
+    edit: impl FnOnce(&mut DocumentMut) -> io::Result<()>,
+) -> io::Result<()> {
+    let invalid =
+        |err: &dyn std::fmt::Display| io::Error::new(io::ErrorKind::InvalidData, err.to_string());
+    let content = if path.exists() {
+        fs::read_to_string(path)?
+    } else {
+        String::new()
+    };
+    toml::from_str::<Config>(&content).map_err(|err| invalid(&err))?;
+    let mut document = content
+        .parse::<DocumentMut>()
+        .map_err(|err| invalid(&err))?;
+
+    edit(&mut document)?;
+
+    let content = document.to_string();
+    toml::from_str::<Config>(&content).map_err(|err| invalid(&err))?;
+    fs::write(path, content)
+}
+
+pub fn preset_table(preset: &Preset) -> io::Result<Table> {
+    let content =
+        toml::to_string(preset).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    let document = content
+        .parse::<DocumentMut>()
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    Ok(document.as_table().clone())
+}
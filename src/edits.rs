@@ -0,0 +1,64 @@
+use regex::Regex;
+use serde::Serialize;
+use std::path::Path;
+use std::{fs, io};
+
+use crate::utils::{effective_pattern, expand_replacement, SubstitutionOptions};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Edit {
+    pub start_byte: usize,
+    pub end_byte: usize,
+    pub replacement: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileEdits {
+    pub path: String,
+    pub edits: Vec<Edit>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct EditSet {
+    pub files: Vec<FileEdits>,
+}
+
+impl EditSet {
+    pub fn edit_count(&self) -> usize {
+        self.files.iter().map(|file| file.edits.len()).sum()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    pub fn write_json(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_json())
+    }
+}
+
+pub fn substitution_edits(
+    content: &str,
+    from_pattern: &str,
+    to_replacement: &str,
+    options: &SubstitutionOptions,
+) -> Vec<Edit> {
+    let Ok(re) = Regex::new(&effective_pattern(from_pattern, options)) else {
+        return Vec::new();
+    };
+    re.captures_iter(content)
+        .filter_map(|caps| {
+            let matched = caps.get(0)?;
+            let replacement = if options.literal {
+                to_replacement.to_string()
+            } else {
+                expand_replacement(&caps, to_replacement)
+            };
+            (replacement != matched.as_str()).then(|| Edit {
+                start_byte: matched.start(),
+                end_byte: matched.end(),
+                replacement,
+            })
+        })
+        .collect()
+}
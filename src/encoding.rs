@@ -0,0 +1,120 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    Utf8Bom,
+    Utf16Le,
+    Utf16Be,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    Lf,
+    Crlf,
+    Mixed,
+    None,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileInfo {
+    pub encoding: Encoding,
+    pub line_ending: LineEnding,
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Encoding::Utf8 => "UTF-8",
+            Encoding::Utf8Bom => "UTF-8 with BOM",
+            Encoding::Utf16Le => "UTF-16LE",
+            Encoding::Utf16Be => "UTF-16BE",
+            Encoding::Unknown => "unknown encoding",
+        })
+    }
+}
+
+impl fmt::Display for LineEnding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            LineEnding::Lf => "LF",
+            LineEnding::Crlf => "CRLF",
+            LineEnding::Mixed => "mixed line endings",
+            LineEnding::None => "no line breaks",
+        })
+    }
+}
+
+impl FileInfo {
+    pub fn label(&self) -> String {
+        format!("{}, {}", self.encoding, self.line_ending)
+    }
+}
+
+fn line_ending(units: impl Iterator<Item = u16>) -> LineEnding {
+    let (mut lf, mut crlf) = (0, 0);
+    let mut previous = 0;
+    for unit in units {
+        if unit == u16::from(b'\n') {
+            if previous == u16::from(b'\r') {
+                crlf += 1;
+            } else {
+                lf += 1;
+            }
+        }
+        previous = unit;
+    }
+    match (lf, crlf) {
+        (0, 0) => LineEnding::None,
+        (_, 0) => LineEnding::Lf,
+        (0, _) => LineEnding::Crlf,
+        _ => LineEnding::Mixed,
+    }
+}
+
+fn utf16_units(bytes: &[u8], little_endian: bool) -> impl Iterator<Item = u16> + '_ {
+    bytes.chunks_exact(2).map(move |pair| {
+        if little_endian {
+            u16::from_le_bytes([pair[0], pair[1]])
+        } else {
+            u16::from_be_bytes([pair[0], pair[1]])
+        }
+    })
+}
+
+pub fn detect_file_info(bytes: &[u8]) -> FileInfo {
+    let bytes_ending = |body: &[u8]| line_ending(body.iter().map(|&b| u16::from(b)));
+
+    if let Some(body) = bytes.strip_prefix(b"\xEF\xBB\xBF") {
+        return FileInfo {
+            encoding: Encoding::Utf8Bom,
+            line_ending: bytes_ending(body),
+        };
+    }
+    if let Some(body) = bytes.strip_prefix(b"\xFF\xFE") {
+        return FileInfo {
+            encoding: Encoding::Utf16Le,
+            line_ending: line_ending(utf16_units(body, true)),
+        };
+    }
+    if let Some(body) = bytes.strip_prefix(b"\xFE\xFF") {
+        return FileInfo {
+            encoding: Encoding::Utf16Be,
+            line_ending: line_ending(utf16_units(body, false)),
+        };
+    }
+
+    let valid = match std::str::from_utf8(bytes) {
+        Ok(_) => true,
+        Err(err) => err.error_len().is_none(),
+    };
+    FileInfo {
+        encoding: if valid {
+            Encoding::Utf8
+        } else {
+            Encoding::Unknown
+        },
+        line_ending: bytes_ending(bytes),
+    }
+}
@@ -0,0 +1,113 @@
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+pub fn open_directory_command(dir: &Path) -> Command {
+    #[cfg(target_os = "macos")]
+    let mut command = Command::new("open");
+    #[cfg(target_os = "windows")]
+    let mut command = Command::new("explorer");
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut command = Command::new("xdg-open");
+
+    command
+        .arg(dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    command
+}
+
+pub fn open_directory(dir: &Path) -> io::Result<()> {
+    let mut child = open_directory_command(dir).spawn()?;
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+pub fn clipboard_command() -> Command {
+    #[cfg(target_os = "macos")]
+    let command = Command::new("pbcopy");
+    #[cfg(target_os = "windows")]
+    let command = Command::new("clip");
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let command = if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        Command::new("wl-copy")
+    } else {
+        let mut command = Command::new("xclip");
+        command.args(["-selection", "clipboard"]);
+        command
+    };
+    command
+}
+
+pub fn copy_to_clipboard(text: &str) -> io::Result<()> {
+    let mut child = clipboard_command()
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+pub fn shell_command(command_line: &str) -> Command {
+    #[cfg(target_os = "windows")]
+    let mut command = Command::new("cmd");
+    #[cfg(target_os = "windows")]
+    command.arg("/C");
+    #[cfg(not(target_os = "windows"))]
+    let mut command = Command::new("sh");
+    #[cfg(not(target_os = "windows"))]
+    command.arg("-c");
+
+    command.arg(command_line);
+    command
+}
+
+pub fn pager_command(pager: Option<&str>) -> Command {
+    let pager = pager.filter(|p| !p.trim().is_empty()).unwrap_or("less");
+    let mut command = shell_command(pager);
+    command.stdin(Stdio::piped());
+    command
+}
+
+pub fn page_text(text: &str) -> io::Result<()> {
+    let mut child = pager_command(std::env::var("PAGER").ok().as_deref()).spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // The pager closes its end when quit before reading everything.
+        let _ = stdin.write_all(text.as_bytes());
+    }
+    child.wait()?;
+    Ok(())
+}
+
+pub fn pipe_through(command_line: &str, input: &str) -> io::Result<String> {
+    let mut child = shell_command(command_line)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    let mut stdin = child.stdin.take();
+    let input = input.to_string();
+    let writer = std::thread::spawn(move || match stdin.as_mut() {
+        Some(stdin) => stdin.write_all(input.as_bytes()),
+        None => Ok(()),
+    });
+
+    let output = child.wait_with_output()?;
+    let _ = writer.join();
+
+    let mut text = String::from_utf8_lossy(&output.stdout).to_string();
+    text.push_str(&String::from_utf8_lossy(&output.stderr));
+    if !output.status.success() {
+        if !text.is_empty() && !text.ends_with('\n') {
+            text.push('\n');
+        }
+        text.push_str(&format!("({})", output.status));
+    }
+    Ok(text)
+}
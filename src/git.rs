@@ -0,0 +1,34 @@
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+pub fn staged_files(dir: &Path) -> io::Result<Vec<String>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args([
+            "diff",
+            "--cached",
+            "--name-only",
+            "--relative",
+            "--diff-filter=ACMR",
+        ])
+        .output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(io::Error::other(stderr.trim().to_string()));
+    }
+
+    let files: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| dir.join(line).display().to_string())
+        .collect();
+
+    if files.is_empty() {
+        return Err(io::Error::other("no files are staged"));
+    }
+
+    Ok(files)
+}
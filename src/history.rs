@@ -0,0 +1,43 @@
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct History {
+    entries: Vec<String>,
+}
+
+impl History {
+    pub fn push(&mut self, entry: &str) {
+        if entry.is_empty() {
+            return;
+        }
+        self.entries.retain(|existing| existing != entry);
+        self.entries.push(entry.to_string());
+    }
+
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    pub fn search(&self, query: &str, skip: usize) -> Option<&str> {
+        self.entries
+            .iter()
+            .rev()
+            .filter(|entry| entry.contains(query))
+            .nth(skip)
+            .map(String::as_str)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HistorySearch {
+    pub query: String,
+    pub skip: usize,
+}
+
+impl HistorySearch {
+    pub fn next(&mut self, history: &History) {
+        self.skip = if history.search(&self.query, self.skip + 1).is_some() {
+            self.skip + 1
+        } else {
+            0
+        };
+    }
+}
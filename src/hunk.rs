@@ -0,0 +1,249 @@
+use itertools::{EitherOrBoth, Itertools};
+use std::collections::HashSet;
+use std::ops::Range;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk {
+    pub pairs: Range<usize>,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffRow {
+    Line(usize),
+    Skipped(Range<usize>),
+}
+
+const SYNC_WINDOW: usize = 64;
+
+fn resync(
+    original: &[&str],
+    replaced: &[&str],
+    i: usize,
+    j: usize,
+    same: &impl Fn(&str, &str) -> bool,
+) -> Option<(usize, usize)> {
+    for distance in 1..=2 * SYNC_WINDOW {
+        let closest = (distance.saturating_sub(SYNC_WINDOW)..=distance.min(SYNC_WINDOW))
+            .map(|a| (a, distance - a))
+            .filter(|&(a, b)| {
+                i + a < original.len()
+                    && j + b < replaced.len()
+                    && same(original[i + a], replaced[j + b])
+            })
+            .min_by_key(|&(a, b)| a.abs_diff(b));
+        if closest.is_some() {
+            return closest;
+        }
+    }
+    None
+}
+
+pub fn align_lines<'a>(
+    original: &[&'a str],
+    replaced: &[&'a str],
+    same: impl Fn(&str, &str) -> bool,
+) -> Vec<EitherOrBoth<&'a str, &'a str>> {
+    let mut pairs = Vec::with_capacity(original.len().max(replaced.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < original.len() || j < replaced.len() {
+        if i < original.len() && j < replaced.len() && same(original[i], replaced[j]) {
+            pairs.push(EitherOrBoth::Both(original[i], replaced[j]));
+            i += 1;
+            j += 1;
+            continue;
+        }
+
+        let (removed, added) = resync(original, replaced, i, j, &same).unwrap_or_else(|| {
+            let (left, right) = (original.len() - i, replaced.len() - j);
+            match left.min(right).min(SYNC_WINDOW) {
+                0 => (left, right),
+                chunk => (chunk, chunk),
+            }
+        });
+        pairs.extend(
+            original[i..i + removed]
+                .iter()
+                .copied()
+                .zip_longest(replaced[j..j + added].iter().copied()),
+        );
+        i += removed;
+        j += added;
+    }
+    pairs
+}
+
+fn line_pairs<'a>(original: &'a str, replaced: &'a str) -> Vec<EitherOrBoth<&'a str, &'a str>> {
+    align_lines(
+        &original.split_inclusive('\n').collect::<Vec<_>>(),
+        &replaced.split_inclusive('\n').collect::<Vec<_>>(),
+        |l, r| l.trim_end_matches('\n') == r.trim_end_matches('\n'),
+    )
+}
+
+fn is_unchanged(pair: &EitherOrBoth<&str, &str>) -> bool {
+    matches!(pair, EitherOrBoth::Both(l, r) if l.trim_end_matches('\n') == r.trim_end_matches('\n'))
+}
+
+pub fn hunks(original: &str, replaced: &str, headers: bool) -> Vec<Hunk> {
+    let pairs = line_pairs(original, replaced);
+
+    let mut hunks = Vec::new();
+    let mut line = 0;
+    let mut i = 0;
+    while i < pairs.len() {
+        if is_unchanged(&pairs[i]) {
+            line += 1;
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        let hunk_line = line;
+        if headers {
+            line += 1;
+        }
+        while i < pairs.len() && !is_unchanged(&pairs[i]) {
+            line += usize::from(pairs[i].has_left()) + usize::from(pairs[i].has_right());
+            i += 1;
+        }
+        hunks.push(Hunk {
+            pairs: start..i,
+            line: hunk_line,
+        });
+    }
+    hunks
+}
+
+pub fn apply_hunks(original: &str, replaced: &str, rejected: &HashSet<usize>) -> String {
+    let rejected_pairs = hunks(original, replaced, false)
+        .into_iter()
+        .enumerate()
+        .filter(|(index, _)| rejected.contains(index))
+        .flat_map(|(_, hunk)| hunk.pairs)
+        .collect::<HashSet<_>>();
+
+    let mut output = String::new();
+    for (i, pair) in line_pairs(original, replaced).into_iter().enumerate() {
+        let line = if rejected_pairs.contains(&i) {
+            pair.left()
+        } else {
+            pair.right()
+        };
+        if let Some(line) = line {
+            if !output.is_empty() && !output.ends_with('\n') {
+                output.push('\n');
+            }
+            output.push_str(line);
+        }
+    }
+    output
+}
+
+pub fn change_lines(original: &str, replaced: &str, headers: bool) -> Vec<usize> {
+    let pairs = line_pairs(original, replaced);
+
+    let mut changes = Vec::new();
+    let mut line = 0;
+    for (i, pair) in pairs.iter().enumerate() {
+        if is_unchanged(pair) {
+            line += 1;
+            continue;
+        }
+        if headers && (i == 0 || is_unchanged(&pairs[i - 1])) {
+            line += 1;
+        }
+        changes.push(line);
+        line += usize::from(pair.has_left()) + usize::from(pair.has_right());
+    }
+    changes
+}
+
+pub fn step_wrapping(current: Option<usize>, len: usize, forward: bool) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+    Some(match (current.filter(|&i| i < len), forward) {
+        (None, true) => 0,
+        (None, false) => len - 1,
+        (Some(i), true) => (i + 1) % len,
+        (Some(i), false) => (i + len - 1) % len,
+    })
+}
+
+pub fn diff_row(original: &str, replaced: &str, headers: bool, line: usize) -> usize {
+    let pairs = line_pairs(original, replaced);
+
+    let mut row = 0;
+    let mut original_line = 0;
+    for (i, pair) in pairs.iter().enumerate() {
+        if original_line == line && pair.has_left() {
+            break;
+        }
+        original_line += usize::from(pair.has_left());
+        if is_unchanged(pair) {
+            row += 1;
+            continue;
+        }
+        if headers && (i == 0 || is_unchanged(&pairs[i - 1])) {
+            row += 1;
+        }
+        row += usize::from(pair.has_left()) + usize::from(pair.has_right());
+    }
+    row
+}
+
+pub fn changed_rows(original: &str, replaced: &str, headers: bool) -> Vec<bool> {
+    let pairs = line_pairs(original, replaced);
+
+    let mut rows = Vec::with_capacity(pairs.len());
+    for (i, pair) in pairs.iter().enumerate() {
+        if is_unchanged(pair) {
+            rows.push(false);
+            continue;
+        }
+        if headers && (i == 0 || is_unchanged(&pairs[i - 1])) {
+            rows.push(true);
+        }
+        rows.extend(std::iter::repeat_n(
+            true,
+            usize::from(pair.has_left()) + usize::from(pair.has_right()),
+        ));
+    }
+    rows
+}
+
+pub fn collapse_rows(changed: &[bool], context: usize) -> Vec<DiffRow> {
+    let near_change = |row: usize| {
+        changed[row.saturating_sub(context)..(row + context + 1).min(changed.len())].contains(&true)
+    };
+
+    let mut rows = Vec::new();
+    let mut row = 0;
+    while row < changed.len() {
+        if near_change(row) {
+            rows.push(DiffRow::Line(row));
+            row += 1;
+            continue;
+        }
+        let start = row;
+        while row < changed.len() && !near_change(row) {
+            row += 1;
+        }
+        if row - start == 1 {
+            rows.push(DiffRow::Line(start));
+        } else {
+            rows.push(DiffRow::Skipped(start..row));
+        }
+    }
+    rows
+}
+
+pub fn collapsed_row(rows: &[DiffRow], row: usize) -> usize {
+    rows.iter()
+        .position(|r| match r {
+            DiffRow::Line(line) => *line == row,
+            DiffRow::Skipped(range) => range.contains(&row),
+        })
+        .unwrap_or(row)
+}
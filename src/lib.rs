@@ -1,4 +1,20 @@
 pub mod app;
+pub mod cli;
 pub mod config;
+pub mod edits;
+pub mod encoding;
+pub mod external;
+pub mod git;
+pub mod history;
+pub mod hunk;
+pub mod patch;
+pub mod report;
+pub mod results;
+pub mod script;
+pub mod size;
+pub mod template;
+pub mod theme;
+pub mod tree;
 pub mod ui;
 pub mod utils;
+pub mod watch;
@@ -5,54 +5,158 @@ use crossterm::{
 };
 use ratatui::backend::CrosstermBackend;
 use ratatui::Terminal;
-use std::io;
+use std::path::Path;
 use std::sync::mpsc;
 use std::time::Duration;
+use std::{fs, io};
 
-use crate::app::App;
+use ised::app::App;
+use ised::cli::Args;
+use ised::external::page_text;
+use ised::patch::parse_patch;
+use ised::script::parse_script;
+use ised::{git, ui};
 
-mod app;
-mod config;
-mod ui;
-mod utils;
+type Loaded = (Vec<String>, Option<usize>, Option<Duration>);
+
+fn spawn_loader(app: &App, tx: mpsc::Sender<Loaded>) {
+    let mut app_clone = app.clone();
+    std::thread::spawn(move || {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            app_clone.load_files().await;
+            let _ = tx.send((
+                app_clone.files,
+                app_clone.load_limit_hit,
+                app_clone.load_duration,
+            ));
+        });
+    });
+}
 
 #[tokio::main]
 async fn main() -> io::Result<()> {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("ised: {}", err);
+            std::process::exit(2);
+        }
+    };
+
+    if args.print_config {
+        let app = App::new();
+        match &app.config_path {
+            Some(path) => println!("# Loaded from {}", path.display()),
+            None => println!("# No ised.config.toml found; showing the defaults"),
+        }
+        match toml::to_string(&app.effective_config()) {
+            Ok(config) => print!("{}", config),
+            Err(err) => {
+                eprintln!("ised: --print-config: {}", err);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    let staged_files = if args.staged {
+        match git::staged_files(Path::new(".")) {
+            Ok(files) => Some(files),
+            Err(err) => {
+                eprintln!("ised: --staged: {}", err);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        None
+    };
+
+    let rules = match &args.script {
+        Some(path) => match fs::read_to_string(path)
+            .map_err(|err| err.to_string())
+            .and_then(|script| parse_script(&script))
+        {
+            Ok(rules) => rules,
+            Err(err) => {
+                eprintln!("ised: --script {}: {}", path.display(), err);
+                std::process::exit(2);
+            }
+        },
+        None => Vec::new(),
+    };
+
+    let patches = match &args.patch {
+        Some(path) => match fs::read_to_string(path)
+            .map_err(|err| err.to_string())
+            .and_then(|patch| parse_patch(&patch))
+        {
+            Ok(patches) => patches,
+            Err(err) => {
+                eprintln!("ised: --patch {}: {}", path.display(), err);
+                std::process::exit(2);
+            }
+        },
+        None => Vec::new(),
+    };
+    let staged_files = staged_files.or_else(|| {
+        (!patches.is_empty()).then(|| {
+            patches
+                .iter()
+                .map(|patch| Path::new(".").join(&patch.path).display().to_string())
+                .collect()
+        })
+    });
+
+    let mut app = App::new();
+    app.staged_files = staged_files;
+    app.patches = patches;
+    app.read_only = args.read_only;
+    app.show_timing |= args.timing;
+    app.report_path = args.report;
+    app.edits_path = args.edits;
+    app.rules = rules;
+    app.pipe_command = args.pipe;
+    app.status_message = app
+        .config_path
+        .as_ref()
+        .map(|path| format!("Loaded config from {}", path.display()));
+
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(stdout, EnterAlternateScreen)?;
+    if app.mouse_capture {
+        execute!(stdout, EnableMouseCapture)?;
+    }
+    let mut mouse_captured = app.mouse_capture;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let mut app = App::new();
-
     let (tx, rx) = mpsc::channel();
-    let mut app_clone = app.clone();
-    std::thread::spawn(move || {
-        let rt = tokio::runtime::Runtime::new().unwrap();
-        rt.block_on(async {
-            app_clone.load_files().await;
-            let _ = tx.send(app_clone);
-        });
-    });
+    spawn_loader(&app, tx.clone());
 
     let res: io::Result<()> = loop {
-        if let Ok(new_app) = rx.try_recv() {
-            app = new_app;
+        if let Ok((files, limit_hit, duration)) = rx.try_recv() {
+            app.set_loaded_files(files);
+            app.load_limit_hit = limit_hit;
+            app.load_duration = duration;
         }
 
         let filtered_files = app.filter_files();
-        let file_content = if !app.is_loading {
-            filtered_files
-                .get(app.selected)
-                .and_then(|file| std::fs::read_to_string(file).ok())
+        app.clamp_selection(&filtered_files);
+        app.follow_selection(&filtered_files);
+        app.note_filter_change(&filtered_files);
+        app.update_from_error();
+        let preview = if !app.is_loading {
+            app.selected_file(&filtered_files)
+                .and_then(|file| app.preview(&file))
         } else {
             None
         };
 
-        terminal.draw(|f| ui::draw(f, &mut app, &filtered_files, file_content))?;
+        terminal.draw(|f| ui::draw(f, &mut app, &filtered_files, preview))?;
 
-        if crossterm::event::poll(Duration::from_millis(200))? {
+        if crossterm::event::poll(app.poll_interval)? {
             if let Event::Key(key) = crossterm::event::read()? {
                 if app.handle_key_event(key, &filtered_files)? {
                     break Ok(());
@@ -60,6 +164,31 @@ async fn main() -> io::Result<()> {
             }
         }
 
+        if app.mouse_capture != mouse_captured {
+            if app.mouse_capture {
+                execute!(terminal.backend_mut(), EnableMouseCapture)?;
+            } else {
+                execute!(terminal.backend_mut(), DisableMouseCapture)?;
+            }
+            mouse_captured = app.mouse_capture;
+        }
+
+        if let Some(text) = app.pager_request.take() {
+            disable_raw_mode()?;
+            execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+            if let Err(err) = page_text(&text) {
+                app.status_message = Some(format!("Failed to run pager: {}", err));
+            }
+            execute!(terminal.backend_mut(), EnterAlternateScreen)?;
+            enable_raw_mode()?;
+            terminal.clear()?;
+        }
+
+        if app.rescan_requested {
+            app.rescan_requested = false;
+            spawn_loader(&app, tx.clone());
+        }
+
         app.spin();
     };
 
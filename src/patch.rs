@@ -0,0 +1,188 @@
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatchLine {
+    Context(String),
+    Remove(String),
+    Add(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchHunk {
+    pub old_start: usize,
+    pub lines: Vec<PatchLine>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilePatch {
+    pub path: String,
+    pub hunks: Vec<PatchHunk>,
+}
+
+impl PatchHunk {
+    fn old_lines(&self) -> Vec<&str> {
+        self.lines
+            .iter()
+            .filter_map(|line| match line {
+                PatchLine::Context(text) | PatchLine::Remove(text) => Some(text.as_str()),
+                PatchLine::Add(_) => None,
+            })
+            .collect()
+    }
+
+    fn new_lines(&self) -> Vec<&str> {
+        self.lines
+            .iter()
+            .filter_map(|line| match line {
+                PatchLine::Context(text) | PatchLine::Add(text) => Some(text.as_str()),
+                PatchLine::Remove(_) => None,
+            })
+            .collect()
+    }
+
+    fn start_index(&self) -> usize {
+        if self.old_lines().is_empty() {
+            self.old_start
+        } else {
+            self.old_start.saturating_sub(1)
+        }
+    }
+}
+
+fn patch_path(header: &str) -> String {
+    let path = header.split('\t').next().unwrap_or(header).trim_end();
+    path.strip_prefix("b/").unwrap_or(path).to_string()
+}
+
+fn hunk_range(range: &str) -> Result<(usize, usize), String> {
+    let (start, count) = range.split_once(',').unwrap_or((range, "1"));
+    match (start.parse(), count.parse()) {
+        (Ok(start), Ok(count)) => Ok((start, count)),
+        _ => Err(format!("invalid hunk range '{}'", range)),
+    }
+}
+
+fn hunk_header(line: &str) -> Result<(usize, usize, usize), String> {
+    let ranges = line
+        .strip_prefix("@@ ")
+        .and_then(|rest| rest.split_once(" @@"))
+        .map(|(ranges, _)| ranges)
+        .ok_or_else(|| format!("invalid hunk header '{}'", line))?;
+    let (old, new) = ranges
+        .split_once(' ')
+        .ok_or_else(|| format!("invalid hunk header '{}'", line))?;
+    let (old_start, old_count) = hunk_range(old.strip_prefix('-').unwrap_or(old))?;
+    let (_, new_count) = hunk_range(new.strip_prefix('+').unwrap_or(new))?;
+    Ok((old_start, old_count, new_count))
+}
+
+pub fn parse_patch(text: &str) -> Result<Vec<FilePatch>, String> {
+    let mut patches: Vec<FilePatch> = Vec::new();
+    let mut lines = text.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        if let Some(header) = line.strip_prefix("+++ ") {
+            let path = patch_path(header);
+            if path == "/dev/null" {
+                return Err("deleting files isn't supported".to_string());
+            }
+            patches.push(FilePatch {
+                path,
+                hunks: Vec::new(),
+            });
+            continue;
+        }
+        if !line.starts_with("@@ ") {
+            continue;
+        }
+
+        let patch = patches
+            .last_mut()
+            .ok_or("hunk found before a '+++' file header")?;
+        let (old_start, mut old_left, mut new_left) = hunk_header(line)?;
+        let mut hunk = PatchHunk {
+            old_start,
+            lines: Vec::new(),
+        };
+        while old_left > 0 || new_left > 0 {
+            let line = lines.next().ok_or_else(|| {
+                format!(
+                    "hunk {} of {} ends early",
+                    patch.hunks.len() + 1,
+                    patch.path
+                )
+            })?;
+            let (kind, text) = line.split_at(line.len().min(1));
+            let text = text.to_string();
+            match kind {
+                " " | "" => {
+                    hunk.lines.push(PatchLine::Context(text));
+                    old_left = old_left.saturating_sub(1);
+                    new_left = new_left.saturating_sub(1);
+                }
+                "-" => {
+                    hunk.lines.push(PatchLine::Remove(text));
+                    old_left = old_left.saturating_sub(1);
+                }
+                "+" => {
+                    hunk.lines.push(PatchLine::Add(text));
+                    new_left = new_left.saturating_sub(1);
+                }
+                "\\" => {}
+                _ => return Err(format!("unexpected line in hunk: '{}'", line)),
+            }
+        }
+        while lines.peek().is_some_and(|line| line.starts_with('\\')) {
+            lines.next();
+        }
+        patch.hunks.push(hunk);
+    }
+
+    if patches.is_empty() {
+        return Err("no file patches found".to_string());
+    }
+    Ok(patches)
+}
+
+fn block_matches(lines: &[&str], at: usize, block: &[&str]) -> bool {
+    at + block.len() <= lines.len()
+        && lines[at..at + block.len()]
+            .iter()
+            .zip(block)
+            .all(|(line, expected)| line.trim_end_matches(['\r', '\n']) == *expected)
+}
+
+pub fn apply_patch(content: &str, patch: &FilePatch) -> Result<String, String> {
+    let lines = content.split_inclusive('\n').collect::<Vec<_>>();
+    let newline = if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+
+    let mut output = String::with_capacity(content.len());
+    let mut cursor = 0;
+    let mut offset = 0isize;
+    for (index, hunk) in patch.hunks.iter().enumerate() {
+        let old = hunk.old_lines();
+        let expected = (hunk.start_index() as isize + offset).max(0) as usize;
+        let at = (cursor..=lines.len())
+            .filter(|&at| block_matches(&lines, at, &old))
+            .min_by_key(|&at| at.abs_diff(expected))
+            .ok_or_else(|| format!("hunk {} doesn't match {}", index + 1, patch.path))?;
+
+        output.extend(lines[cursor..at].iter().copied());
+        for line in hunk.new_lines() {
+            output.push_str(line);
+            output.push_str(newline);
+        }
+        offset = at as isize - hunk.start_index() as isize;
+        cursor = at + old.len();
+    }
+    output.extend(lines[cursor..].iter().copied());
+
+    if cursor == lines.len() && !content.is_empty() && !content.ends_with('\n') {
+        if let Some(stripped) = output.strip_suffix(newline) {
+            output.truncate(stripped.len());
+        }
+    }
+    Ok(output)
+}
@@ -0,0 +1,109 @@
+use serde::Serialize;
+use std::path::Path;
+use std::{fs, io};
+
+pub fn format_lines(lines: &[usize]) -> Option<String> {
+    (!lines.is_empty()).then(|| {
+        lines
+            .iter()
+            .map(|line| format!("L{}", line))
+            .collect::<Vec<_>>()
+            .join(",")
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    Changed,
+    Unchanged,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileResult {
+    pub path: String,
+    pub outcome: Outcome,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub lines: Vec<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ApplyReport {
+    pub changed: usize,
+    pub unchanged: usize,
+    pub errors: usize,
+    pub files: Vec<FileResult>,
+}
+
+impl ApplyReport {
+    pub fn push(&mut self, path: &str, result: io::Result<Option<Vec<usize>>>) {
+        let (outcome, lines, error) = match result {
+            Ok(Some(lines)) => {
+                self.changed += 1;
+                (Outcome::Changed, lines, None)
+            }
+            Ok(None) => {
+                self.unchanged += 1;
+                (Outcome::Unchanged, Vec::new(), None)
+            }
+            Err(err) => {
+                self.errors += 1;
+                (Outcome::Error, Vec::new(), Some(err.to_string()))
+            }
+        };
+
+        self.files.push(FileResult {
+            path: path.to_string(),
+            outcome,
+            lines,
+            error,
+        });
+    }
+
+    pub fn changed_paths(&self) -> Vec<&str> {
+        self.files
+            .iter()
+            .filter(|file| file.outcome == Outcome::Changed)
+            .map(|file| file.path.as_str())
+            .collect()
+    }
+
+    pub fn paths(&self) -> Vec<&str> {
+        self.files.iter().map(|file| file.path.as_str()).collect()
+    }
+
+    pub fn line_log(&self) -> Option<String> {
+        let entries = self
+            .files
+            .iter()
+            .filter(|file| file.outcome == Outcome::Changed)
+            .map(|file| match format_lines(&file.lines) {
+                Some(lines) => format!("{} {}", file.path, lines),
+                None => file.path.clone(),
+            })
+            .collect::<Vec<_>>();
+        if entries.is_empty() {
+            None
+        } else {
+            Some(entries.join("; "))
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{} changed, {} unchanged, {} errors",
+            self.changed, self.unchanged, self.errors
+        )
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    pub fn write_json(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_json())
+    }
+}
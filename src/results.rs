@@ -0,0 +1,50 @@
+use regex::Regex;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchResult {
+    pub path: String,
+    pub line: usize,
+    pub context: Vec<(usize, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResultsView {
+    pub results: Vec<MatchResult>,
+    pub selected: usize,
+}
+
+impl ResultsView {
+    pub fn file_count(&self) -> usize {
+        let mut paths = self
+            .results
+            .iter()
+            .map(|r| r.path.as_str())
+            .collect::<Vec<_>>();
+        paths.dedup();
+        paths.len()
+    }
+}
+
+pub fn match_results(path: &str, content: &str, re: &Regex, context: usize) -> Vec<MatchResult> {
+    let lines = content.lines().collect::<Vec<_>>();
+    let line_starts = std::iter::once(0)
+        .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+        .collect::<Vec<_>>();
+
+    let mut match_lines = re
+        .find_iter(content)
+        .map(|m| line_starts.partition_point(|&start| start <= m.start()) - 1)
+        .collect::<Vec<_>>();
+    match_lines.dedup();
+
+    match_lines
+        .into_iter()
+        .map(|line| MatchResult {
+            path: path.to_string(),
+            line,
+            context: (line.saturating_sub(context)..(line + context + 1).min(lines.len()))
+                .map(|i| (i, lines[i].to_string()))
+                .collect(),
+        })
+        .collect()
+}
@@ -0,0 +1,184 @@
+use globset::{Glob, GlobMatcher};
+use regex::Regex;
+use std::fmt;
+
+#[derive(Debug, Clone)]
+pub struct Scope {
+    glob: String,
+    matcher: GlobMatcher,
+}
+
+impl Scope {
+    pub fn new(glob: &str) -> Result<Self, globset::Error> {
+        let glob = glob.strip_prefix("./").unwrap_or(glob);
+        Ok(Self {
+            glob: glob.to_string(),
+            matcher: Glob::new(glob)?.compile_matcher(),
+        })
+    }
+
+    pub fn glob(&self) -> &str {
+        &self.glob
+    }
+
+    pub fn is_match(&self, path: &str) -> bool {
+        self.matcher
+            .is_match(path.strip_prefix("./").unwrap_or(path))
+    }
+}
+
+impl PartialEq for Scope {
+    fn eq(&self, other: &Self) -> bool {
+        self.glob == other.glob
+    }
+}
+
+impl Eq for Scope {}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.glob)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub from: String,
+    pub to: String,
+    pub global: bool,
+    pub case_insensitive: bool,
+    pub scope: Option<Scope>,
+}
+
+impl Rule {
+    pub fn pattern(&self) -> String {
+        if self.case_insensitive {
+            format!("(?i){}", self.from)
+        } else {
+            self.from.clone()
+        }
+    }
+
+    pub fn applies_to(&self, path: &str) -> bool {
+        self.scope.as_ref().is_none_or(|scope| scope.is_match(path))
+    }
+
+    pub fn apply(&self, content: &str) -> String {
+        match Regex::new(&self.pattern()) {
+            Ok(re) => self.apply_regex(&re, content),
+            Err(_) => content.to_string(),
+        }
+    }
+
+    pub fn apply_regex(&self, re: &Regex, content: &str) -> String {
+        if self.global {
+            return re.replace_all(content, self.to.as_str()).to_string();
+        }
+
+        content
+            .split_inclusive('\n')
+            .map(|line| re.replacen(line, 1, self.to.as_str()))
+            .collect()
+    }
+}
+
+pub fn parse_script(script: &str) -> Result<Vec<Rule>, String> {
+    script
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(number, line)| parse_rule(line).map_err(|err| format!("line {}: {}", number, err)))
+        .collect()
+}
+
+fn parse_rule(line: &str) -> Result<Rule, String> {
+    let Some(scoped) = line.strip_prefix('[') else {
+        return parse_substitution(line);
+    };
+    let (scope, rest) = scoped.split_once(']').ok_or("unterminated [glob] scope")?;
+    let scope = scope.trim();
+    let scope = Scope::new(scope).map_err(|err| format!("invalid scope '{}': {}", scope, err))?;
+    Ok(Rule {
+        scope: Some(scope),
+        ..parse_substitution(rest.trim_start())?
+    })
+}
+
+fn parse_substitution(line: &str) -> Result<Rule, String> {
+    let mut chars = line.chars();
+    if chars.next() != Some('s') {
+        return Err("expected s/from/to/flags".to_string());
+    }
+    let delimiter = chars
+        .next()
+        .filter(|c| !c.is_alphanumeric() && *c != '\\' && *c != '\n')
+        .ok_or("missing or invalid delimiter")?;
+
+    let mut parts = vec![String::new()];
+    let mut escaped = false;
+    for c in chars {
+        if escaped {
+            parts.last_mut().unwrap().extend(['\\', c]);
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == delimiter && parts.len() < 3 {
+            parts.push(String::new());
+        } else {
+            parts.last_mut().unwrap().push(c);
+        }
+    }
+    if escaped {
+        parts.last_mut().unwrap().push('\\');
+    }
+    if parts.len() < 3 {
+        return Err("unterminated substitution".to_string());
+    }
+
+    let mut rule = Rule {
+        from: unescape_pattern(&parts[0], delimiter),
+        to: convert_replacement(&parts[1], delimiter),
+        global: false,
+        case_insensitive: false,
+        scope: None,
+    };
+    for flag in parts[2].chars() {
+        match flag {
+            'g' => rule.global = true,
+            'i' | 'I' => rule.case_insensitive = true,
+            _ => return Err(format!("unknown flag '{}'", flag)),
+        }
+    }
+
+    Ok(rule)
+}
+
+fn unescape_pattern(pattern: &str, delimiter: char) -> String {
+    pattern.replace(
+        &format!("\\{}", delimiter),
+        &regex::escape(&delimiter.to_string()),
+    )
+}
+
+fn convert_replacement(replacement: &str, delimiter: char) -> String {
+    let mut converted = String::new();
+    let mut chars = replacement.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(d) if d.is_ascii_digit() => converted.push_str(&format!("${{{}}}", d)),
+                Some('n') => converted.push('\n'),
+                Some('t') => converted.push('\t'),
+                Some('$') => converted.push_str("$$"),
+                Some(d) if d == delimiter || d == '&' || d == '\\' => converted.push(d),
+                Some(other) => converted.extend(['\\', other]),
+                None => converted.push('\\'),
+            },
+            '&' => converted.push_str("${0}"),
+            '$' => converted.push_str("$$"),
+            _ => converted.push(c),
+        }
+    }
+    converted
+}
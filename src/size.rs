@@ -0,0 +1,68 @@
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SizeRange {
+    pub min: Option<u64>,
+    pub max: Option<u64>,
+}
+
+impl SizeRange {
+    pub fn contains(&self, size: u64) -> bool {
+        self.min.is_none_or(|min| size >= min) && self.max.is_none_or(|max| size <= max)
+    }
+}
+
+pub fn parse_size(text: &str) -> Option<u64> {
+    let text = text.trim().to_ascii_lowercase();
+    let text = text.strip_suffix('b').unwrap_or(&text);
+    let (number, unit) = match text.char_indices().last()? {
+        (i, 'k') => (&text[..i], 1 << 10),
+        (i, 'm') => (&text[..i], 1 << 20),
+        (i, 'g') => (&text[..i], 1 << 30),
+        _ => (text, 1),
+    };
+    number.trim().parse::<u64>().ok()?.checked_mul(unit)
+}
+
+pub fn parse_size_filter(pattern: &str) -> Option<SizeRange> {
+    let spec = pattern.strip_prefix("size:")?.trim();
+
+    if let Some((min, max)) = spec.split_once("..") {
+        let bound = |text: &str| match text.trim() {
+            "" => Some(None),
+            text => parse_size(text).map(Some),
+        };
+        return Some(SizeRange {
+            min: bound(min)?,
+            max: bound(max)?,
+        });
+    }
+    if let Some(min) = spec.strip_prefix(">=") {
+        return Some(SizeRange {
+            min: Some(parse_size(min)?),
+            max: None,
+        });
+    }
+    if let Some(max) = spec.strip_prefix("<=") {
+        return Some(SizeRange {
+            min: None,
+            max: Some(parse_size(max)?),
+        });
+    }
+    if let Some(min) = spec.strip_prefix('>') {
+        return Some(SizeRange {
+            min: Some(parse_size(min)?.checked_add(1)?),
+            max: None,
+        });
+    }
+    if let Some(max) = spec.strip_prefix('<') {
+        return Some(SizeRange {
+            min: None,
+            max: Some(parse_size(max)?.checked_sub(1)?),
+        });
+    }
+
+    let exact = parse_size(spec)?;
+    Some(SizeRange {
+        min: Some(exact),
+        max: Some(exact),
+    })
+}
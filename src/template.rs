@@ -0,0 +1,82 @@
+use std::collections::HashSet;
+
+fn escape_replacement(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('$', "$$")
+}
+
+fn parse_placeholder(
+    spec: &str,
+    names: &mut HashSet<String>,
+    next_group: &mut usize,
+) -> Result<(String, String), String> {
+    if let Some((old, new)) = spec.split_once("=>") {
+        return Ok((regex::escape(old), escape_replacement(new)));
+    }
+
+    let (name, pattern) = spec.split_once(':').unwrap_or((spec, r"\w+"));
+    if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        return Err(format!("invalid placeholder name '{}'", name));
+    }
+    if !names.insert(name.to_string()) {
+        return Err(format!("placeholder '{}' is used twice", name));
+    }
+    let inner_groups = regex::Regex::new(pattern)
+        .map_err(|err| format!("placeholder '{}': {}", name, err))?
+        .captures_len()
+        - 1;
+
+    let group = *next_group;
+    *next_group += 1 + inner_groups;
+    Ok((format!("({})", pattern), format!("${{{}}}", group)))
+}
+
+pub fn parse_template(template: &str) -> Result<(String, String), String> {
+    let mut from = String::new();
+    let mut to = String::new();
+    let mut names = HashSet::new();
+    let mut next_group = 1;
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                from.push_str(r"\{");
+                to.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                from.push_str(r"\}");
+                to.push('}');
+            }
+            '{' => {
+                let mut spec = String::new();
+                let mut depth = 0;
+                loop {
+                    match chars.next() {
+                        Some('}') if depth == 0 => break,
+                        Some(c) => {
+                            match c {
+                                '{' => depth += 1,
+                                '}' => depth -= 1,
+                                _ => {}
+                            }
+                            spec.push(c);
+                        }
+                        None => return Err("unterminated placeholder".to_string()),
+                    }
+                }
+                let (pattern, replacement) = parse_placeholder(&spec, &mut names, &mut next_group)?;
+                from.push_str(&pattern);
+                to.push_str(&replacement);
+            }
+            '}' => return Err("unmatched '}'".to_string()),
+            _ => {
+                from.push_str(&regex::escape(&c.to_string()));
+                to.push_str(&escape_replacement(&c.to_string()));
+            }
+        }
+    }
+
+    Ok((from, to))
+}
@@ -0,0 +1,74 @@
+use ratatui::style::{Color, Modifier, Style};
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use crate::config::DiffThemeConfig;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffTheme {
+    pub removed_marker: String,
+    pub added_marker: String,
+    pub removed_color: Color,
+    pub added_color: Color,
+    pub hunk_headers: bool,
+    pub hunk_color: Color,
+}
+
+impl Default for DiffTheme {
+    fn default() -> Self {
+        Self {
+            removed_marker: "- ".to_string(),
+            added_marker: "+ ".to_string(),
+            removed_color: Color::Red,
+            added_color: Color::Green,
+            hunk_headers: false,
+            hunk_color: Color::Cyan,
+        }
+    }
+}
+
+impl DiffTheme {
+    pub fn from_config(config: &DiffThemeConfig) -> Self {
+        let default = Self::default();
+        Self {
+            removed_marker: config
+                .removed_marker
+                .clone()
+                .unwrap_or(default.removed_marker),
+            added_marker: config.added_marker.clone().unwrap_or(default.added_marker),
+            removed_color: parse_color(config.removed_color.as_deref(), default.removed_color),
+            added_color: parse_color(config.added_color.as_deref(), default.added_color),
+            hunk_headers: config.hunk_headers.unwrap_or(default.hunk_headers),
+            hunk_color: parse_color(config.hunk_color.as_deref(), default.hunk_color),
+        }
+    }
+
+    pub fn to_config(&self) -> DiffThemeConfig {
+        DiffThemeConfig {
+            removed_marker: Some(self.removed_marker.clone()),
+            added_marker: Some(self.added_marker.clone()),
+            removed_color: Some(self.removed_color.to_string()),
+            added_color: Some(self.added_color.to_string()),
+            hunk_headers: Some(self.hunk_headers),
+            hunk_color: Some(self.hunk_color.to_string()),
+        }
+    }
+}
+
+fn parse_color(name: Option<&str>, fallback: Color) -> Color {
+    name.and_then(|name| Color::from_str(name).ok())
+        .unwrap_or(fallback)
+}
+
+pub fn no_color() -> bool {
+    static NO_COLOR: OnceLock<bool> = OnceLock::new();
+    *NO_COLOR.get_or_init(|| std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()))
+}
+
+pub fn fg(color: Color, fallback: Modifier) -> Style {
+    if no_color() {
+        Style::default().add_modifier(fallback)
+    } else {
+        Style::default().fg(color)
+    }
+}
@@ -0,0 +1,70 @@
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListRow {
+    Dir {
+        path: String,
+        collapsed: bool,
+        files: usize,
+    },
+    File(String),
+}
+
+impl ListRow {
+    pub fn file(&self) -> Option<&str> {
+        match self {
+            ListRow::File(path) => Some(path),
+            ListRow::Dir { .. } => None,
+        }
+    }
+}
+
+fn parent_dir(file: &str) -> PathBuf {
+    Path::new(file)
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."))
+        .to_path_buf()
+}
+
+pub fn group_rows(files: &[String], collapsed: &HashSet<String>) -> Vec<ListRow> {
+    let mut by_dir: BTreeMap<PathBuf, Vec<&String>> = BTreeMap::new();
+    for file in files {
+        by_dir.entry(parent_dir(file)).or_default().push(file);
+    }
+
+    let mut rows = Vec::new();
+    for (dir, dir_files) in &by_dir {
+        let hidden = collapsed
+            .iter()
+            .any(|c| dir.starts_with(c) && dir.as_path() != Path::new(c));
+        if hidden {
+            continue;
+        }
+
+        let path = dir.display().to_string();
+        let is_collapsed = collapsed.contains(&path);
+        let count = if is_collapsed {
+            by_dir
+                .iter()
+                .filter(|(d, _)| d.starts_with(dir))
+                .map(|(_, f)| f.len())
+                .sum()
+        } else {
+            dir_files.len()
+        };
+
+        rows.push(ListRow::Dir {
+            path,
+            collapsed: is_collapsed,
+            files: count,
+        });
+        if !is_collapsed {
+            let mut sorted = dir_files.clone();
+            sorted.sort();
+            rows.extend(sorted.into_iter().map(|f| ListRow::File(f.clone())));
+        }
+    }
+    rows
+}
@@ -1,75 +1,841 @@
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Position},
+    layout::{Constraint, Direction, Layout, Position, Rect},
     style::{Color, Modifier, Style},
-    text::{Line, Text},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
     Frame,
 };
+use std::path::Path;
 
-use crate::app::{App, ConfirmState, Focus};
-use crate::utils::apply_substitution_partial;
-use crate::utils::highlight_diff_lines;
-use crate::utils::highlight_match;
+use crate::app::{App, ConfirmState, Focus, LayoutMode};
+use crate::results::{MatchResult, ResultsView};
+use crate::theme::fg;
+use crate::tree::ListRow;
+use crate::utils::{
+    emphasize_basename, highlight_captures, highlight_match, safe_slice_chars, Preview,
+};
+
+const INLINE_PREVIEW_CHARS: usize = 30;
+
+fn char_count(text: &str) -> usize {
+    text.chars().count()
+}
 
-fn safe_slice_chars(text: &str, start_char: usize, end_char: usize) -> &str {
-    let char_indices: Vec<(usize, char)> = text.char_indices().collect();
+fn char_visual_width(c: char) -> usize {
+    match c {
+        '\u{1100}'..='\u{11FF}' | // Hangul Jamo
+        '\u{3040}'..='\u{309F}' | // Hiragana
+        '\u{30A0}'..='\u{30FF}' | // Katakana
+        '\u{3100}'..='\u{312F}' | // Bopomofo
+        '\u{3200}'..='\u{32FF}' | // Enclosed CJK Letters and Months
+        '\u{3400}'..='\u{4DBF}' | // CJK Unified Ideographs Extension A
+        '\u{4E00}'..='\u{9FFF}' | // CJK Unified Ideographs
+        '\u{A960}'..='\u{A97F}' | // Hangul Jamo Extended-A
+        '\u{AC00}'..='\u{D7AF}' | // Hangul Syllables
+        '\u{D7B0}'..='\u{D7FF}' | // Hangul Jamo Extended-B
+        '\u{F900}'..='\u{FAFF}' | // CJK Compatibility Ideographs
+        '\u{FE10}'..='\u{FE1F}' | // Vertical Forms
+        '\u{FE30}'..='\u{FE4F}' | // CJK Compatibility Forms
+        '\u{FF00}'..='\u{FFEF}' => 2, // Fullwidth forms
+        _ => 1,
+    }
+}
+
+fn cursor_visual_position(text: &str, cursor_char_pos: usize) -> usize {
+    text.chars()
+        .take(cursor_char_pos)
+        .map(char_visual_width)
+        .sum()
+}
 
-    if char_indices.is_empty() || start_char >= char_indices.len() {
+fn visible_input_text(text: &str, view_offset: usize, field_width: usize) -> &str {
+    if char_count(text) <= view_offset {
         return "";
     }
 
-    let start_byte = char_indices[start_char].0;
-    let end_byte = if end_char >= char_indices.len() {
-        text.len()
+    // Calculate how many characters we can fit based on visual width
+    let mut visual_width_used = 0;
+    let mut end_char = view_offset;
+    for (i, c) in text.chars().enumerate().skip(view_offset) {
+        let width = char_visual_width(c);
+        if visual_width_used + width > field_width {
+            break;
+        }
+        visual_width_used += width;
+        end_char = i + 1;
+    }
+
+    safe_slice_chars(text, view_offset, end_char)
+}
+
+fn expand_tabs(line: Line<'static>, tab_width: usize) -> Line<'static> {
+    let mut column = 0;
+    let spans = line
+        .spans
+        .into_iter()
+        .map(|span| {
+            if !span.content.contains('\t') {
+                column += span.content.chars().map(char_visual_width).sum::<usize>();
+                return span;
+            }
+            let mut expanded = String::new();
+            for c in span.content.chars() {
+                if c == '\t' {
+                    let spaces = tab_width - column % tab_width;
+                    expanded.extend(std::iter::repeat_n(' ', spaces));
+                    column += spaces;
+                } else {
+                    expanded.push(c);
+                    column += char_visual_width(c);
+                }
+            }
+            Span::styled(expanded, span.style)
+        })
+        .collect::<Vec<_>>();
+    Line::from(spans).style(line.style)
+}
+
+fn draw_input_field(
+    f: &mut Frame,
+    area: Rect,
+    title: &str,
+    text: &str,
+    cursor: usize,
+    view_offset: usize,
+    focused: bool,
+) {
+    let field_width = area.width.saturating_sub(2) as usize;
+    let visible_text = visible_input_text(text, view_offset, field_width);
+
+    let paragraph = Paragraph::new(Text::from(visible_text)).block(
+        Block::default()
+            .title(title.to_string())
+            .borders(Borders::ALL)
+            .border_style(if focused {
+                fg(Color::Cyan, Modifier::BOLD)
+            } else {
+                Style::default()
+            }),
+    );
+    f.render_widget(paragraph, area);
+
+    if focused {
+        let cursor_x = if cursor >= view_offset {
+            cursor_visual_position(visible_text, cursor - view_offset)
+        } else {
+            0
+        };
+        f.set_cursor_position(Position::new(area.x + 1 + cursor_x as u16, area.y + 1));
+    }
+}
+
+fn draw_field_error(f: &mut Frame, area: Rect, error: &str) {
+    if area.height < 3 || area.width < 3 {
+        return;
+    }
+    let row = Rect::new(area.x + 1, area.bottom() - 1, area.width - 2, 1);
+    f.render_widget(
+        Paragraph::new(Line::from(Span::styled(
+            format!(" {} ", error),
+            fg(Color::Red, Modifier::BOLD),
+        ))),
+        row,
+    );
+}
+
+fn collapsed_fields_line(fields: &[(&str, &str, bool)]) -> Line<'static> {
+    let spans = fields
+        .iter()
+        .filter(|(_, _, expanded)| !expanded)
+        .enumerate()
+        .flat_map(|(i, (label, value, _))| {
+            let separator =
+                (i > 0).then(|| Span::styled(" │ ", fg(Color::DarkGray, Modifier::empty())));
+            let value = if value.is_empty() {
+                Span::styled("(empty)", fg(Color::DarkGray, Modifier::DIM))
+            } else {
+                Span::raw(value.to_string())
+            };
+            separator.into_iter().chain([
+                Span::styled(format!("{}: ", label), fg(Color::DarkGray, Modifier::BOLD)),
+                value,
+            ])
+        })
+        .collect::<Vec<_>>();
+    Line::from(spans)
+}
+
+fn truncate_preview(text: &str) -> String {
+    if text.chars().count() > INLINE_PREVIEW_CHARS {
+        format!("{}…", safe_slice_chars(text, 0, INLINE_PREVIEW_CHARS - 1))
     } else {
-        char_indices[end_char].0
+        text.to_string()
+    }
+}
+
+fn file_list_item<'a>(app: &App, fpath: &str, label: &'a str, is_selected: bool) -> Vec<Line<'a>> {
+    let mut content = match app.type_ahead() {
+        Some(typed) if is_selected => highlight_match(label, typed),
+        _ => highlight_match(label, &app.filter_input),
     };
+    if app.emphasize_basename {
+        content = content
+            .into_iter()
+            .map(|line| emphasize_basename(line, label))
+            .collect();
+    }
+    if app.is_modified(fpath) {
+        if let Some(line) = content.first_mut() {
+            line.spans
+                .insert(0, Span::styled("* ", fg(Color::Magenta, Modifier::BOLD)));
+        }
+    }
+    if app.show_match_lines {
+        if let (Some(line), Some(summary)) = (content.first_mut(), app.match_summary(fpath)) {
+            line.spans.push(Span::styled(
+                format!(" ({})", summary.label()),
+                fg(Color::DarkGray, Modifier::DIM),
+            ));
+        }
+    }
+    if app.show_inline_preview {
+        if let (Some(line), Some((before, after))) =
+            (content.first_mut(), app.inline_preview(fpath))
+        {
+            line.spans.push(Span::styled(
+                format!(
+                    "  {} → {}",
+                    truncate_preview(&before),
+                    truncate_preview(&after)
+                ),
+                fg(Color::DarkGray, Modifier::DIM),
+            ));
+        }
+    }
+    content
+}
 
-    &text[start_byte..end_byte]
+fn file_list_style(app: &App, fpath: &str, is_selected: bool) -> Style {
+    let mut style = Style::default();
+    if app.deselected.contains(fpath) {
+        style = style
+            .patch(fg(Color::DarkGray, Modifier::DIM))
+            .add_modifier(Modifier::CROSSED_OUT);
+    }
+    if is_selected {
+        style = style
+            .patch(fg(Color::Yellow, Modifier::REVERSED))
+            .add_modifier(Modifier::BOLD);
+    }
+    style
 }
 
-fn char_count(text: &str) -> usize {
-    text.chars().count()
+fn directory_item(
+    path: String,
+    collapsed: bool,
+    files: usize,
+    is_selected: bool,
+) -> ListItem<'static> {
+    let marker = if collapsed { "▸" } else { "▾" };
+    let mut style = fg(Color::Blue, Modifier::BOLD).add_modifier(Modifier::BOLD);
+    if is_selected {
+        style = style.patch(fg(Color::Yellow, Modifier::REVERSED));
+    }
+    ListItem::new(format!("{} {}/ ({})", marker, path, files)).style(style)
 }
 
-fn cursor_visual_position(text: &str, cursor_char_pos: usize) -> usize {
-    text.chars()
-        .take(cursor_char_pos)
-        .map(|c| {
-            match c {
-            '\u{1100}'..='\u{11FF}' | // Hangul Jamo
-            '\u{3040}'..='\u{309F}' | // Hiragana
-            '\u{30A0}'..='\u{30FF}' | // Katakana
-            '\u{3100}'..='\u{312F}' | // Bopomofo
-            '\u{3200}'..='\u{32FF}' | // Enclosed CJK Letters and Months
-            '\u{3400}'..='\u{4DBF}' | // CJK Unified Ideographs Extension A
-            '\u{4E00}'..='\u{9FFF}' | // CJK Unified Ideographs
-            '\u{A960}'..='\u{A97F}' | // Hangul Jamo Extended-A
-            '\u{AC00}'..='\u{D7AF}' | // Hangul Syllables
-            '\u{D7B0}'..='\u{D7FF}' | // Hangul Jamo Extended-B
-            '\u{F900}'..='\u{FAFF}' | // CJK Compatibility Ideographs
-            '\u{FE10}'..='\u{FE1F}' | // Vertical Forms
-            '\u{FE30}'..='\u{FE4F}' | // CJK Compatibility Forms
-            '\u{FF00}'..='\u{FFEF}' => 2, // Fullwidth forms
-            _ => 1,
-        }
+fn list_title(app: &App) -> String {
+    let mut tags = Vec::new();
+    if app.read_only {
+        tags.push("READ-ONLY".to_string());
+    }
+    if app.staged_files.is_some() {
+        tags.push("staged".to_string());
+    }
+    if !app.from_input.is_empty() {
+        tags.push(if app.only_matching {
+            "matches only".to_string()
+        } else {
+            "all files".to_string()
+        });
+    }
+    if app.conflicts_only {
+        tags.push("conflicts".to_string());
+    }
+    if app.compare_with.is_some() {
+        tags.push("comparing".to_string());
+    }
+    if app.skip_applied && !app.from_input.is_empty() {
+        tags.push("skip applied".to_string());
+    }
+    if app.changed_only && (!app.from_input.is_empty() || !app.rules.is_empty()) {
+        tags.push("changed only".to_string());
+    }
+    if !app.rules.is_empty() {
+        tags.push(format!("{} rules", app.rules.len()));
+    }
+    if app.modified_count() > 0 {
+        tags.push(format!("{} modified", app.modified_count()));
+    }
+    if !app.deselected.is_empty() {
+        tags.push(format!("{} deselected", app.deselected.len()));
+    }
+    if !app.saved_states.is_empty() {
+        tags.push(format!("{} saved", app.saved_states.len()));
+    }
+    if app.group_by_dir {
+        tags.push("grouped".to_string());
+    }
+    tags.extend(app.size_filters().into_iter().map(str::to_string));
+    if let Some(typed) = app.type_ahead() {
+        tags.push(format!("find: {}", typed));
+    }
+    if !app.is_watching() {
+        tags.push("watcher off".to_string());
+    }
+
+    if tags.is_empty() {
+        "File [L]ist".to_string()
+    } else {
+        format!("File [L]ist ({})", tags.join(", "))
+    }
+}
+
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(area);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(rows[1])[1]
+}
+
+fn draw_affected_files_popup(f: &mut Frame, app: &App, paths: &[String]) {
+    let area = centered_rect(60, 60, f.area());
+    let height = area.height.saturating_sub(2) as usize;
+    let scroll = app.confirm_scroll.min(paths.len().saturating_sub(1));
+
+    let items = paths
+        .iter()
+        .skip(scroll)
+        .take(height)
+        .map(|path| ListItem::new(path.as_str()))
+        .collect::<Vec<_>>();
+
+    let shown_end = (scroll + height).min(paths.len());
+    let title = if paths.is_empty() {
+        "No files will be modified (n)".to_string()
+    } else {
+        format!(
+            "Apply changes to {} files? (y/n) [{}-{} of {}]",
+            paths.len(),
+            scroll + 1,
+            shown_end,
+            paths.len()
+        )
+    };
+
+    let popup = List::new(items).block(
+        Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(fg(Color::Yellow, Modifier::BOLD)),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+fn draw_preset_picker(f: &mut Frame, app: &App, selected: usize) {
+    let area = centered_rect(60, 60, f.area());
+
+    let items = app
+        .presets
+        .iter()
+        .enumerate()
+        .map(|(i, preset)| {
+            let style = if i == selected {
+                fg(Color::Yellow, Modifier::REVERSED).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(preset.name.clone(), style),
+                Span::styled(
+                    format!("  {} -> {}", preset.from, preset.to),
+                    fg(Color::DarkGray, Modifier::DIM),
+                ),
+            ]))
         })
-        .sum()
+        .collect::<Vec<_>>();
+
+    let popup = List::new(items).block(
+        Block::default()
+            .title("Presets (Enter to load, Esc to close)")
+            .borders(Borders::ALL)
+            .border_style(fg(Color::Cyan, Modifier::BOLD)),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+fn draw_rules_view(f: &mut Frame, app: &App, selected: usize) {
+    let area = centered_rect(60, 40, f.area());
+
+    let items = app
+        .rules
+        .iter()
+        .enumerate()
+        .map(|(i, rule)| {
+            let style = if i == selected {
+                fg(Color::Yellow, Modifier::REVERSED).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            let flags = format!(
+                "{}{}",
+                if rule.global { "g" } else { "" },
+                if rule.case_insensitive { "i" } else { "" }
+            );
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!(
+                        "{}. {}s/{}/{}/",
+                        i + 1,
+                        rule.scope
+                            .as_ref()
+                            .map_or(String::new(), |scope| format!("[{}] ", scope)),
+                        rule.from,
+                        rule.to
+                    ),
+                    style,
+                ),
+                Span::styled(flags, fg(Color::DarkGray, Modifier::DIM)),
+            ]))
+        })
+        .collect::<Vec<_>>();
+
+    let popup = List::new(items).block(
+        Block::default()
+            .title("Rules, applied top to bottom (J/K to move, Esc to close)")
+            .borders(Borders::ALL)
+            .border_style(fg(Color::Cyan, Modifier::BOLD)),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+fn draw_pipe_output(f: &mut Frame, app: &App, output: &str) {
+    let area = centered_rect(80, 80, f.area());
+    let title = format!(
+        "{} (Esc to close)",
+        app.pipe_command.as_deref().unwrap_or_default()
+    );
+
+    let popup = Paragraph::new(Text::from(output))
+        .scroll((app.pipe_scroll.min(u16::MAX as usize) as u16, 0))
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(fg(Color::Cyan, Modifier::BOLD)),
+        );
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+fn result_lines(result: &MatchResult, selected: bool) -> Vec<Line<'static>> {
+    let header_style = if selected {
+        fg(Color::Yellow, Modifier::REVERSED).add_modifier(Modifier::BOLD)
+    } else {
+        fg(Color::Cyan, Modifier::BOLD)
+    };
+    let mut lines = vec![Line::styled(
+        format!("{}:{}", result.path, result.line + 1),
+        header_style,
+    )];
+    lines.extend(result.context.iter().map(|(line, text)| {
+        let style = if *line == result.line {
+            fg(Color::Yellow, Modifier::BOLD)
+        } else {
+            fg(Color::DarkGray, Modifier::DIM)
+        };
+        Line::styled(format!("{:>6}│ {}", line + 1, text), style)
+    }));
+    lines
+}
+
+fn draw_results(f: &mut Frame, view: &ResultsView) {
+    let area = centered_rect(80, 80, f.area());
+    let height = area.height.saturating_sub(2) as usize;
+
+    let Some(selected) = view.results.get(view.selected) else {
+        return;
+    };
+
+    let mut first = view.selected;
+    let mut used = selected.context.len() + 1;
+    while first > 0 {
+        let above = view.results[first - 1].context.len() + 1;
+        if used + above > height / 2 {
+            break;
+        }
+        used += above;
+        first -= 1;
+    }
+
+    let lines = view.results[first..]
+        .iter()
+        .enumerate()
+        .flat_map(|(i, result)| result_lines(result, first + i == view.selected))
+        .take(height)
+        .collect::<Vec<_>>();
+
+    let title = format!(
+        "Matches: {}/{} in {} files (j/k to move, Enter to open, Esc to close)",
+        view.selected + 1,
+        view.results.len(),
+        view.file_count()
+    );
+    let popup = Paragraph::new(lines).block(
+        Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(fg(Color::Cyan, Modifier::BOLD)),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+fn draw_export_prompt(f: &mut Frame, target: &str) {
+    let column = centered_rect(60, 100, f.area());
+    let area = Rect {
+        y: column.y + column.height.saturating_sub(3) / 2,
+        height: column.height.min(3),
+        ..column
+    };
+
+    let popup = Paragraph::new(format!("{}█", target)).block(
+        Block::default()
+            .title("Export config to (path or path#preset, Enter to save, Esc to cancel)")
+            .borders(Borders::ALL)
+            .border_style(fg(Color::Cyan, Modifier::BOLD)),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+fn draw_scratch(f: &mut Frame, app: &App, input: &str) {
+    let area = centered_rect(70, 60, f.area());
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+
+    let input_block = Paragraph::new(format!("{}█", input)).block(
+        Block::default()
+            .title("Scratch input (Enter for a new line, Esc to close)")
+            .borders(Borders::ALL)
+            .border_style(fg(Color::Cyan, Modifier::BOLD)),
+    );
+    let result_block = Paragraph::new(app.scratch_result().unwrap_or_default()).block(
+        Block::default()
+            .title("Result")
+            .borders(Borders::ALL)
+            .border_style(fg(Color::Cyan, Modifier::BOLD)),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(input_block, rows[0]);
+    f.render_widget(result_block, rows[1]);
+}
+
+fn status_line(app: &App, filtered_files: &[String]) -> (String, Style) {
+    let empty_match_warning = app.from_matches_empty();
+    let confirm_suffix = if empty_match_warning {
+        " (From matches the empty string! Press Y to apply anyway)"
+    } else {
+        ""
+    };
+    match &app.confirm {
+        ConfirmState::Confirming(path) => (
+            format!("Apply changes to {}? (y/n){}", path, confirm_suffix),
+            Style::default(),
+        ),
+        ConfirmState::ConfirmingAll(paths) => (
+            format!(
+                "Apply changes to {} files? (y/n){}",
+                paths.len(),
+                confirm_suffix
+            ),
+            Style::default(),
+        ),
+        ConfirmState::ConfirmingFrom(paths) => (
+            format!(
+                "Apply changes to {} files from here on? (y/n){}",
+                paths.len(),
+                confirm_suffix
+            ),
+            Style::default(),
+        ),
+        ConfirmState::None => {
+            if let Some(limit) = app.load_limit_hit {
+                let answer = if app.focus == Focus::FileList {
+                    "(y/n)"
+                } else {
+                    "(y/n in the File List)"
+                };
+                (
+                    format!(
+                        "Stopped after {} files. Load everything anyway? {}",
+                        limit, answer
+                    ),
+                    fg(Color::Yellow, Modifier::BOLD),
+                )
+            } else if app.scan_timed_out() {
+                (
+                    format!(
+                        "Pattern timed out after {} ms; the file list is incomplete and Ctrl+A/Ctrl+Q are disabled",
+                        app.match_timeout.as_millis()
+                    ),
+                    fg(Color::Red, Modifier::BOLD),
+                )
+            } else if let Some(message) = &app.status_message {
+                (message.clone(), Style::default())
+            } else if empty_match_warning {
+                (
+                    "Warning: From matches the empty string; To will be inserted between characters"
+                        .to_string(),
+                    fg(Color::Red, Modifier::BOLD),
+                )
+            } else if let Some(warning) = app.to_escape_warning() {
+                (warning, fg(Color::Yellow, Modifier::BOLD))
+            } else if let Some(hint) = app.filter_hint(filtered_files) {
+                (hint, fg(Color::Yellow, Modifier::BOLD))
+            } else if let Some(reason) = app.no_change_reason(filtered_files) {
+                (
+                    reason.message().to_string(),
+                    fg(Color::Yellow, Modifier::BOLD),
+                )
+            } else if let Some(captures) = app.capture_summary(filtered_files) {
+                (captures, fg(Color::Cyan, Modifier::BOLD))
+            } else {
+                let notes = [app.total_byte_delta(filtered_files), app.timing_status()]
+                    .into_iter()
+                    .flatten()
+                    .collect::<Vec<_>>();
+                (notes.join(" | "), Style::default())
+            }
+        }
+    }
+}
+
+fn draw_diff(
+    f: &mut Frame,
+    app: &mut App,
+    filtered_files: &[String],
+    preview: Option<Preview>,
+    area: Rect,
+    footer: Option<Line<'static>>,
+) {
+    let height = area.height as usize - 2;
+    let truncated = preview.as_ref().is_some_and(|p| p.truncated);
+    let truncation_marker = truncated.then(|| {
+        Line::from(Span::styled(
+            format!(
+                "... preview truncated at {} KB; apply still edits the whole file",
+                app.preview_limit / 1024
+            ),
+            fg(Color::DarkGray, Modifier::DIM),
+        ))
+    });
+    let hex = preview.as_ref().is_some_and(|p| p.hex);
+    let mut diff_notes = Vec::new();
+    if hex {
+        diff_notes.push("hex".to_string());
+    }
+    if app.wrap_diff {
+        diff_notes.push("wrap".to_string());
+    }
+    if app.full_diff {
+        diff_notes.push("whole file".to_string());
+    }
+    if truncated {
+        diff_notes.push("truncated preview".to_string());
+    }
+    let match_re = app.match_view.then(|| app.from_regex()).flatten();
+    if match_re.is_some() && !hex {
+        diff_notes.push("matches".to_string());
+    } else if let Some(other) = app
+        .selected_file(filtered_files)
+        .and_then(|path| app.compare_target(&path).map(str::to_string))
+    {
+        diff_notes.push(format!("vs {}", other));
+    } else if let Some(path) = app.selected_file(filtered_files) {
+        diff_notes.extend(app.match_count_status(&path));
+        diff_notes.extend(app.patch_error(&path));
+        diff_notes.extend(app.hunk_status(&path));
+        diff_notes.extend(app.change_status(&path));
+        diff_notes.extend(app.byte_delta_status(&path));
+        diff_notes.extend(app.trailing_whitespace_status(&path));
+    }
+    if app.show_file_info {
+        if let Some(info) = app
+            .selected_file(filtered_files)
+            .and_then(|path| app.file_info(&path))
+        {
+            diff_notes.push(info.label());
+        }
+    }
+    if app.layout == LayoutMode::Review {
+        if let Some(path) = app.selected_file(filtered_files) {
+            let position = filtered_files.iter().position(|f| *f == path).unwrap_or(0);
+            diff_notes.insert(
+                0,
+                format!("{} {}/{}", path, position + 1, filtered_files.len()),
+            );
+        }
+        if app.read_only {
+            diff_notes.insert(0, "READ-ONLY".to_string());
+        }
+    }
+    let diff_title = if diff_notes.is_empty() {
+        "[D]iff".to_string()
+    } else {
+        format!("[D]iff ({})", diff_notes.join(", "))
+    };
+    let visible_diff = match (app.selected_file(filtered_files), preview, match_re) {
+        (Some(_), Some(preview), _) if preview.hex => preview
+            .content
+            .lines()
+            .map(|line| Line::from(line.to_string()))
+            .chain(truncation_marker)
+            .skip(app.diff_scroll)
+            .take(height)
+            .collect::<Vec<_>>(),
+        (Some(_), Some(preview), Some(re)) => highlight_captures(&preview.content, &re)
+            .into_iter()
+            .chain(truncation_marker)
+            .skip(app.diff_scroll)
+            .take(height)
+            .map(|line| expand_tabs(line, app.tab_width))
+            .collect::<Vec<_>>(),
+        (Some(path), Some(preview), None) => {
+            let diff_scroll = app.diff_scroll;
+            let tab_width = app.tab_width;
+            app.diff_lines(&path, preview.content)
+                .iter()
+                .cloned()
+                .chain(truncation_marker)
+                .skip(diff_scroll)
+                .take(height)
+                .map(|line| expand_tabs(line, tab_width))
+                .collect::<Vec<_>>()
+        }
+        _ => vec![Line::from("No file selected.")],
+    };
+
+    let mut block = Block::default()
+        .title(diff_title)
+        .borders(Borders::ALL)
+        .border_style(if app.focus == Focus::DiffView {
+            fg(Color::Cyan, Modifier::BOLD)
+        } else {
+            Style::default()
+        });
+    if let Some(footer) = footer {
+        block = block.title_bottom(footer);
+    }
+    let mut diff_view = Paragraph::new(visible_diff).block(block);
+    if app.wrap_diff {
+        diff_view = diff_view.wrap(Wrap { trim: false });
+    }
+    f.render_widget(diff_view, area);
 }
 
-pub fn draw(f: &mut Frame, app: &mut App, filtered_files: &[String], file_content: Option<String>) {
+fn draw_review(f: &mut Frame, app: &mut App, filtered_files: &[String], preview: Option<Preview>) {
+    let (status, status_style) = status_line(app, filtered_files);
+    let footer = if status.is_empty() {
+        "j/k: next/previous file, Enter: apply, Esc: leave review".to_string()
+    } else {
+        status
+    };
+    let area = f.area();
+    draw_diff(
+        f,
+        app,
+        filtered_files,
+        preview,
+        area,
+        Some(Line::styled(footer, status_style)),
+    );
+
+    if let ConfirmState::ConfirmingAll(paths) | ConfirmState::ConfirmingFrom(paths) = &app.confirm {
+        draw_affected_files_popup(f, app, paths);
+    }
+
+    if let Some(selected) = app.preset_picker {
+        draw_preset_picker(f, app, selected);
+    }
+
+    if let Some(selected) = app.rules_view {
+        draw_rules_view(f, app, selected);
+    }
+
+    if let Some(output) = &app.pipe_output {
+        draw_pipe_output(f, app, output);
+    }
+
+    if let Some(target) = &app.export_prompt {
+        draw_export_prompt(f, target);
+    }
+
+    if let Some(input) = &app.scratch {
+        draw_scratch(f, app, input);
+    }
+
+    if let Some(view) = &app.results_view {
+        draw_results(f, view);
+    }
+}
+
+pub fn draw(f: &mut Frame, app: &mut App, filtered_files: &[String], preview: Option<Preview>) {
+    if app.layout == LayoutMode::Review {
+        draw_review(f, app, filtered_files, preview);
+        return;
+    }
+
     let size = f.area();
     let columns = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
         .split(size);
 
+    let collapsed = app.collapse_inputs;
+    let field_height = |focus: Focus| {
+        if collapsed && app.focus != focus {
+            0
+        } else {
+            3
+        }
+    };
     let left_rows = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Min(10),
-            Constraint::Length(3),
-            Constraint::Length(3),
+            Constraint::Length(field_height(Focus::FilePathFilter)),
+            Constraint::Length(field_height(Focus::ContentFilter)),
+            Constraint::Length(u16::from(collapsed)),
+            Constraint::Length(if collapsed { 1 } else { 3 }),
         ])
         .split(columns[0]);
 
@@ -77,8 +843,9 @@ pub fn draw(f: &mut Frame, app: &mut App, filtered_files: &[String], file_conten
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Min(10),
-            Constraint::Length(3),
-            Constraint::Length(3),
+            Constraint::Length(field_height(Focus::From)),
+            Constraint::Length(field_height(Focus::To)),
+            Constraint::Length(u16::from(collapsed)),
         ])
         .split(columns[1]);
 
@@ -95,31 +862,62 @@ pub fn draw(f: &mut Frame, app: &mut App, filtered_files: &[String], file_conten
             offset = app.selected;
         }
 
-        let visible_files = filtered_files
-            .iter()
-            .skip(offset)
-            .take(list_height)
-            .enumerate()
-            .map(|(i, fpath)| {
-                let content = highlight_match(fpath, &app.filter_input);
-                let mut item = ListItem::new(content);
-                if i + offset == app.selected {
-                    item = item.style(
-                        Style::default()
-                            .fg(Color::Yellow)
-                            .add_modifier(Modifier::BOLD),
-                    );
-                }
-                item
-            })
-            .collect::<Vec<_>>();
+        let visible_files = if app.group_by_dir {
+            app.list_rows(filtered_files)
+                .into_iter()
+                .enumerate()
+                .skip(offset)
+                .take(list_height)
+                .map(|(i, row)| match row {
+                    ListRow::Dir {
+                        path,
+                        collapsed,
+                        files,
+                    } => directory_item(path, collapsed, files, i == app.selected),
+                    ListRow::File(fpath) => {
+                        let name = Path::new(&fpath)
+                            .file_name()
+                            .map(|name| name.to_string_lossy().to_string())
+                            .unwrap_or_else(|| fpath.clone());
+                        let lines = file_list_item(app, &fpath, &name, i == app.selected)
+                            .into_iter()
+                            .map(|line| {
+                                let spans = line.spans.into_iter().map(|span| {
+                                    Span::styled(span.content.into_owned(), span.style)
+                                });
+                                Line::from(
+                                    std::iter::once(Span::raw("  "))
+                                        .chain(spans)
+                                        .collect::<Vec<_>>(),
+                                )
+                            })
+                            .collect::<Vec<_>>();
+                        ListItem::new(lines).style(file_list_style(app, &fpath, i == app.selected))
+                    }
+                })
+                .collect::<Vec<_>>()
+        } else {
+            filtered_files
+                .iter()
+                .enumerate()
+                .skip(offset)
+                .take(list_height)
+                .map(|(i, fpath)| {
+                    let is_selected = i == app.selected;
+                    ListItem::new(file_list_item(app, fpath, fpath, is_selected))
+                        .style(file_list_style(app, fpath, is_selected))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let list_title = list_title(app);
 
         let file_list = List::new(visible_files).block(
             Block::default()
-                .title("File [L]ist")
+                .title(list_title)
                 .borders(Borders::ALL)
                 .border_style(if app.focus == Focus::FileList {
-                    Style::default().fg(Color::Cyan)
+                    fg(Color::Cyan, Modifier::BOLD)
                 } else {
                     Style::default()
                 }),
@@ -128,226 +926,189 @@ pub fn draw(f: &mut Frame, app: &mut App, filtered_files: &[String], file_conten
     }
 
     let filter_field_width = (left_rows[1].width.saturating_sub(2)) as usize;
+    let content_filter_field_width = (left_rows[2].width.saturating_sub(2)) as usize;
     let from_field_width = (right_rows[1].width.saturating_sub(2)) as usize;
     let to_field_width = (right_rows[2].width.saturating_sub(2)) as usize;
 
-    app.update_field_widths(filter_field_width, from_field_width, to_field_width);
-
-    let filter_char_count = char_count(&app.filter_input);
-    let mut filter_visible_text = "";
-    let mut filter_end_char = app.filter_view_offset;
-
-    if filter_char_count > app.filter_view_offset {
-        // Calculate how many characters we can fit based on visual width
-        let mut visual_width_used = 0;
-        let chars: Vec<char> = app.filter_input.chars().collect();
-
-        for (i, &char) in chars.iter().enumerate().skip(app.filter_view_offset) {
-            let char_visual_width = match char {
-                '\u{1100}'..='\u{11FF}'
-                | '\u{3040}'..='\u{309F}'
-                | '\u{30A0}'..='\u{30FF}'
-                | '\u{3100}'..='\u{312F}'
-                | '\u{3200}'..='\u{32FF}'
-                | '\u{3400}'..='\u{4DBF}'
-                | '\u{4E00}'..='\u{9FFF}'
-                | '\u{A960}'..='\u{A97F}'
-                | '\u{AC00}'..='\u{D7AF}'
-                | '\u{D7B0}'..='\u{D7FF}'
-                | '\u{F900}'..='\u{FAFF}'
-                | '\u{FE10}'..='\u{FE1F}'
-                | '\u{FE30}'..='\u{FE4F}'
-                | '\u{FF00}'..='\u{FFEF}' => 2,
-                _ => 1,
-            };
+    app.update_field_widths(
+        filter_field_width,
+        content_filter_field_width,
+        from_field_width,
+        to_field_width,
+    );
 
-            if visual_width_used + char_visual_width > filter_field_width {
-                break;
-            }
-            visual_width_used += char_visual_width;
-            filter_end_char = i + 1;
-        }
+    draw_input_field(
+        f,
+        left_rows[1],
+        if app.path_regex {
+            "Path Re[g]ex Filter"
+        } else {
+            "[G]lob Filter"
+        },
+        &app.filter_input,
+        app.filter_cursor,
+        app.filter_view_offset,
+        app.focus == Focus::FilePathFilter,
+    );
 
-        filter_visible_text =
-            safe_slice_chars(&app.filter_input, app.filter_view_offset, filter_end_char);
-    }
-    let filter_input = Paragraph::new(Text::from(filter_visible_text)).block(
-        Block::default()
-            .title("[G]lob Filter")
-            .borders(Borders::ALL)
-            .border_style(if app.focus == Focus::FilePathFilter {
-                Style::default().fg(Color::Cyan)
-            } else {
-                Style::default()
-            }),
+    let content_filter_title = match app.content_filter_patterns().len() {
+        0 | 1 => "Co[n]tent Filter".to_string(),
+        n => format!("Co[n]tent Filter (any of {})", n),
+    };
+    draw_input_field(
+        f,
+        left_rows[2],
+        &content_filter_title,
+        &app.content_filter_input,
+        app.content_filter_cursor,
+        app.content_filter_view_offset,
+        app.focus == Focus::ContentFilter,
     );
-    f.render_widget(filter_input, left_rows[1]);
-    if app.focus == Focus::FilePathFilter {
-        let cursor_x = if app.filter_cursor >= app.filter_view_offset {
-            let visible_cursor_pos = app.filter_cursor - app.filter_view_offset;
-            cursor_visual_position(filter_visible_text, visible_cursor_pos)
-        } else {
-            0
-        };
-        f.set_cursor_position(Position::new(
-            left_rows[1].x + 1 + cursor_x as u16,
-            left_rows[1].y + 1,
-        ));
+
+    let (status, status_style) = status_line(app, filtered_files);
+    f.render_widget(
+        Paragraph::new(Text::from(status)).style(status_style),
+        left_rows[4],
+    );
+
+    if collapsed {
+        let filter_label = if app.path_regex { "Regex" } else { "Glob" };
+        f.render_widget(
+            Paragraph::new(collapsed_fields_line(&[
+                (
+                    filter_label,
+                    &app.filter_input,
+                    app.focus == Focus::FilePathFilter,
+                ),
+                (
+                    "Content",
+                    &app.content_filter_input,
+                    app.focus == Focus::ContentFilter,
+                ),
+            ])),
+            left_rows[3],
+        );
+        f.render_widget(
+            Paragraph::new(collapsed_fields_line(&[
+                ("From", &app.from_input, app.focus == Focus::From),
+                ("To", &app.to_input, app.focus == Focus::To),
+            ])),
+            right_rows[3],
+        );
     }
 
-    let blank_text = match &app.confirm {
-        ConfirmState::Confirming(path) => format!("Apply changes to {}? (y/n)", path),
-        ConfirmState::ConfirmingAll(_) => "Apply changes to ALL files? (y/n)".to_string(),
-        ConfirmState::None => "".to_string(),
-    };
-    let blank = Paragraph::new(Text::from(blank_text));
-    f.render_widget(blank, left_rows[2]);
+    draw_diff(f, app, filtered_files, preview, right_rows[0], None);
 
-    let diff_output = if let Some(content) = file_content {
-        let replaced = apply_substitution_partial(&content, &app.from_input, &app.to_input);
-        highlight_diff_lines(content, replaced)
+    let mut from_notes = Vec::new();
+    if app.options.literal {
+        from_notes.push("literal".to_string());
+    }
+    if app.options.case_insensitive {
+        from_notes.push("i".to_string());
+    }
+    if app.options.multiline {
+        from_notes.push("m".to_string());
+    }
+    if app.options.dotall {
+        from_notes.push("s".to_string());
+    }
+    if app.options.anchor_start {
+        from_notes.push("line start".to_string());
+    }
+    if app.options.anchor_end {
+        from_notes.push("line end".to_string());
+    }
+    if app.options.keep_indent {
+        from_notes.push("keep indent".to_string());
+    }
+    if app.options.strip_trailing_whitespace {
+        from_notes.push("strip trailing whitespace".to_string());
+    }
+    match app.from_group_count() {
+        Some(1) => from_notes.push("1 group".to_string()),
+        Some(groups) => from_notes.push(format!("{} groups", groups)),
+        None => {}
+    }
+    let from_title = if from_notes.is_empty() {
+        "[F]rom".to_string()
     } else {
-        vec![Line::from("No file selected.")]
+        format!("[F]rom ({})", from_notes.join(", "))
     };
+    match app.history_search_view(Focus::From) {
+        Some((label, text)) => draw_input_field(
+            f,
+            right_rows[1],
+            &format!("[F]rom ({})", label),
+            &text,
+            0,
+            0,
+            true,
+        ),
+        None => draw_input_field(
+            f,
+            right_rows[1],
+            &from_title,
+            &app.from_input,
+            app.from_cursor,
+            app.from_view_offset,
+            app.focus == Focus::From,
+        ),
+    }
 
-    let height = right_rows[0].height as usize - 2;
-    let visible_diff = diff_output
-        .into_iter()
-        .skip(app.diff_scroll)
-        .take(height)
-        .collect::<Vec<_>>();
+    if let Some(error) = &app.from_error {
+        draw_field_error(f, right_rows[1], &format!("Invalid regex: {}", error));
+    }
 
-    let diff_view = Paragraph::new(visible_diff).block(
-        Block::default()
-            .title("[D]iff")
-            .borders(Borders::ALL)
-            .border_style(if app.focus == Focus::DiffView {
-                Style::default().fg(Color::Cyan)
+    match app.history_search_view(Focus::To) {
+        Some((label, text)) => draw_input_field(
+            f,
+            right_rows[2],
+            &format!("[T]o ({})", label),
+            &text,
+            0,
+            0,
+            true,
+        ),
+        None => draw_input_field(
+            f,
+            right_rows[2],
+            if app.options.literal {
+                "[T]o (literal)"
             } else {
-                Style::default()
-            }),
-    );
-    f.render_widget(diff_view, right_rows[0]);
-
-    let from_char_count = char_count(&app.from_input);
-    let mut from_visible_text = "";
-    let mut from_end_char = app.from_view_offset;
-
-    if from_char_count > app.from_view_offset {
-        // Calculate how many characters we can fit based on visual width
-        let mut visual_width_used = 0;
-        let chars: Vec<char> = app.from_input.chars().collect();
-
-        for (i, &char) in chars.iter().enumerate().skip(app.from_view_offset) {
-            let char_visual_width = match char {
-                '\u{1100}'..='\u{11FF}'
-                | '\u{3040}'..='\u{309F}'
-                | '\u{30A0}'..='\u{30FF}'
-                | '\u{3100}'..='\u{312F}'
-                | '\u{3200}'..='\u{32FF}'
-                | '\u{3400}'..='\u{4DBF}'
-                | '\u{4E00}'..='\u{9FFF}'
-                | '\u{A960}'..='\u{A97F}'
-                | '\u{AC00}'..='\u{D7AF}'
-                | '\u{D7B0}'..='\u{D7FF}'
-                | '\u{F900}'..='\u{FAFF}'
-                | '\u{FE10}'..='\u{FE1F}'
-                | '\u{FE30}'..='\u{FE4F}'
-                | '\u{FF00}'..='\u{FFEF}' => 2,
-                _ => 1,
-            };
+                "[T]o"
+            },
+            &app.to_input,
+            app.to_cursor,
+            app.to_view_offset,
+            app.focus == Focus::To,
+        ),
+    }
 
-            if visual_width_used + char_visual_width > from_field_width {
-                break;
-            }
-            visual_width_used += char_visual_width;
-            from_end_char = i + 1;
-        }
+    if let ConfirmState::ConfirmingAll(paths) | ConfirmState::ConfirmingFrom(paths) = &app.confirm {
+        draw_affected_files_popup(f, app, paths);
+    }
 
-        from_visible_text = safe_slice_chars(&app.from_input, app.from_view_offset, from_end_char);
+    if let Some(selected) = app.preset_picker {
+        draw_preset_picker(f, app, selected);
     }
-    let from_paragraph = Paragraph::new(Text::from(from_visible_text)).block(
-        Block::default()
-            .title("[F]rom")
-            .borders(Borders::ALL)
-            .border_style(if app.focus == Focus::From {
-                Style::default().fg(Color::Cyan)
-            } else {
-                Style::default()
-            }),
-    );
-    f.render_widget(from_paragraph, right_rows[1]);
-    if app.focus == Focus::From {
-        let cursor_x = if app.from_cursor >= app.from_view_offset {
-            let visible_cursor_pos = app.from_cursor - app.from_view_offset;
-            cursor_visual_position(from_visible_text, visible_cursor_pos)
-        } else {
-            0
-        };
-        f.set_cursor_position(Position::new(
-            right_rows[1].x + 1 + cursor_x as u16,
-            right_rows[1].y + 1,
-        ));
-    }
-
-    let to_char_count = char_count(&app.to_input);
-    let mut to_visible_text = "";
-    let mut to_end_char = app.to_view_offset;
-
-    if to_char_count > app.to_view_offset {
-        // Calculate how many characters we can fit based on visual width
-        let mut visual_width_used = 0;
-        let chars: Vec<char> = app.to_input.chars().collect();
-
-        for (i, &char) in chars.iter().enumerate().skip(app.to_view_offset) {
-            let char_visual_width = match char {
-                '\u{1100}'..='\u{11FF}'
-                | '\u{3040}'..='\u{309F}'
-                | '\u{30A0}'..='\u{30FF}'
-                | '\u{3100}'..='\u{312F}'
-                | '\u{3200}'..='\u{32FF}'
-                | '\u{3400}'..='\u{4DBF}'
-                | '\u{4E00}'..='\u{9FFF}'
-                | '\u{A960}'..='\u{A97F}'
-                | '\u{AC00}'..='\u{D7AF}'
-                | '\u{D7B0}'..='\u{D7FF}'
-                | '\u{F900}'..='\u{FAFF}'
-                | '\u{FE10}'..='\u{FE1F}'
-                | '\u{FE30}'..='\u{FE4F}'
-                | '\u{FF00}'..='\u{FFEF}' => 2,
-                _ => 1,
-            };
 
-            if visual_width_used + char_visual_width > to_field_width {
-                break;
-            }
-            visual_width_used += char_visual_width;
-            to_end_char = i + 1;
-        }
+    if let Some(selected) = app.rules_view {
+        draw_rules_view(f, app, selected);
+    }
 
-        to_visible_text = safe_slice_chars(&app.to_input, app.to_view_offset, to_end_char);
+    if let Some(output) = &app.pipe_output {
+        draw_pipe_output(f, app, output);
     }
-    let to_paragraph = Paragraph::new(Text::from(to_visible_text)).block(
-        Block::default()
-            .title("[T]o")
-            .borders(Borders::ALL)
-            .border_style(if app.focus == Focus::To {
-                Style::default().fg(Color::Cyan)
-            } else {
-                Style::default()
-            }),
-    );
-    f.render_widget(to_paragraph, right_rows[2]);
-    if app.focus == Focus::To {
-        let cursor_x = if app.to_cursor >= app.to_view_offset {
-            let visible_cursor_pos = app.to_cursor - app.to_view_offset;
-            cursor_visual_position(to_visible_text, visible_cursor_pos)
-        } else {
-            0
-        };
-        f.set_cursor_position(Position::new(
-            right_rows[2].x + 1 + cursor_x as u16,
-            right_rows[2].y + 1,
-        ));
+
+    if let Some(target) = &app.export_prompt {
+        draw_export_prompt(f, target);
+    }
+
+    if let Some(input) = &app.scratch {
+        draw_scratch(f, app, input);
+    }
+
+    if let Some(view) = &app.results_view {
+        draw_results(f, view);
     }
 }
@@ -1,9 +1,13 @@
-use itertools::Itertools;
+use itertools::{EitherOrBoth, Itertools};
 use ratatui::{
     style::{Color, Modifier, Style},
     text::{Line, Span},
 };
-use regex::{Captures, Regex};
+use regex::{Captures, NoExpand, Regex};
+use std::time::Duration;
+
+use crate::hunk::{align_lines, DiffRow};
+use crate::theme::{fg, no_color, DiffTheme};
 
 pub fn highlight_match<'a>(text: &'a str, pattern: &str) -> Vec<Line<'a>> {
     if let Some(index) = text.find(pattern) {
@@ -13,9 +17,7 @@ pub fn highlight_match<'a>(text: &'a str, pattern: &str) -> Vec<Line<'a>> {
         }
         spans.push(Span::styled(
             &text[index..index + pattern.len()],
-            Style::default()
-                .fg(Color::Green)
-                .add_modifier(Modifier::BOLD),
+            fg(Color::Green, Modifier::UNDERLINED).add_modifier(Modifier::BOLD),
         ));
         if index + pattern.len() < text.len() {
             spans.push(Span::raw(&text[index + pattern.len()..]));
@@ -26,69 +28,950 @@ pub fn highlight_match<'a>(text: &'a str, pattern: &str) -> Vec<Line<'a>> {
     }
 }
 
+pub fn emphasize_basename<'a>(line: Line<'a>, path: &str) -> Line<'a> {
+    let split = path.rfind(std::path::is_separator).map_or(0, |i| i + 1);
+    let base_style = Style::default().add_modifier(Modifier::BOLD);
+    let dim = |style: Style| {
+        if style == Style::default() {
+            style.add_modifier(Modifier::DIM)
+        } else {
+            style
+        }
+    };
+
+    let mut spans = Vec::new();
+    let mut start = 0;
+    for span in line.spans {
+        let end = start + span.content.len();
+        if end <= split {
+            let style = dim(span.style);
+            spans.push(span.style(style));
+        } else if start >= split {
+            spans.push(span.patch_style(base_style));
+        } else {
+            let (dir, base) = span.content.split_at(split - start);
+            spans.push(Span::styled(dir.to_string(), dim(span.style)));
+            spans.push(Span::styled(base.to_string(), span.style.patch(base_style)));
+        }
+        start = end;
+    }
+    Line::from(spans)
+}
+
+const GROUP_COLORS: [Color; 5] = [
+    Color::Yellow,
+    Color::Cyan,
+    Color::Magenta,
+    Color::Blue,
+    Color::Red,
+];
+
+fn capture_style(level: usize) -> Style {
+    match level {
+        0 => Style::default(),
+        1 => fg(Color::Green, Modifier::UNDERLINED).add_modifier(Modifier::BOLD),
+        group => fg(
+            GROUP_COLORS[(group - 2) % GROUP_COLORS.len()],
+            Modifier::REVERSED,
+        )
+        .add_modifier(Modifier::BOLD),
+    }
+}
+
+pub fn highlight_captures(content: &str, re: &Regex) -> Vec<Line<'static>> {
+    let mut levels = vec![0; content.len()];
+    for caps in re.captures_iter(content) {
+        for (i, group) in caps.iter().enumerate() {
+            if let Some(group) = group {
+                levels[group.range()].fill(i + 1);
+            }
+        }
+    }
+
+    let mut lines = Vec::new();
+    let mut start = 0;
+    for line in content.split('\n') {
+        let text = line.strip_suffix('\r').unwrap_or(line);
+        let spans = text
+            .char_indices()
+            .chunk_by(|(i, _)| levels[start + i])
+            .into_iter()
+            .map(|(level, chars)| {
+                Span::styled(
+                    chars.map(|(_, c)| c).collect::<String>(),
+                    capture_style(level),
+                )
+            })
+            .collect::<Vec<_>>();
+        lines.push(Line::from(spans));
+        start += line.len() + 1;
+    }
+    if content.ends_with('\n') {
+        lines.pop();
+    }
+    lines
+}
+
 pub fn highlight_diff_lines(original: String, replaced: String) -> Vec<Line<'static>> {
+    highlight_diff_lines_with(original, replaced, &DiffTheme::default())
+}
+
+pub fn highlight_diff_lines_with(
+    original: String,
+    replaced: String,
+    theme: &DiffTheme,
+) -> Vec<Line<'static>> {
     use itertools::EitherOrBoth::*;
+
+    let marked = |marker: &str, text: &str, style: Style| {
+        Line::from(vec![
+            Span::styled(marker.to_string(), style),
+            Span::styled(text.to_string(), style),
+        ])
+    };
+    let whitespace_style = if no_color() {
+        Style::default().add_modifier(Modifier::REVERSED)
+    } else {
+        Style::default().bg(Color::Red)
+    };
+    let is_unchanged = |pair: &EitherOrBoth<&str, &str>| matches!(pair, Both(l, r) if l == r);
+
+    let pairs = diff_pairs(&original, &replaced);
+
+    let mut lines = Vec::new();
+    let (mut old_line, mut new_line) = (1, 1);
+    let mut i = 0;
+    while i < pairs.len() {
+        if let Both(l, _) = pairs[i] {
+            if is_unchanged(&pairs[i]) {
+                lines.push(Line::from(Span::raw(l.to_string())));
+                old_line += 1;
+                new_line += 1;
+                i += 1;
+                continue;
+            }
+        }
+
+        let start = i;
+        while i < pairs.len() && !is_unchanged(&pairs[i]) {
+            i += 1;
+        }
+        let hunk = &pairs[start..i];
+        let removed = hunk.iter().filter(|pair| pair.has_left()).count();
+        let added = hunk.iter().filter(|pair| pair.has_right()).count();
+
+        if theme.hunk_headers {
+            lines.push(Line::from(Span::styled(
+                format!("@@ -{},{} +{},{} @@", old_line, removed, new_line, added),
+                fg(theme.hunk_color, Modifier::UNDERLINED),
+            )));
+        }
+        old_line += removed;
+        new_line += added;
+
+        for pair in hunk {
+            if let Some(l) = pair.clone().left() {
+                lines.push(marked(
+                    &theme.removed_marker,
+                    l,
+                    fg(theme.removed_color, Modifier::DIM),
+                ));
+            }
+            if let Some(r) = pair.clone().right() {
+                let mut line = marked(
+                    &theme.added_marker,
+                    r,
+                    fg(theme.added_color, Modifier::BOLD),
+                );
+                if adds_trailing_whitespace(pair) {
+                    let body = r.trim_end_matches([' ', '\t']);
+                    line.spans[1].content = body.to_string().into();
+                    line.spans
+                        .push(Span::styled(r[body.len()..].to_string(), whitespace_style));
+                }
+                lines.push(line);
+            }
+        }
+    }
+    lines
+}
+
+pub fn collapse_diff_lines(lines: Vec<Line<'static>>, rows: &[DiffRow]) -> Vec<Line<'static>> {
+    let fits = rows.iter().all(|row| match row {
+        DiffRow::Line(line) => *line < lines.len(),
+        DiffRow::Skipped(range) => range.end <= lines.len(),
+    });
+    if !fits {
+        return lines;
+    }
+    rows.iter()
+        .map(|row| match row {
+            DiffRow::Line(line) => lines[*line].clone(),
+            DiffRow::Skipped(range) => Line::from(Span::styled(
+                format!("⋯ {} unchanged lines", range.len()),
+                fg(Color::DarkGray, Modifier::DIM),
+            )),
+        })
+        .collect()
+}
+
+pub fn file_set_delta(before: &[String], after: &[String]) -> (usize, usize) {
+    let before: std::collections::HashSet<&String> = before.iter().collect();
+    let after: std::collections::HashSet<&String> = after.iter().collect();
+    (
+        before.difference(&after).count(),
+        after.difference(&before).count(),
+    )
+}
+
+pub fn byte_delta(original: &str, replaced: &str) -> isize {
+    replaced.len() as isize - original.len() as isize
+}
+
+pub fn format_byte_delta(delta: isize) -> String {
+    let unit = if delta.abs() == 1 { "byte" } else { "bytes" };
+    if delta > 0 {
+        format!("+{} {}", delta, unit)
+    } else {
+        format!("{} {}", delta, unit)
+    }
+}
+
+pub fn regex_error_message(err: &regex::Error) -> String {
+    let message = err.to_string();
+    let last = message.lines().last().unwrap_or_default();
+    last.strip_prefix("error: ").unwrap_or(last).to_string()
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    if duration < Duration::from_millis(1) {
+        format!("{} µs", duration.as_micros())
+    } else if duration < Duration::from_secs(1) {
+        format!("{} ms", duration.as_millis())
+    } else {
+        format!("{:.1} s", duration.as_secs_f64())
+    }
+}
+
+pub fn safe_slice_chars(text: &str, start_char: usize, end_char: usize) -> &str {
+    let byte_at = |char_index: usize| {
+        text.char_indices()
+            .nth(char_index)
+            .map_or(text.len(), |(byte, _)| byte)
+    };
+
+    let start_byte = byte_at(start_char);
+    let end_byte = byte_at(end_char.max(start_char));
+    &text[start_byte..end_byte]
+}
+
+pub fn first_changed_line(original: &str, replaced: &str) -> Option<(String, String)> {
     original
-        .lines()
-        .zip_longest(replaced.lines())
-        .flat_map(|pair| match pair {
-            Both(l, r) if l == r => vec![Line::from(Span::raw(l.to_string()))],
-            Both(l, r) => vec![
-                Line::from(vec![
-                    Span::styled("- ".to_string(), Style::default().fg(Color::Red)),
-                    Span::styled(l.to_string(), Style::default().fg(Color::Red)),
-                ]),
-                Line::from(vec![
-                    Span::styled("+ ".to_string(), Style::default().fg(Color::Green)),
-                    Span::styled(r.to_string(), Style::default().fg(Color::Green)),
-                ]),
-            ],
-            Left(l) => vec![Line::from(vec![
-                Span::styled("- ".to_string(), Style::default().fg(Color::Red)),
-                Span::styled(l.to_string(), Style::default().fg(Color::Red)),
-            ])],
-            Right(r) => vec![Line::from(vec![
-                Span::styled("+ ".to_string(), Style::default().fg(Color::Green)),
-                Span::styled(r.to_string(), Style::default().fg(Color::Green)),
-            ])],
+        .split('\n')
+        .zip_longest(replaced.split('\n'))
+        .map(|pair| pair.or_default())
+        .find(|(before, after)| before != after)
+        .map(|(before, after)| (before.trim().to_string(), after.trim().to_string()))
+}
+
+fn diff_pairs<'a>(original: &'a str, replaced: &'a str) -> Vec<EitherOrBoth<&'a str, &'a str>> {
+    align_lines(
+        &original.lines().collect::<Vec<_>>(),
+        &replaced.lines().collect::<Vec<_>>(),
+        |l, r| l == r,
+    )
+}
+
+fn trailing_whitespace(line: &str) -> &str {
+    let line = line.trim_end_matches(['\n', '\r']);
+    &line[line.trim_end_matches([' ', '\t']).len()..]
+}
+
+fn adds_trailing_whitespace(pair: &EitherOrBoth<&str, &str>) -> bool {
+    match pair {
+        EitherOrBoth::Both(l, r) => {
+            l != r && trailing_whitespace(l).is_empty() && !trailing_whitespace(r).is_empty()
+        }
+        EitherOrBoth::Right(r) => !trailing_whitespace(r).is_empty(),
+        EitherOrBoth::Left(_) => false,
+    }
+}
+
+pub fn added_trailing_whitespace(original: &str, replaced: &str) -> Vec<usize> {
+    let mut lines = Vec::new();
+    let mut line = 0;
+    for pair in diff_pairs(original, replaced) {
+        if pair.has_right() {
+            line += 1;
+            if adds_trailing_whitespace(&pair) {
+                lines.push(line);
+            }
+        }
+    }
+    lines
+}
+
+pub fn strip_changed_trailing_whitespace(original: &str, replaced: &str) -> String {
+    let pairs = align_lines(
+        &original.split_inclusive('\n').collect::<Vec<_>>(),
+        &replaced.split_inclusive('\n').collect::<Vec<_>>(),
+        |l, r| l.trim_end_matches('\n') == r.trim_end_matches('\n'),
+    );
+    pairs
+        .into_iter()
+        .filter_map(|pair| match pair {
+            EitherOrBoth::Both(l, r) if l.trim_end_matches('\n') == r.trim_end_matches('\n') => {
+                Some(r.to_string())
+            }
+            pair => pair.right().map(|line| {
+                let ending = &line[line.trim_end_matches(['\n', '\r']).len()..];
+                let body = &line[..line.len() - ending.len()];
+                format!("{}{}", body.trim_end_matches([' ', '\t']), ending)
+            }),
         })
         .collect()
 }
 
+pub fn substitution_lines(
+    content: &str,
+    re: &Regex,
+    to_replacement: &str,
+    options: &SubstitutionOptions,
+) -> Vec<usize> {
+    let changes = |caps: &Captures| {
+        let matched = caps.get(0).unwrap().as_str();
+        if options.literal {
+            to_replacement != matched
+        } else {
+            expand_replacement(caps, to_replacement) != matched
+        }
+    };
+    let spans_in = |text: &str, offset: usize| {
+        re.captures_iter(text)
+            .filter(|caps| changes(caps))
+            .map(|caps| {
+                let matched = caps.get(0).unwrap();
+                (offset + matched.start(), offset + matched.end())
+            })
+            .collect::<Vec<_>>()
+    };
+    let spans = if options.keep_indent {
+        let mut offset = 0;
+        let mut spans = Vec::new();
+        for line in content.split_inclusive('\n') {
+            let (indent, body, _) = split_indent(line);
+            spans.extend(spans_in(body, offset + indent.len()));
+            offset += line.len();
+        }
+        spans
+    } else {
+        spans_in(content, 0)
+    };
+
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let line_of = |offset: usize| line_starts.partition_point(|&start| start <= offset);
+    let mut lines: Vec<usize> = Vec::new();
+    for (start, end) in spans {
+        for line in line_of(start)..=line_of(end.saturating_sub(1).max(start)) {
+            if lines.last().is_none_or(|&last| last < line) {
+                lines.push(line);
+            }
+        }
+    }
+    lines
+}
+
+pub fn changed_lines(original: &str, replaced: &str) -> Vec<usize> {
+    let mut lines = Vec::new();
+    let mut line = 0;
+    for pair in diff_pairs(original, replaced) {
+        if !matches!(pair, EitherOrBoth::Both(l, r) if l == r) && lines.last() != Some(&(line + 1))
+        {
+            lines.push(line + 1);
+        }
+        if pair.has_left() {
+            line += 1;
+        }
+    }
+    lines
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SubstitutionOptions {
+    pub literal: bool,
+    pub anchor_start: bool,
+    pub anchor_end: bool,
+    pub keep_indent: bool,
+    pub strip_trailing_whitespace: bool,
+    pub case_insensitive: bool,
+    pub multiline: bool,
+    pub dotall: bool,
+}
+
+pub fn effective_pattern(from_pattern: &str, options: &SubstitutionOptions) -> String {
+    let pattern = anchored_pattern(from_pattern, options);
+    let flags = [
+        (options.case_insensitive, 'i'),
+        (options.multiline, 'm'),
+        (options.dotall, 's'),
+    ]
+    .into_iter()
+    .filter_map(|(on, flag)| on.then_some(flag))
+    .collect::<String>();
+    if flags.is_empty() {
+        pattern
+    } else {
+        format!("(?{}){}", flags, pattern)
+    }
+}
+
+fn anchored_pattern(from_pattern: &str, options: &SubstitutionOptions) -> String {
+    let pattern = if options.literal {
+        regex::escape(from_pattern)
+    } else {
+        from_pattern.to_string()
+    };
+    if !options.anchor_start && !options.anchor_end {
+        return pattern;
+    }
+
+    let mut inner = pattern.as_str();
+    if options.anchor_start {
+        inner = inner.strip_prefix('^').unwrap_or(inner);
+    }
+    if options.anchor_end && inner.ends_with('$') && !inner.ends_with("\\$") {
+        inner = &inner[..inner.len() - 1];
+    }
+    format!(
+        "(?mR){}(?:{}){}",
+        if options.anchor_start { "^" } else { "" },
+        inner,
+        if options.anchor_end { "$" } else { "" }
+    )
+}
+
 pub fn apply_substitution_partial(
     content: &str,
     from_pattern: &str,
     to_replacement: &str,
 ) -> String {
-    let re = Regex::new(from_pattern).unwrap_or_else(|_| Regex::new("$^").unwrap());
+    apply_substitution_with(
+        content,
+        from_pattern,
+        to_replacement,
+        &SubstitutionOptions::default(),
+    )
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Case {
+    Upper,
+    Lower,
+    UpperNext,
+    LowerNext,
+    End,
+}
+
+enum Escape {
+    Char(char),
+    Case(Case),
+    Unknown,
+    Invalid,
+}
+
+fn parse_escape(text: &str) -> (Escape, usize) {
+    let rest = &text[1..];
+    let Some(c) = rest.chars().next() else {
+        return (Escape::Invalid, 1);
+    };
+    let simple = match c {
+        'n' => Some('\n'),
+        't' => Some('\t'),
+        'r' => Some('\r'),
+        '0' => Some('\0'),
+        '\\' => Some('\\'),
+        _ => None,
+    };
+    if let Some(c) = simple {
+        return (Escape::Char(c), 2);
+    }
+
+    match c {
+        'U' => (Escape::Case(Case::Upper), 2),
+        'L' => (Escape::Case(Case::Lower), 2),
+        'E' => (Escape::Case(Case::End), 2),
+        'l' => (Escape::Case(Case::LowerNext), 2),
+        'u' if !rest[1..].starts_with('{') => (Escape::Case(Case::UpperNext), 2),
+        'x' => {
+            let digits = rest[1..]
+                .chars()
+                .take(2)
+                .take_while(char::is_ascii_hexdigit)
+                .count();
+            if digits < 2 {
+                return (Escape::Invalid, 2 + digits);
+            }
+            match u8::from_str_radix(&rest[1..3], 16) {
+                Ok(byte) if byte.is_ascii() => (Escape::Char(byte as char), 4),
+                _ => (Escape::Invalid, 4),
+            }
+        }
+        'u' => {
+            let Some(end) = rest[1..]
+                .strip_prefix('{')
+                .and_then(|body| body.find('}'))
+                .filter(|&end| end <= 8)
+            else {
+                return (Escape::Invalid, 2);
+            };
+            let digits = &rest[2..2 + end];
+            let len = 4 + end;
+            let decoded = (!digits.is_empty() && digits.len() <= 6)
+                .then(|| u32::from_str_radix(digits, 16).ok())
+                .flatten()
+                .and_then(char::from_u32);
+            match decoded {
+                Some(c) => (Escape::Char(c), len),
+                None => (Escape::Invalid, len),
+            }
+        }
+        _ => (Escape::Unknown, 1 + c.len_utf8()),
+    }
+}
+
+pub fn unescape_replacement(text: &str) -> (String, Vec<String>) {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut invalid = Vec::new();
+    let mut rest = text;
+    while let Some(index) = rest.find('\\') {
+        unescaped.push_str(&rest[..index]);
+        rest = &rest[index..];
+        let (escape, len) = parse_escape(rest);
+        match escape {
+            Escape::Char(c) => unescaped.push(c),
+            Escape::Case(_) | Escape::Unknown => unescaped.push_str(&rest[..len]),
+            Escape::Invalid => {
+                invalid.push(rest[..len].to_string());
+                unescaped.push_str(&rest[..len]);
+            }
+        }
+        rest = &rest[len..];
+    }
+    unescaped.push_str(rest);
+    (unescaped, invalid)
+}
+
+#[derive(Default)]
+struct CaseWriter {
+    text: String,
+    region: Option<Case>,
+    next: Option<Case>,
+}
+
+impl CaseWriter {
+    fn set(&mut self, case: Case) {
+        match case {
+            Case::Upper | Case::Lower => self.region = Some(case),
+            Case::End => self.region = None,
+            Case::UpperNext | Case::LowerNext => self.next = Some(case),
+        }
+    }
+
+    fn push_str(&mut self, text: &str) {
+        if self.region.is_none() && self.next.is_none() {
+            self.text.push_str(text);
+            return;
+        }
+        for c in text.chars() {
+            match self.next.take().or(self.region) {
+                Some(Case::Upper | Case::UpperNext) => self.text.extend(c.to_uppercase()),
+                Some(Case::Lower | Case::LowerNext) => self.text.extend(c.to_lowercase()),
+                _ => self.text.push(c),
+            }
+        }
+    }
+}
+
+pub fn expand_replacement(caps: &Captures, template: &str) -> String {
+    let group = |i: usize| caps.get(i).map_or("", |m| m.as_str());
+    let mut replaced = CaseWriter {
+        text: String::with_capacity(template.len()),
+        ..Default::default()
+    };
+    let mut rest = template;
+    while let Some(index) = rest.find(['$', '\\']) {
+        replaced.push_str(&rest[..index]);
+        rest = &rest[index..];
+        if rest.starts_with('\\') {
+            let (escape, len) = parse_escape(rest);
+            match escape {
+                Escape::Char(c) => replaced.push_str(c.encode_utf8(&mut [0; 4])),
+                Escape::Case(case) => replaced.set(case),
+                Escape::Unknown | Escape::Invalid => replaced.push_str(&rest[..len]),
+            }
+            rest = &rest[len..];
+            continue;
+        }
+
+        let after = &rest[1..];
+        if let Some(tail) = after.strip_prefix('$') {
+            replaced.push_str("$");
+            rest = tail;
+            continue;
+        }
+        if let Some((name, tail)) = after
+            .strip_prefix('{')
+            .and_then(|body| body.split_once('}'))
+        {
+            replaced.push_str(match name.parse::<usize>() {
+                Ok(i) => group(i),
+                Err(_) => caps.name(name).map_or("", |m| m.as_str()),
+            });
+            rest = tail;
+            continue;
+        }
+        let digits = after.len() - after.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        if digits == 0 {
+            replaced.push_str("$");
+            rest = after;
+            continue;
+        }
+        let (len, i) = (1..=digits)
+            .rev()
+            .find_map(|len| {
+                after[..len]
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&i| i < caps.len())
+                    .map(|i| (len, i))
+            })
+            .unwrap_or((digits, caps.len()));
+        replaced.push_str(group(i));
+        rest = &after[len..];
+    }
+    replaced.push_str(rest);
+    replaced.text
+}
+
+pub fn already_applied(
+    content: &str,
+    re: &Regex,
+    to_replacement: &str,
+    options: &SubstitutionOptions,
+) -> bool {
+    let mut matches = re
+        .captures_iter(content)
+        .filter(|caps| !caps.get(0).unwrap().is_empty())
+        .peekable();
+    matches.peek().is_some()
+        && matches.all(|caps| {
+            let matched = caps.get(0).unwrap();
+            let replacement = if options.literal {
+                to_replacement.to_string()
+            } else {
+                expand_replacement(&caps, to_replacement)
+            };
+            replacement != matched.as_str()
+                && replacement
+                    .match_indices(matched.as_str())
+                    .any(|(offset, _)| {
+                        matched
+                            .start()
+                            .checked_sub(offset)
+                            .and_then(|start| content.get(start..))
+                            .is_some_and(|rest| rest.starts_with(&replacement))
+                    })
+        })
+}
+
+pub fn apply_substitution_with(
+    content: &str,
+    from_pattern: &str,
+    to_replacement: &str,
+    options: &SubstitutionOptions,
+) -> String {
+    let re = Regex::new(&effective_pattern(from_pattern, options))
+        .unwrap_or_else(|_| Regex::new("$^").unwrap());
+    apply_regex_substitution(content, &re, to_replacement, options)
+}
 
-    re.replace_all(content, |caps: &Captures| {
-        let mut replaced = to_replacement.to_string();
-        for i in 1..caps.len() {
-            let group_ref = format!("${}", i);
-            replaced = replaced.replace(&group_ref, caps.get(i).map_or("", |m| m.as_str()));
+pub fn apply_regex_substitution(
+    content: &str,
+    re: &Regex,
+    to_replacement: &str,
+    options: &SubstitutionOptions,
+) -> String {
+    let substitute = |text: &str| {
+        if options.literal {
+            return re.replace_all(text, NoExpand(to_replacement)).to_string();
         }
+
+        re.replace_all(text, |caps: &Captures| {
+            expand_replacement(caps, to_replacement)
+        })
+        .to_string()
+    };
+
+    let replaced = if options.keep_indent {
+        content
+            .split_inclusive('\n')
+            .map(|line| reindent(line, substitute))
+            .collect()
+    } else {
+        substitute(content)
+    };
+    if options.strip_trailing_whitespace {
+        strip_changed_trailing_whitespace(content, &replaced)
+    } else {
         replaced
+    }
+}
+
+pub fn substitution_is_match(content: &str, re: &Regex, options: &SubstitutionOptions) -> bool {
+    if options.keep_indent {
+        content
+            .split_inclusive('\n')
+            .any(|line| re.is_match(split_indent(line).1))
+    } else {
+        re.is_match(content)
+    }
+}
+
+fn split_indent(line: &str) -> (&str, &str, &str) {
+    let body = line.trim_start_matches([' ', '\t']);
+    let indent = &line[..line.len() - body.len()];
+    match body.strip_suffix('\n') {
+        Some(body) => (indent, body, "\n"),
+        None => (indent, body, ""),
+    }
+}
+
+fn reindent(line: &str, substitute: impl Fn(&str) -> String) -> String {
+    let (indent, body, newline) = split_indent(line);
+
+    let replaced = substitute(body);
+    if replaced == body {
+        return line.to_string();
+    }
+    let replaced = replaced.trim_start_matches([' ', '\t']);
+    format!(
+        "{}{}{}",
+        indent,
+        replaced.replace('\n', &format!("\n{}", indent)),
+        newline
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MatchSummary {
+    pub first_line: usize,
+    pub count: usize,
+}
+
+impl MatchSummary {
+    pub fn label(&self) -> String {
+        if self.count == 1 {
+            format!("L{}", self.first_line)
+        } else {
+            format!("L{}, {} matches", self.first_line, self.count)
+        }
+    }
+}
+
+pub fn match_summary(content: &str, re: &Regex) -> Option<MatchSummary> {
+    let mut matches = re.find_iter(content);
+    let first = matches.next()?;
+
+    Some(MatchSummary {
+        first_line: content[..first.start()].matches('\n').count() + 1,
+        count: 1 + matches.count(),
     })
-    .to_string()
 }
 
-pub fn is_text_file(path: &std::path::Path) -> bool {
-    use std::fs::File;
-    use std::io::Read;
+pub fn first_match_captures(content: &str, re: &Regex) -> Option<String> {
+    if re.captures_len() < 2 {
+        return None;
+    }
+    let caps = re.captures(content)?;
+    Some(
+        (1..caps.len())
+            .map(|i| match caps.get(i) {
+                Some(group) => format!("${}={:?}", i, group.as_str()),
+                None => format!("${}=(unmatched)", i),
+            })
+            .join(" "),
+    )
+}
 
-    const BUFFER_SIZE: usize = 4096;
+pub fn expand_path_tokens(template: &str, path: &str) -> String {
+    expand_path_tokens_with(template, path, str::to_string)
+}
+
+/// Like `expand_path_tokens`, but escapes `\` and `$` in the inserted values
+/// so `expand_replacement` copies them literally instead of reading them as
+/// escapes or group references.
+pub fn expand_path_tokens_escaped(template: &str, path: &str) -> String {
+    expand_path_tokens_with(template, path, |value| {
+        value.replace('\\', "\\\\").replace('$', "$$")
+    })
+}
+
+fn expand_path_tokens_with(template: &str, path: &str, escape: impl Fn(&str) -> String) -> String {
+    let path_ref = std::path::Path::new(path);
+    let stem = path_ref
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let dir = path_ref
+        .parent()
+        .map(|p| p.display().to_string())
+        .unwrap_or_default();
+
+    let tokens = [
+        ("\\filestem", escape(&stem)),
+        ("\\file", escape(path)),
+        ("\\dir", escape(&dir)),
+    ];
+
+    let mut expanded = String::with_capacity(template.len());
+    let mut rest = template;
+    'outer: while !rest.is_empty() {
+        for (token, value) in &tokens {
+            if let Some(after) = rest.strip_prefix(token) {
+                expanded.push_str(value);
+                rest = after;
+                continue 'outer;
+            }
+        }
+        let c = rest.chars().next().unwrap();
+        expanded.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    expanded
+}
+
+pub fn copy_target(path: &str) -> std::path::PathBuf {
+    let first = std::path::PathBuf::from(format!("{}.new", path));
+    std::iter::once(first)
+        .chain((1..).map(|n| std::path::PathBuf::from(format!("{}.new.{}", path, n))))
+        .find(|candidate| !candidate.exists())
+        .unwrap_or_default()
+}
+
+pub fn is_text_file(path: &std::path::Path, sample_bytes: usize) -> bool {
+    use std::fs::File;
+    use std::io::{Read, Seek, SeekFrom};
 
     let mut file = match File::open(path) {
         Ok(file) => file,
         Err(_) => return false,
     };
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
 
-    let mut buffer = [0u8; BUFFER_SIZE];
-    let n = match file.read(&mut buffer) {
-        Ok(n) => n,
-        Err(_) => return false,
+    let mut offsets = vec![0];
+    if len > 2 * sample_bytes as u64 {
+        offsets.push(len / 2);
+    }
+
+    let mut buffer = vec![0u8; sample_bytes];
+    offsets.into_iter().all(|offset| {
+        if file.seek(SeekFrom::Start(offset)).is_err() {
+            return false;
+        }
+        match file.read(&mut buffer) {
+            Ok(n) => !buffer[..n].contains(&0),
+            Err(_) => false,
+        }
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Preview {
+    pub content: String,
+    pub truncated: bool,
+    pub hex: bool,
+}
+
+pub fn read_preview(path: &str, limit: usize) -> Option<Preview> {
+    use std::fs::File;
+    use std::io::Read;
+
+    let file = File::open(path).ok()?;
+    let mut bytes = Vec::new();
+    file.take(limit as u64 + 1).read_to_end(&mut bytes).ok()?;
+
+    let truncated = bytes.len() > limit;
+    if truncated {
+        bytes.truncate(limit);
+        if let Some(newline) = bytes.iter().rposition(|b| *b == b'\n') {
+            bytes.truncate(newline + 1);
+        }
+    }
+
+    let content = match String::from_utf8(bytes) {
+        Ok(content) => content,
+        Err(err) if truncated => {
+            let valid_up_to = err.utf8_error().valid_up_to();
+            let mut bytes = err.into_bytes();
+            bytes.truncate(valid_up_to);
+            String::from_utf8(bytes).ok()?
+        }
+        Err(_) => return None,
     };
 
-    !buffer[..n].contains(&0)
+    Some(Preview {
+        content,
+        truncated,
+        hex: false,
+    })
+}
+
+pub fn hex_dump(bytes: &[u8]) -> String {
+    bytes
+        .chunks(16)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let mut hex = String::new();
+            for j in 0..16 {
+                match chunk.get(j) {
+                    Some(byte) => hex.push_str(&format!("{:02x} ", byte)),
+                    None => hex.push_str("   "),
+                }
+                if j == 7 {
+                    hex.push(' ');
+                }
+            }
+            let ascii = chunk
+                .iter()
+                .map(|&byte| {
+                    if byte.is_ascii_graphic() || byte == b' ' {
+                        byte as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect::<String>();
+            format!("{:08x}  {} |{}|\n", i * 16, hex, ascii)
+        })
+        .collect()
+}
+
+pub fn read_hex_preview(path: &str, limit: usize) -> Option<Preview> {
+    use std::fs::File;
+    use std::io::Read;
+
+    let file = File::open(path).ok()?;
+    let mut bytes = Vec::new();
+    file.take(limit as u64 + 1).read_to_end(&mut bytes).ok()?;
+
+    let truncated = bytes.len() > limit;
+    bytes.truncate(limit);
+
+    Some(Preview {
+        content: hex_dump(&bytes),
+        truncated,
+        hex: true,
+    })
 }
@@ -0,0 +1,62 @@
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+pub const SELF_WRITE_WINDOW: Duration = Duration::from_millis(1500);
+
+type Stamp = Option<(u64, SystemTime)>;
+
+#[derive(Debug)]
+pub struct RecentWrites {
+    writes: RwLock<HashMap<PathBuf, (Instant, Stamp)>>,
+    window: Duration,
+}
+
+impl Default for RecentWrites {
+    fn default() -> Self {
+        Self::with_window(SELF_WRITE_WINDOW)
+    }
+}
+
+impl RecentWrites {
+    pub fn with_window(window: Duration) -> Self {
+        Self {
+            writes: RwLock::new(HashMap::new()),
+            window,
+        }
+    }
+
+    /// Call after writing `path`; events are only ignored while the file
+    /// keeps the length and modification time it had right after the write.
+    pub fn record(&self, path: &Path) {
+        let now = Instant::now();
+        let mut writes = self.writes.write();
+        writes.retain(|_, (at, _)| now.duration_since(*at) < self.window);
+        writes.insert(absolute(path), (now, stamp(path)));
+    }
+
+    pub fn contains(&self, path: &Path) -> bool {
+        self.writes
+            .read()
+            .get(&absolute(path))
+            .is_some_and(|(at, written)| at.elapsed() < self.window && *written == stamp(path))
+    }
+}
+
+fn stamp(path: &Path) -> Stamp {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.len(), metadata.modified().ok()?))
+}
+
+fn absolute(path: &Path) -> PathBuf {
+    let joined = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir().unwrap_or_default().join(path)
+    };
+    joined
+        .components()
+        .filter(|component| *component != Component::CurDir)
+        .collect()
+}
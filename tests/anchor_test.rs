@@ -0,0 +1,92 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::App;
+use ised::utils::{apply_substitution_with, effective_pattern, SubstitutionOptions};
+use std::fs;
+use tempdir::TempDir;
+
+fn anchored(start: bool, end: bool) -> SubstitutionOptions {
+    SubstitutionOptions {
+        anchor_start: start,
+        anchor_end: end,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_anchor_toggles_wrap_the_pattern() {
+    assert_eq!(effective_pattern("foo", &anchored(false, false)), "foo");
+    assert_eq!(
+        effective_pattern("foo", &anchored(true, false)),
+        "(?mR)^(?:foo)"
+    );
+    assert_eq!(
+        effective_pattern("a|b", &anchored(true, true)),
+        "(?mR)^(?:a|b)$"
+    );
+}
+
+#[test]
+fn test_typed_anchors_are_not_doubled() {
+    assert_eq!(
+        effective_pattern("^foo$", &anchored(true, true)),
+        "(?mR)^(?:foo)$"
+    );
+    assert_eq!(
+        effective_pattern(r"cost\$", &anchored(false, true)),
+        r"(?mR)(?:cost\$)$"
+    );
+
+    let literal = SubstitutionOptions {
+        literal: true,
+        ..anchored(true, true)
+    };
+    assert_eq!(effective_pattern("^a$", &literal), r"(?mR)^(?:\^a\$)$");
+}
+
+#[test]
+fn test_anchored_matching() {
+    let content = "foo foo\nbar foo\r\nfoo";
+
+    assert_eq!(
+        apply_substitution_with(content, "foo", "X", &anchored(true, false)),
+        "X foo\nbar foo\r\nX"
+    );
+    assert_eq!(
+        apply_substitution_with(content, "foo", "X", &anchored(false, true)),
+        "foo X\nbar X\r\nX"
+    );
+    assert_eq!(
+        apply_substitution_with(content, "foo", "X", &anchored(true, true)),
+        "foo foo\nbar foo\r\nX"
+    );
+    assert_eq!(
+        apply_substitution_with(content, "(\\w+) foo", "$1", &anchored(true, true)),
+        "foo\nbar\r\nfoo"
+    );
+}
+
+#[test]
+fn test_anchor_keys_toggle_and_filter() {
+    let tmp_dir = TempDir::new("ised_test_anchor").unwrap();
+    let path = tmp_dir.path().join("a.txt");
+    fs::write(&path, "  indented foo\n").unwrap();
+
+    let mut app = App::with_config(None);
+    app.files = vec![path.display().to_string()];
+    app.from_input = "indented".to_string();
+    assert_eq!(app.filter_files().len(), 1);
+    let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::ALT);
+
+    app.handle_key_event(key('h'), &[]).unwrap();
+    assert!(app.options.anchor_start);
+    assert!(app.filter_files().is_empty());
+
+    app.handle_key_event(key('h'), &[]).unwrap();
+    app.handle_key_event(key('t'), &[]).unwrap();
+    assert!(!app.options.anchor_start);
+    assert!(app.options.anchor_end);
+    assert!(app.filter_files().is_empty());
+
+    app.from_input = "foo".to_string();
+    assert_eq!(app.filter_files().len(), 1);
+}
@@ -0,0 +1,169 @@
+use ised::app::App;
+use std::fs;
+use tempdir::TempDir;
+
+fn write_fixture(dir: &TempDir, name: &str, content: &str) -> String {
+    let path = dir.path().join(name);
+    fs::write(&path, content).unwrap();
+    path.display().to_string()
+}
+
+#[test]
+fn test_affected_files_lists_only_files_with_matches() {
+    let tmp_dir = TempDir::new("ised_test_affected").unwrap();
+    let a = write_fixture(&tmp_dir, "a.txt", "foo bar");
+    let b = write_fixture(&tmp_dir, "b.txt", "nothing here");
+    let c = write_fixture(&tmp_dir, "c.txt", "another foo");
+
+    let mut app = App::new();
+    app.from_input = "foo".to_string();
+
+    let affected = app.affected_files(&[a.clone(), b, c.clone()]);
+
+    assert_eq!(affected, vec![a, c]);
+}
+
+#[test]
+fn test_affected_files_empty_with_invalid_pattern() {
+    let tmp_dir = TempDir::new("ised_test_affected_invalid").unwrap();
+    let a = write_fixture(&tmp_dir, "a.txt", "foo(");
+
+    let mut app = App::new();
+    app.from_input = "foo(".to_string();
+
+    assert!(app.affected_files(&[a]).is_empty());
+}
+
+#[test]
+fn test_deselected_files_are_skipped_in_batch() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use ised::app::ConfirmState;
+
+    let tmp_dir = TempDir::new("ised_test_deselect").unwrap();
+    let a = write_fixture(&tmp_dir, "a.txt", "foo");
+    let b = write_fixture(&tmp_dir, "b.txt", "foo");
+    let files = vec![a.clone(), b.clone()];
+
+    let mut app = App::new();
+    app.from_input = "foo".to_string();
+    app.to_input = "bar".to_string();
+
+    app.handle_key_event(KeyEvent::from(KeyCode::Char(' ')), &files)
+        .unwrap();
+    assert!(app.deselected.contains(&a));
+    assert_eq!(app.batch_files(&files), vec![b.clone()]);
+
+    app.handle_key_event(
+        KeyEvent::new(KeyCode::Char('a'), KeyModifiers::CONTROL),
+        &files,
+    )
+    .unwrap();
+    assert!(
+        matches!(&app.confirm, ConfirmState::ConfirmingAll(paths) if *paths == vec![b.clone()])
+    );
+
+    app.handle_key_event(KeyEvent::from(KeyCode::Char('y')), &files)
+        .unwrap();
+
+    assert_eq!(fs::read_to_string(&a).unwrap(), "foo");
+    assert_eq!(fs::read_to_string(&b).unwrap(), "bar");
+}
+
+#[test]
+fn test_cache_holds_replaced_content_after_apply() {
+    let tmp_dir = TempDir::new("ised_test_cache_after_apply").unwrap();
+    let a = write_fixture(&tmp_dir, "a.txt", "foo foo");
+
+    let mut app = App::new();
+    app.from_input = "foo".to_string();
+    app.to_input = "bar".to_string();
+
+    assert_eq!(
+        app.affected_files(std::slice::from_ref(&a)),
+        vec![a.clone()]
+    );
+    assert_eq!(app.cached_file_content(&a).as_deref(), Some("foo foo"));
+
+    app.apply_all(std::slice::from_ref(&a));
+
+    assert_eq!(app.cached_file_content(&a).as_deref(), Some("bar bar"));
+    assert!(app.affected_files(&[a]).is_empty());
+}
+
+#[test]
+fn test_applied_files_are_marked_modified() {
+    let tmp_dir = TempDir::new("ised_test_modified").unwrap();
+    let a = write_fixture(&tmp_dir, "a.txt", "foo");
+    let b = write_fixture(&tmp_dir, "b.txt", "bar");
+
+    let mut app = App::new();
+    app.from_input = "foo".to_string();
+    app.to_input = "baz".to_string();
+
+    app.apply_all(&[a.clone(), b.clone()]);
+
+    assert!(app.is_modified(&a));
+    assert!(!app.is_modified(&b));
+    assert_eq!(app.modified_count(), 1);
+}
+
+#[test]
+fn test_noop_substitution_leaves_bytes_and_mtime_untouched() {
+    let tmp_dir = TempDir::new("ised_test_noop").unwrap();
+    let original = b"foo\r\nbar\r\nfoo";
+    let path = tmp_dir.path().join("crlf.txt");
+    fs::write(&path, original).unwrap();
+
+    let old = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000);
+    fs::File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(old)
+        .unwrap();
+
+    let mut app = App::new();
+    app.from_input = "(foo)".to_string();
+    app.to_input = "$1".to_string();
+    let path_str = path.display().to_string();
+    let report = app.apply_all(std::slice::from_ref(&path_str));
+
+    assert_eq!(report.summary(), "0 changed, 1 unchanged, 0 errors");
+    assert_eq!(fs::read(&path).unwrap(), original);
+    assert_eq!(fs::metadata(&path).unwrap().modified().unwrap(), old);
+    assert!(!app.is_modified(&path_str));
+}
+
+#[test]
+fn test_apply_from_selected_file_onward() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use ised::app::ConfirmState;
+
+    let tmp_dir = TempDir::new("ised_test_apply_from").unwrap();
+    let a = write_fixture(&tmp_dir, "a.txt", "foo");
+    let b = write_fixture(&tmp_dir, "b.txt", "foo");
+    let c = write_fixture(&tmp_dir, "c.txt", "foo");
+    let files = vec![a.clone(), b.clone(), c.clone()];
+
+    let mut app = App::new();
+    app.from_input = "foo".to_string();
+    app.to_input = "bar".to_string();
+    app.selected = 1;
+
+    app.handle_key_event(
+        KeyEvent::new(KeyCode::Char('q'), KeyModifiers::CONTROL),
+        &files,
+    )
+    .unwrap();
+    assert!(matches!(
+        &app.confirm,
+        ConfirmState::ConfirmingFrom(paths) if *paths == vec![b.clone(), c.clone()]
+    ));
+
+    app.handle_key_event(KeyEvent::from(KeyCode::Char('y')), &files)
+        .unwrap();
+
+    assert_eq!(fs::read_to_string(&a).unwrap(), "foo");
+    assert_eq!(fs::read_to_string(&b).unwrap(), "bar");
+    assert_eq!(fs::read_to_string(&c).unwrap(), "bar");
+}
@@ -0,0 +1,69 @@
+use ised::app::App;
+use ised::utils::{byte_delta, format_byte_delta};
+use std::fs;
+use tempdir::TempDir;
+
+#[test]
+fn test_byte_delta_of_known_content() {
+    assert_eq!(byte_delta("foo bar", "foobar baz"), 3);
+    assert_eq!(byte_delta("remove me\n", "\n"), -9);
+    assert_eq!(byte_delta("café", "cafe"), -1);
+    assert_eq!(byte_delta("same", "same"), 0);
+}
+
+#[test]
+fn test_format_byte_delta() {
+    assert_eq!(format_byte_delta(42), "+42 bytes");
+    assert_eq!(format_byte_delta(-17), "-17 bytes");
+    assert_eq!(format_byte_delta(1), "+1 byte");
+    assert_eq!(format_byte_delta(0), "0 bytes");
+}
+
+#[test]
+fn test_per_file_and_total_byte_delta() {
+    let tmp_dir = TempDir::new("ised_test_byte_delta").unwrap();
+    let grows = tmp_dir.path().join("grows.txt");
+    let shrinks = tmp_dir.path().join("shrinks.txt");
+    let untouched = tmp_dir.path().join("untouched.txt");
+    fs::write(&grows, "a a a\n").unwrap();
+    fs::write(&shrinks, "aaaa\n").unwrap();
+    fs::write(&untouched, "b\n").unwrap();
+    let files = [&grows, &shrinks, &untouched]
+        .iter()
+        .map(|f| f.display().to_string())
+        .collect::<Vec<_>>();
+
+    let mut app = App::new();
+    app.files = files.clone();
+    app.is_loading = false;
+    app.from_input = "a+".to_string();
+    app.to_input = "xx".to_string();
+
+    assert_eq!(
+        app.byte_delta_status(&files[0]).as_deref(),
+        Some("+3 bytes")
+    );
+    assert_eq!(
+        app.byte_delta_status(&files[1]).as_deref(),
+        Some("-2 bytes")
+    );
+    assert_eq!(app.byte_delta_status(&files[2]), None);
+    assert_eq!(
+        app.total_byte_delta(&files).as_deref(),
+        Some("Net +1 byte across 2 changed files")
+    );
+    app.to_input = "x".to_string();
+    assert_eq!(
+        app.total_byte_delta(&files).as_deref(),
+        Some("Net -3 bytes across 2 changed files")
+    );
+
+    app.apply_all(&files[..1]);
+    assert_eq!(
+        app.total_byte_delta(&files).as_deref(),
+        Some("Net -3 bytes across 1 changed file")
+    );
+
+    app.from_input.clear();
+    assert_eq!(app.total_byte_delta(&files), None);
+}
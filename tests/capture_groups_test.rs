@@ -0,0 +1,139 @@
+use ised::app::{App, Focus};
+use ised::utils::{apply_substitution_partial, first_match_captures};
+use regex::Regex;
+use std::fs;
+use tempdir::TempDir;
+
+fn group_count(pattern: &str) -> Option<usize> {
+    let mut app = App::new();
+    app.from_input = pattern.to_string();
+    app.from_group_count()
+}
+
+#[test]
+fn test_group_count() {
+    assert_eq!(group_count(""), None);
+    assert_eq!(group_count("foo"), Some(0));
+    assert_eq!(group_count("(foo)(bar)"), Some(2));
+    assert_eq!(group_count("((a)(b))"), Some(3));
+    assert_eq!(group_count("(?:foo)(bar)"), Some(1));
+    assert_eq!(group_count("(?P<name>\\w+)"), Some(1));
+    assert_eq!(group_count("(unclosed"), None);
+}
+
+#[test]
+fn test_group_count_in_literal_mode() {
+    let mut app = App::new();
+    app.from_input = "(foo)".to_string();
+    app.options.literal = true;
+    assert_eq!(app.from_group_count(), Some(0));
+}
+
+#[test]
+fn test_first_match_captures() {
+    let re = Regex::new(r"(\w+)=(\d+)?(;)").unwrap();
+    assert_eq!(
+        first_match_captures("skip\nkey=42; other=7;", &re).as_deref(),
+        Some(r#"$1="key" $2="42" $3=";""#)
+    );
+    assert_eq!(
+        first_match_captures("flag=;", &re).as_deref(),
+        Some(r#"$1="flag" $2=(unmatched) $3=";""#)
+    );
+    assert_eq!(first_match_captures("nothing", &re), None);
+    assert_eq!(
+        first_match_captures("a\tb", &Regex::new(r"(a\tb)").unwrap()).as_deref(),
+        Some(r#"$1="a\tb""#)
+    );
+    assert_eq!(first_match_captures("abc", &Regex::new("b").unwrap()), None);
+}
+
+#[test]
+fn test_capture_summary_follows_the_selected_file() {
+    let tmp_dir = TempDir::new("ised_test_capture_summary").unwrap();
+    let files: Vec<String> = [("a.rs", "fn alpha() {}"), ("b.rs", "fn beta() {}")]
+        .iter()
+        .map(|(name, content)| {
+            let path = tmp_dir.path().join(name);
+            fs::write(&path, content).unwrap();
+            path.display().to_string()
+        })
+        .collect();
+
+    let mut app = App::new();
+    app.files = files.clone();
+    app.from_input = r"fn (\w+)\(".to_string();
+    assert_eq!(app.capture_summary(&files), None);
+
+    app.focus = Focus::From;
+    assert_eq!(
+        app.capture_summary(&files).as_deref(),
+        Some(r#"First match: $1="alpha""#)
+    );
+    app.selected = 1;
+    assert_eq!(
+        app.capture_summary(&files).as_deref(),
+        Some(r#"First match: $1="beta""#)
+    );
+}
+
+#[test]
+fn test_named_groups_in_to() {
+    assert_eq!(
+        apply_substitution_partial("10-20 3-4", r"(?P<a>\d+)-(?P<b>\d+)", "${b}-${a}"),
+        "20-10 4-3"
+    );
+    assert_eq!(
+        apply_substitution_partial("10-20", r"(?P<a>\d+)-(?P<b>\d+)", "$2/${a}/${1}"),
+        "20/10/10"
+    );
+    assert_eq!(
+        apply_substitution_partial("key=value", r"(?P<k>\w+)=(\w+)", "${missing}${k}:$2"),
+        "key:value"
+    );
+    assert_eq!(
+        apply_substitution_partial("a", "(?P<x>a)", "${x}$9 $ ${x"),
+        "a $ ${x"
+    );
+}
+
+#[test]
+fn test_whole_match_reference() {
+    assert_eq!(
+        apply_substitution_partial("a cat", "cat", "<<$0>>"),
+        "a <<cat>>"
+    );
+    assert_eq!(
+        apply_substitution_partial("cat", "c(a)t", "${0}/$1"),
+        "cat/a"
+    );
+    assert_eq!(apply_substitution_partial("cat", "c(a)t", "$10"), "a0");
+}
+
+#[test]
+fn test_double_dollar_is_a_literal_dollar() {
+    assert_eq!(apply_substitution_partial("5", r"(\d+)", "$$$1"), "$5");
+    assert_eq!(apply_substitution_partial("5", r"(\d+)", "$$1"), "$1");
+    assert_eq!(
+        apply_substitution_partial("price", "price", "$${total}"),
+        "${total}"
+    );
+    assert_eq!(apply_substitution_partial("a", "a", "$$$$"), "$$");
+}
+
+#[test]
+fn test_double_digit_group_references() {
+    let from = r"(a)(b)(c)(d)(e)(f)(g)(h)(i)(j)(k)";
+    assert_eq!(
+        apply_substitution_partial("abcdefghijk", from, "$11$10$1"),
+        "kja"
+    );
+    assert_eq!(
+        apply_substitution_partial("abcdefghijk", from, "${1}0 $110"),
+        "a0 k0"
+    );
+    assert_eq!(
+        apply_substitution_partial("ab", "(a)", "[$2][${12}]"),
+        "[][]b"
+    );
+}
@@ -0,0 +1,56 @@
+use ised::utils::{apply_substitution_partial, apply_substitution_with, SubstitutionOptions};
+
+#[test]
+fn test_upper_and_lower_regions() {
+    assert_eq!(
+        apply_substitution_partial("let foo_bar = 1;", r"(\w+) =", r"\U$1\E ="),
+        "let FOO_BAR = 1;"
+    );
+    assert_eq!(
+        apply_substitution_partial("HELLO World", r"(\w+) (\w+)", r"\L$1\E $2"),
+        "hello World"
+    );
+    assert_eq!(
+        apply_substitution_partial("key: value", r"(\w+): (\w+)", r"\U$1: $2"),
+        "KEY: VALUE"
+    );
+    assert_eq!(
+        apply_substitution_partial("a-b", r"(\w)-(\w)", r"\U$1\L-x-\E$2"),
+        "A-x-b"
+    );
+}
+
+#[test]
+fn test_single_character_case() {
+    assert_eq!(
+        apply_substitution_partial("hello world", r"(\w+)", r"\u$1"),
+        "Hello World"
+    );
+    assert_eq!(
+        apply_substitution_partial("Hello WORLD", r"(\w+)", r"\l$1"),
+        "hello wORLD"
+    );
+    assert_eq!(
+        apply_substitution_partial("hELLO", r"(\w+)", r"\u\L$1"),
+        "Hello"
+    );
+    assert_eq!(
+        apply_substitution_partial("snake_case_name", r"_(\w)", r"\u$1"),
+        "snakeCaseName"
+    );
+}
+
+#[test]
+fn test_case_escapes_are_literal_when_escaped_or_in_literal_mode() {
+    assert_eq!(apply_substitution_partial("ab", r"(a)", r"\\U$1"), r"\Uab");
+    assert_eq!(apply_substitution_partial("é", r"(é)", r"\u{e9}$1"), "éé");
+
+    let literal = SubstitutionOptions {
+        literal: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        apply_substitution_with("ab", "a", r"\Ux", &literal),
+        r"\Uxb"
+    );
+}
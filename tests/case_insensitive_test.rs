@@ -0,0 +1,70 @@
+mod common;
+
+use common::render_rows;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::App;
+use ised::utils::{apply_substitution_with, effective_pattern, SubstitutionOptions};
+use std::fs;
+use tempdir::TempDir;
+
+fn insensitive() -> SubstitutionOptions {
+    SubstitutionOptions {
+        case_insensitive: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_case_insensitive_prefixes_the_pattern() {
+    assert_eq!(effective_pattern("foo", &insensitive()), "(?i)foo");
+    let anchored = SubstitutionOptions {
+        anchor_start: true,
+        ..insensitive()
+    };
+    assert_eq!(effective_pattern("foo", &anchored), "(?i)(?mR)^(?:foo)");
+    assert_eq!(
+        apply_substitution_with("Foo FOO foo", "foo", "bar", &insensitive()),
+        "bar bar bar"
+    );
+}
+
+#[test]
+fn test_alt_i_toggles_case_insensitive_filter_diff_and_apply() {
+    let tmp_dir = TempDir::new("ised_test_case_insensitive").unwrap();
+    let path = tmp_dir.path().join("a.rs");
+    fs::write(&path, "let UserName = userName;\n").unwrap();
+    let file = path.display().to_string();
+
+    let mut app = App::with_config(None);
+    app.is_loading = false;
+    app.files = vec![file.clone()];
+    app.only_matching = true;
+    app.from_input = "username".to_string();
+    app.to_input = "login".to_string();
+    assert!(app.filter_files().is_empty());
+
+    let files = app.files.clone();
+    app.handle_key_event(KeyEvent::new(KeyCode::Char('i'), KeyModifiers::ALT), &files)
+        .unwrap();
+    assert!(app.options.case_insensitive);
+    assert_eq!(app.clone().options, app.options);
+
+    let filtered = app.filter_files();
+    assert_eq!(filtered, vec![file.clone()]);
+    assert_eq!(
+        app.substitute("let UserName = userName;\n", &file),
+        "let login = login;\n"
+    );
+
+    let rows = render_rows(&mut app, &filtered, None, 80, 20);
+    assert!(rows.iter().any(|row| row.contains("[F]rom (i, 0 groups)")));
+
+    app.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE), &filtered)
+        .unwrap();
+    app.handle_key_event(
+        KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE),
+        &filtered,
+    )
+    .unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "let login = login;\n");
+}
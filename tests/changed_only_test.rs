@@ -0,0 +1,54 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::App;
+use std::fs;
+use tempdir::TempDir;
+
+fn press(app: &mut App, code: KeyCode, modifiers: KeyModifiers, files: &[String]) {
+    app.handle_key_event(KeyEvent::new(code, modifiers), files)
+        .unwrap();
+}
+
+#[test]
+fn test_navigation_skips_files_that_would_not_change() {
+    let tmp_dir = TempDir::new("ised_test_changed_only").unwrap();
+    let fixtures = [
+        ("a.txt", "version = 1"),
+        ("b.txt", "version = 2"),
+        ("c.txt", "version = 2"),
+        ("d.txt", "version = 3"),
+    ];
+    let paths: Vec<String> = fixtures
+        .iter()
+        .map(|(name, content)| {
+            let path = tmp_dir.path().join(name);
+            fs::write(&path, content).unwrap();
+            path.display().to_string()
+        })
+        .collect();
+
+    let mut app = App::new();
+    app.files = paths.clone();
+    app.from_input = r"version = (\d)".to_string();
+    app.to_input = "version = 2".to_string();
+    let files = app.filter_files();
+    assert_eq!(files.len(), 4);
+    assert!(app.changes_file(&paths[0]));
+    assert!(!app.changes_file(&paths[1]));
+
+    app.selected = 1;
+    press(&mut app, KeyCode::Char('j'), KeyModifiers::ALT, &files);
+    assert!(app.changed_only);
+    assert_eq!(app.selected, 0);
+
+    press(&mut app, KeyCode::Char('j'), KeyModifiers::NONE, &files);
+    assert_eq!(app.selected, 3);
+    press(&mut app, KeyCode::Down, KeyModifiers::NONE, &files);
+    assert_eq!(app.selected, 3);
+    press(&mut app, KeyCode::Up, KeyModifiers::NONE, &files);
+    assert_eq!(app.selected, 0);
+
+    press(&mut app, KeyCode::Char('j'), KeyModifiers::ALT, &files);
+    assert!(!app.changed_only);
+    press(&mut app, KeyCode::Char('j'), KeyModifiers::NONE, &files);
+    assert_eq!(app.selected, 1);
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use common::{preview, render_rows};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::{App, Focus};
+
+const WIDTH: u16 = 80;
+const HEIGHT: u16 = 20;
+
+fn render(app: &mut App, files: &[String]) -> Vec<String> {
+    render_rows(app, files, Some(preview("foo\n")), WIDTH, HEIGHT)
+}
+
+fn bottom_border(rows: &[String], column: usize) -> usize {
+    rows.iter()
+        .position(|row| row.chars().nth(column) == Some('└'))
+        .unwrap()
+}
+
+#[test]
+fn test_collapsed_inputs_give_list_and_diff_more_rows() {
+    let files = vec!["a.txt".to_string()];
+    let mut app = App::with_config(None);
+    app.is_loading = false;
+    app.focus = Focus::FileList;
+    app.filter_input = "*.txt".to_string();
+    app.from_input = "foo".to_string();
+    app.to_input = "bar".to_string();
+
+    let normal = render(&mut app, &files);
+    let (normal_list, normal_diff) = (bottom_border(&normal, 0), bottom_border(&normal, 40));
+
+    app.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::ALT), &files)
+        .unwrap();
+    assert!(app.collapse_inputs);
+
+    let collapsed = render(&mut app, &files);
+    assert!(bottom_border(&collapsed, 0) > normal_list);
+    assert!(bottom_border(&collapsed, 40) > normal_diff);
+    assert!(collapsed.iter().all(|row| !row.contains("[F]rom")));
+    assert!(collapsed
+        .iter()
+        .any(|row| row.contains("Glob: *.txt │ Content: (empty)")));
+    assert!(collapsed
+        .iter()
+        .any(|row| row.contains("From: foo │ To: bar")));
+}
+
+#[test]
+fn test_editing_a_collapsed_field_expands_it() {
+    let files = vec!["a.txt".to_string()];
+    let mut app = App::with_config(None);
+    app.is_loading = false;
+    app.collapse_inputs = true;
+    app.focus = Focus::From;
+    app.from_input = "foo".to_string();
+    app.to_input = "bar".to_string();
+
+    let rows = render(&mut app, &files);
+    assert!(rows.iter().any(|row| row.contains("[F]rom")));
+    assert!(rows.iter().all(|row| !row.contains("[T]o")));
+    assert!(rows.iter().any(|row| row.contains("To: bar")));
+    assert!(rows.iter().all(|row| !row.contains("From: foo")));
+}
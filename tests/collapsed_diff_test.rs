@@ -0,0 +1,127 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::App;
+use ised::hunk::{changed_rows, collapse_rows, collapsed_row, DiffRow};
+use ratatui::text::Line;
+use std::fs;
+use tempdir::TempDir;
+
+fn line_to_string(line: &Line) -> String {
+    line.iter().map(|s| s.content.as_ref()).collect()
+}
+
+fn numbered(change_at: usize) -> String {
+    (0..40)
+        .map(|i| {
+            if i == change_at {
+                "foo\n".to_string()
+            } else {
+                format!("line {}\n", i)
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn test_collapse_rows_keeps_context_around_changes() {
+    let mut changed = vec![false; 20];
+    changed[10] = true;
+    assert_eq!(
+        collapse_rows(&changed, 3),
+        vec![
+            DiffRow::Skipped(0..7),
+            DiffRow::Line(7),
+            DiffRow::Line(8),
+            DiffRow::Line(9),
+            DiffRow::Line(10),
+            DiffRow::Line(11),
+            DiffRow::Line(12),
+            DiffRow::Line(13),
+            DiffRow::Skipped(14..20),
+        ]
+    );
+
+    let mut one_gap = vec![false; 9];
+    one_gap[0] = true;
+    one_gap[8] = true;
+    assert!(collapse_rows(&one_gap, 3)
+        .iter()
+        .all(|row| matches!(row, DiffRow::Line(_))));
+
+    let rows = collapse_rows(&changed, 3);
+    assert_eq!(collapsed_row(&rows, 10), 4);
+    assert_eq!(collapsed_row(&rows, 2), 0);
+    assert_eq!(collapsed_row(&rows, 15), 8);
+}
+
+#[test]
+fn test_changed_rows_follow_the_rendered_diff() {
+    assert_eq!(
+        changed_rows("a\nb\nc\n", "a\nB\nc\n", false),
+        vec![false, true, true, false]
+    );
+    assert_eq!(
+        changed_rows("a\nb\nc\n", "a\nB\nc\n", true),
+        vec![false, true, true, true, false]
+    );
+}
+
+#[test]
+fn test_diff_shows_only_changed_regions_by_default() {
+    let mut app = App::new();
+    assert!(!app.full_diff);
+    app.from_input = "foo".to_string();
+    app.to_input = "bar".to_string();
+
+    let lines: Vec<String> = app
+        .diff_lines("a.txt", numbered(20))
+        .iter()
+        .map(line_to_string)
+        .collect();
+    assert_eq!(
+        lines,
+        vec![
+            "⋯ 17 unchanged lines",
+            "line 17",
+            "line 18",
+            "line 19",
+            "- foo",
+            "+ bar",
+            "line 21",
+            "line 22",
+            "line 23",
+            "⋯ 16 unchanged lines",
+        ]
+    );
+
+    app.handle_key_event(
+        KeyEvent::new(KeyCode::Char('v'), KeyModifiers::CONTROL),
+        &[],
+    )
+    .unwrap();
+    assert!(app.full_diff);
+    assert_eq!(app.diff_lines("a.txt", numbered(20)).len(), 41);
+}
+
+#[test]
+fn test_unchanged_files_are_not_collapsed() {
+    let mut app = App::new();
+    app.from_input = "missing".to_string();
+    assert_eq!(app.diff_lines("a.txt", numbered(20)).len(), 40);
+}
+
+#[test]
+fn test_change_navigation_uses_collapsed_rows() {
+    let tmp_dir = TempDir::new("ised_test_collapsed_diff").unwrap();
+    let path = tmp_dir.path().join("a.txt");
+    fs::write(&path, numbered(30)).unwrap();
+    let file = path.display().to_string();
+
+    let mut app = App::new();
+    app.from_input = "foo".to_string();
+    app.to_input = "bar".to_string();
+    assert_eq!(app.file_changes(&file), vec![4]);
+    assert_eq!(app.file_hunks(&file)[0].line, 4);
+
+    app.full_diff = true;
+    assert_eq!(app.file_changes(&file), vec![30]);
+}
@@ -0,0 +1,51 @@
+#![allow(dead_code)]
+
+use ised::app::App;
+use ised::ui;
+use ised::utils::Preview;
+use ratatui::backend::TestBackend;
+use ratatui::buffer::Buffer;
+use ratatui::Terminal;
+
+/// Wraps `content` as a complete text preview.
+pub fn preview(content: &str) -> Preview {
+    Preview {
+        content: content.to_string(),
+        truncated: false,
+        hex: false,
+    }
+}
+
+/// Draws a single frame of `app` on a `width` x `height` test terminal.
+pub fn render(
+    app: &mut App,
+    files: &[String],
+    preview: Option<Preview>,
+    width: u16,
+    height: u16,
+) -> Buffer {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    terminal.draw(|f| ui::draw(f, app, files, preview)).unwrap();
+    terminal.backend().buffer().clone()
+}
+
+/// Draws a single frame and returns it as one string per terminal row.
+pub fn render_rows(
+    app: &mut App,
+    files: &[String],
+    preview: Option<Preview>,
+    width: u16,
+    height: u16,
+) -> Vec<String> {
+    rows(&render(app, files, preview, width, height))
+}
+
+pub fn rows(buffer: &Buffer) -> Vec<String> {
+    (0..buffer.area.height)
+        .map(|y| {
+            (0..buffer.area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect()
+        })
+        .collect()
+}
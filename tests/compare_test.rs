@@ -0,0 +1,61 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::App;
+use ised::utils::highlight_diff_lines;
+use std::fs;
+use tempdir::TempDir;
+
+fn texts(lines: &[ratatui::text::Line]) -> Vec<String> {
+    lines
+        .iter()
+        .map(|line| line.spans.iter().map(|s| s.content.as_ref()).collect())
+        .collect()
+}
+
+#[test]
+fn test_diff_two_arbitrary_contents() {
+    let lines = highlight_diff_lines(
+        "same\nleft only\nsame again\n".to_string(),
+        "same\nright only\nsame again\nextra\n".to_string(),
+    );
+    let texts = texts(&lines);
+    assert!(texts.iter().any(|t| t.contains("- left only")));
+    assert!(texts.iter().any(|t| t.contains("+ right only")));
+    assert!(texts.iter().any(|t| t.contains("+ extra")));
+    assert!(!texts.iter().any(|t| t.contains("- same")));
+}
+
+#[test]
+fn test_ctrl_b_compares_the_selected_file_against_the_marked_one() {
+    let tmp_dir = TempDir::new("ised_test_compare").unwrap();
+    let base = tmp_dir.path().join("base.rs");
+    let other = tmp_dir.path().join("other.rs");
+    fs::write(&base, "fn a() {}\nfn b() {}\n").unwrap();
+    fs::write(&other, "fn a() {}\nfn c() {}\n").unwrap();
+    let files = vec![base.display().to_string(), other.display().to_string()];
+
+    let mut app = App::new();
+    app.is_loading = false;
+    app.files = files.clone();
+    app.from_input = "fn".to_string();
+    app.to_input = "pub fn".to_string();
+
+    let ctrl_b = KeyEvent::new(KeyCode::Char('b'), KeyModifiers::CONTROL);
+    app.handle_key_event(ctrl_b, &files).unwrap();
+    assert_eq!(app.compare_with.as_deref(), Some(files[0].as_str()));
+    assert_eq!(app.compare_target(&files[0]), None);
+
+    app.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE), &files)
+        .unwrap();
+    assert_eq!(app.compare_target(&files[1]), Some(files[0].as_str()));
+
+    let content = fs::read_to_string(&other).unwrap();
+    let compared = texts(app.diff_lines(&files[1], content.clone()));
+    assert!(compared.iter().any(|t| t.contains("- fn c() {}")));
+    assert!(compared.iter().any(|t| t.contains("+ fn b() {}")));
+    assert!(!compared.iter().any(|t| t.contains("pub fn")));
+
+    app.handle_key_event(ctrl_b, &files).unwrap();
+    assert_eq!(app.compare_with, None);
+    let substituted = texts(app.diff_lines(&files[1], content));
+    assert!(substituted.iter().any(|t| t.contains("+ pub fn c() {}")));
+}
@@ -1,7 +1,10 @@
 use ised::app::App;
+use ised::cli::Args;
+use ised::config::{find_config_from, Config, DEFAULT_POLL_MS, MAX_POLL_MS, MIN_POLL_MS};
 use std::fs;
 use std::io::Write;
 use std::path::Path;
+use std::time::Duration;
 use tempdir::TempDir;
 
 fn write_config(dir: &Path, content: &str) {
@@ -36,3 +39,175 @@ fn test_no_config_file_defaults_to_empty() {
 
     assert_eq!(app.filter_input.trim(), "");
 }
+
+#[test]
+fn test_poll_interval_from_config() {
+    let config: Config = toml::from_str(
+        r#"
+        [behavior]
+        poll_ms = 50
+    "#,
+    )
+    .unwrap();
+
+    let app = App::with_config(Some(config));
+
+    assert_eq!(app.poll_interval, Duration::from_millis(50));
+}
+
+#[test]
+fn test_poll_interval_defaults_and_clamps() {
+    let app = App::with_config(None);
+    assert_eq!(app.poll_interval, Duration::from_millis(DEFAULT_POLL_MS));
+
+    let config: Config = toml::from_str("[behavior]\npoll_ms = 1").unwrap();
+    let app = App::with_config(Some(config));
+    assert_eq!(app.poll_interval, Duration::from_millis(MIN_POLL_MS));
+
+    let config: Config = toml::from_str("[behavior]\npoll_ms = 100000").unwrap();
+    let app = App::with_config(Some(config));
+    assert_eq!(app.poll_interval, Duration::from_millis(MAX_POLL_MS));
+}
+
+#[test]
+fn test_parses_presets() {
+    let config: Config = toml::from_str(
+        r#"
+        [[presets]]
+        name = "rename-foo"
+        from = "foo"
+        to = "bar"
+
+        [[presets]]
+        name = "trailing-ws"
+        from = '[ \t]+$'
+        to = ""
+        filter = "*.rs"
+    "#,
+    )
+    .unwrap();
+
+    let presets = config.presets.unwrap();
+    assert_eq!(presets.len(), 2);
+    assert_eq!(presets[0].name, "rename-foo");
+    assert_eq!(presets[0].filter, None);
+    assert_eq!(presets[1].from, "[ \\t]+$");
+    assert_eq!(presets[1].filter.as_deref(), Some("*.rs"));
+}
+
+#[test]
+fn test_diff_theme_from_config() {
+    let config: Config = toml::from_str(
+        r#"
+        [theme.diff]
+        removed_marker = "-"
+        added_color = "blue"
+        removed_color = "not-a-color"
+        hunk_headers = true
+    "#,
+    )
+    .unwrap();
+
+    let app = App::with_config(Some(config));
+
+    assert_eq!(app.diff_theme.removed_marker, "-");
+    assert_eq!(app.diff_theme.added_marker, "+ ");
+    assert_eq!(app.diff_theme.added_color, ratatui::style::Color::Blue);
+    assert_eq!(app.diff_theme.removed_color, ratatui::style::Color::Red);
+    assert!(app.diff_theme.hunk_headers);
+}
+
+#[test]
+fn test_mouse_capture_from_config() {
+    assert!(App::with_config(None).mouse_capture);
+
+    let config: Config = toml::from_str("[behavior]\nmouse_capture = false").unwrap();
+    let mut app = App::with_config(Some(config));
+    assert!(!app.mouse_capture);
+
+    app.handle_key_event(
+        crossterm::event::KeyEvent::new(
+            crossterm::event::KeyCode::Char('u'),
+            crossterm::event::KeyModifiers::ALT,
+        ),
+        &[],
+    )
+    .unwrap();
+    assert!(app.mouse_capture);
+}
+
+#[test]
+fn test_thread_pool_size_from_config() {
+    let config: Config = toml::from_str(
+        r#"
+        [performance]
+        threads = 2
+    "#,
+    )
+    .unwrap();
+
+    let app = App::with_config(Some(config));
+
+    assert_eq!(app.thread_count(), 2);
+}
+
+#[test]
+fn test_thread_pool_defaults_to_cpu_count() {
+    let cpus = std::thread::available_parallelism().unwrap().get();
+    let config: Config = toml::from_str(
+        r#"
+        [performance]
+        threads = 0
+    "#,
+    )
+    .unwrap();
+
+    assert_eq!(App::with_config(None).thread_count(), cpus);
+    assert_eq!(App::with_config(Some(config)).thread_count(), cpus);
+}
+
+#[test]
+fn test_reports_nearest_ancestor_config_path() {
+    let tmp_dir = TempDir::new("ised_test_config_path").unwrap();
+    let outer = tmp_dir.path().join("outer");
+    let inner = outer.join("inner");
+    let leaf = inner.join("src").join("deep");
+    fs::create_dir_all(&leaf).unwrap();
+    write_config(&outer, "[display]\ntab_width = 8");
+    fs::write(inner.join(".ised.config.toml"), "[display]\ntab_width = 2").unwrap();
+
+    let (path, config) = find_config_from(&leaf).unwrap();
+    assert_eq!(path, inner.join(".ised.config.toml"));
+    assert_eq!(config.display.unwrap().tab_width, Some(2));
+
+    let (path, _) = find_config_from(&outer).unwrap();
+    assert_eq!(path, outer.join("ised.config.toml"));
+}
+
+#[test]
+fn test_effective_config_includes_defaults() {
+    let config: Config = toml::from_str(
+        r#"
+        [display]
+        tab_width = 8
+    "#,
+    )
+    .unwrap();
+
+    let effective = App::with_config(Some(config)).effective_config();
+
+    assert_eq!(effective.display.unwrap().tab_width, Some(8));
+    assert_eq!(effective.behavior.unwrap().poll_ms, Some(DEFAULT_POLL_MS));
+    let theme = effective.theme.unwrap().diff.unwrap();
+    assert_eq!(theme.removed_color.as_deref(), Some("Red"));
+}
+
+#[test]
+fn test_print_config_flag() {
+    assert!(
+        Args::parse(["--print-config".to_string()])
+            .unwrap()
+            .print_config
+    );
+    assert!(!Args::parse([]).unwrap().print_config);
+}
@@ -0,0 +1,55 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::App;
+use std::fs;
+use tempdir::TempDir;
+
+#[test]
+fn test_conflict_mode_lists_only_files_with_markers() {
+    let tmp_dir = TempDir::new("ised_test_conflicts").unwrap();
+    let fixtures = [
+        (
+            "conflicted.rs",
+            "fn a() {}\n<<<<<<< HEAD\nlet x = 1;\n=======\nlet x = 2;\n>>>>>>> feature\n",
+        ),
+        (
+            "crlf.rs",
+            "<<<<<<< HEAD\r\na\r\n=======\r\nb\r\n>>>>>>> main\r\n",
+        ),
+        ("clean.rs", "fn a() {}\n"),
+        (
+            "lookalike.md",
+            "Title\n=======\n\n// <<<<<<<< not a marker\n",
+        ),
+    ];
+    let paths: Vec<String> = fixtures
+        .iter()
+        .map(|(name, content)| {
+            let path = tmp_dir.path().join(name);
+            fs::write(&path, content).unwrap();
+            path.display().to_string()
+        })
+        .collect();
+
+    let mut app = App::new();
+    app.files = paths.clone();
+    assert_eq!(app.filter_files().len(), 4);
+
+    app.handle_key_event(
+        KeyEvent::new(KeyCode::Char('k'), KeyModifiers::CONTROL),
+        &[],
+    )
+    .unwrap();
+    assert!(app.conflicts_only);
+    assert_eq!(app.filter_files(), vec![paths[0].clone(), paths[1].clone()]);
+
+    app.content_filter_input = "feature".to_string();
+    assert_eq!(app.filter_files(), vec![paths[0].clone()]);
+
+    app.content_filter_input.clear();
+    app.handle_key_event(
+        KeyEvent::new(KeyCode::Char('k'), KeyModifiers::CONTROL),
+        &[],
+    )
+    .unwrap();
+    assert_eq!(app.filter_files().len(), 4);
+}
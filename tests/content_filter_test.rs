@@ -0,0 +1,135 @@
+use ised::app::App;
+use std::fs;
+use tempdir::TempDir;
+
+fn fixture_app(dir: &TempDir) -> (App, Vec<String>) {
+    let fixtures = [
+        ("both.rs", "fn main() { unsafe { run() } }"),
+        ("main_only.rs", "fn main() { run() }"),
+        ("unsafe_only.rs", "unsafe fn run() {}"),
+    ];
+
+    let paths: Vec<String> = fixtures
+        .iter()
+        .map(|(name, content)| {
+            let path = dir.path().join(name);
+            fs::write(&path, content).unwrap();
+            path.display().to_string()
+        })
+        .collect();
+
+    let mut app = App::new();
+    app.files = paths.clone();
+    (app, paths)
+}
+
+#[test]
+fn test_content_filter_is_anded_with_from() {
+    let tmp_dir = TempDir::new("ised_test_content_and").unwrap();
+    let (mut app, paths) = fixture_app(&tmp_dir);
+    app.from_input = "fn main".to_string();
+    app.content_filter_input = "unsafe".to_string();
+
+    assert_eq!(app.filter_files(), vec![paths[0].clone()]);
+}
+
+#[test]
+fn test_content_filter_alone_narrows_files() {
+    let tmp_dir = TempDir::new("ised_test_content_alone").unwrap();
+    let (mut app, paths) = fixture_app(&tmp_dir);
+    app.content_filter_input = "unsafe".to_string();
+
+    assert_eq!(app.filter_files(), vec![paths[0].clone(), paths[2].clone()]);
+}
+
+#[test]
+fn test_content_filter_does_not_affect_substitution() {
+    let tmp_dir = TempDir::new("ised_test_content_subst").unwrap();
+    let (mut app, paths) = fixture_app(&tmp_dir);
+    app.from_input = "run".to_string();
+    app.to_input = "go".to_string();
+    app.content_filter_input = "unsafe".to_string();
+
+    let filtered = app.filter_files();
+    assert_eq!(filtered, vec![paths[0].clone(), paths[2].clone()]);
+
+    app.apply_all(&filtered);
+
+    assert_eq!(
+        fs::read_to_string(&paths[0]).unwrap(),
+        "fn main() { unsafe { go() } }"
+    );
+    assert_eq!(
+        fs::read_to_string(&paths[1]).unwrap(),
+        "fn main() { run() }"
+    );
+}
+
+#[test]
+fn test_only_matching_mode_keeps_from_matches() {
+    let tmp_dir = TempDir::new("ised_test_only_matching").unwrap();
+    let (mut app, paths) = fixture_app(&tmp_dir);
+    app.filter_input = "*.rs".to_string();
+    app.from_input = "fn main".to_string();
+
+    assert!(app.only_matching);
+    assert_eq!(app.filter_files(), vec![paths[0].clone(), paths[1].clone()]);
+}
+
+#[test]
+fn test_all_files_mode_ignores_from_for_listing() {
+    let tmp_dir = TempDir::new("ised_test_all_files").unwrap();
+    let (mut app, paths) = fixture_app(&tmp_dir);
+    app.filter_input = "*.rs".to_string();
+    app.from_input = "fn main".to_string();
+    app.only_matching = false;
+
+    assert_eq!(app.filter_files(), paths);
+    assert_eq!(
+        app.affected_files(&paths),
+        vec![paths[0].clone(), paths[1].clone()]
+    );
+}
+
+#[test]
+fn test_content_filter_alternatives_are_ored() {
+    let tmp_dir = TempDir::new("ised_test_content_or").unwrap();
+    let (mut app, paths) = fixture_app(&tmp_dir);
+    app.content_filter_input = "unsafe fn || main\\(\\) \\{ run".to_string();
+
+    assert_eq!(
+        app.content_filter_patterns(),
+        vec!["unsafe fn", "main\\(\\) \\{ run"]
+    );
+    assert_eq!(app.filter_files(), vec![paths[1].clone(), paths[2].clone()]);
+}
+
+#[test]
+fn test_content_filter_alternatives_only_select_files() {
+    let tmp_dir = TempDir::new("ised_test_content_or_subst").unwrap();
+    let (mut app, paths) = fixture_app(&tmp_dir);
+    app.from_input = "run".to_string();
+    app.to_input = "go".to_string();
+    app.content_filter_input = "unsafe fn||fn main\\(\\) \\{ run".to_string();
+
+    let filtered = app.filter_files();
+    assert_eq!(filtered, vec![paths[1].clone(), paths[2].clone()]);
+
+    app.apply_all(&filtered);
+
+    assert_eq!(
+        fs::read_to_string(&paths[0]).unwrap(),
+        "fn main() { unsafe { run() } }"
+    );
+    assert_eq!(fs::read_to_string(&paths[1]).unwrap(), "fn main() { go() }");
+    assert_eq!(fs::read_to_string(&paths[2]).unwrap(), "unsafe fn go() {}");
+}
+
+#[test]
+fn test_invalid_content_filter_alternative_is_ignored() {
+    let tmp_dir = TempDir::new("ised_test_content_or_invalid").unwrap();
+    let (mut app, paths) = fixture_app(&tmp_dir);
+    app.content_filter_input = "unsafe fn || (".to_string();
+
+    assert_eq!(app.filter_files(), vec![paths[2].clone()]);
+}
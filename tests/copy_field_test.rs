@@ -0,0 +1,50 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::{App, Focus};
+
+fn copy(app: &mut App) {
+    app.handle_key_event(KeyEvent::new(KeyCode::Char('='), KeyModifiers::ALT), &[])
+        .unwrap();
+}
+
+#[test]
+fn test_alt_equals_copies_from_into_to() {
+    let mut app = App::new();
+    app.focus = Focus::From;
+    app.from_input = r"get_(\w+)\(\)".to_string();
+    app.from_cursor = 4;
+    app.to_input = "old".to_string();
+
+    copy(&mut app);
+    assert_eq!(app.to_input, r"get_(\w+)\(\)");
+    assert_eq!(app.to_cursor, 4);
+    assert_eq!(app.to_view_offset, 0);
+    assert!(app.focus == Focus::To);
+    assert_eq!(app.status_message.as_deref(), Some("Copied From into To"));
+}
+
+#[test]
+fn test_alt_equals_copies_to_into_from_and_warns_on_invalid_regex() {
+    let mut app = App::new();
+    app.focus = Focus::To;
+    app.to_input = "call(".to_string();
+    app.to_cursor = 5;
+
+    copy(&mut app);
+    assert_eq!(app.from_input, "call(");
+    assert_eq!(app.from_cursor, 5);
+    assert!(app.focus == Focus::From);
+    assert!(app
+        .status_message
+        .as_deref()
+        .unwrap()
+        .contains("isn't a valid regex"));
+
+    app.focus = Focus::FileList;
+    copy(&mut app);
+    assert_eq!(app.from_input, "call(");
+    assert!(app
+        .status_message
+        .as_deref()
+        .unwrap()
+        .contains("Focus From or To"));
+}
@@ -0,0 +1,63 @@
+use ised::app::App;
+
+#[test]
+fn test_diff_cache_hit_when_inputs_unchanged() {
+    let mut app = App::new();
+    app.from_input = "foo".to_string();
+    app.to_input = "bar".to_string();
+
+    let first = app.diff_lines("a.txt", "foo\n".to_string()).as_ptr();
+    let second = app.diff_lines("a.txt", "foo\n".to_string()).as_ptr();
+
+    assert_eq!(first, second);
+}
+
+#[test]
+fn test_diff_cache_recomputes_when_inputs_change() {
+    let mut app = App::new();
+    app.from_input = "foo".to_string();
+    app.to_input = "bar".to_string();
+
+    assert_eq!(app.diff_lines("a.txt", "foo\n".to_string()).len(), 2);
+
+    app.from_input = "missing".to_string();
+    assert_eq!(app.diff_lines("a.txt", "foo\n".to_string()).len(), 1);
+
+    assert_eq!(app.diff_lines("a.txt", "foo\nbaz\n".to_string()).len(), 2);
+}
+
+// Run with `cargo test --release --test diff_cache_test -- --ignored --nocapture`.
+#[test]
+#[ignore]
+fn measure_idle_frames_on_a_large_diff() {
+    use std::time::Instant;
+
+    let content = "fn foo() { let foo = 1; }\n".repeat(20_000);
+    let frames = 50;
+    let mut app = App::new();
+    app.from_input = "foo".to_string();
+    app.to_input = "bar".to_string();
+
+    let start = Instant::now();
+    for frame in 0..frames {
+        app.to_input = if frame % 2 == 0 { "bar" } else { "baz" }.to_string();
+        app.diff_lines("a.rs", content.clone());
+    }
+    let uncached = start.elapsed();
+
+    let start = Instant::now();
+    for _ in 0..frames {
+        app.diff_lines("a.rs", content.clone());
+    }
+    let cached = start.elapsed();
+
+    println!(
+        "{} frames on {} bytes: {:?} recomputing, {:?} cached ({:.0}x)",
+        frames,
+        content.len(),
+        uncached,
+        cached,
+        uncached.as_secs_f64() / cached.as_secs_f64()
+    );
+    assert!(cached < uncached);
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use common::render_rows;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::App;
+use ised::utils::{apply_substitution_with, effective_pattern, SubstitutionOptions};
+use std::fs;
+use tempdir::TempDir;
+
+fn dotall() -> SubstitutionOptions {
+    SubstitutionOptions {
+        dotall: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_dotall_lets_dot_span_newlines() {
+    assert_eq!(
+        apply_substitution_with("a\nb", "a.*b", "X", &SubstitutionOptions::default()),
+        "a\nb"
+    );
+    assert_eq!(apply_substitution_with("a\nb", "a.*b", "X", &dotall()), "X");
+    assert_eq!(effective_pattern("a.*b", &dotall()), "(?s)a.*b");
+    let all = SubstitutionOptions {
+        case_insensitive: true,
+        multiline: true,
+        ..dotall()
+    };
+    assert_eq!(effective_pattern("^a.*b$", &all), "(?ims)^a.*b$");
+}
+
+#[test]
+fn test_alt_s_toggles_dotall_in_preview_filter_and_title() {
+    let tmp_dir = TempDir::new("ised_test_dotall").unwrap();
+    let path = tmp_dir.path().join("a.txt");
+    fs::write(&path, "a\nb").unwrap();
+    let file = path.display().to_string();
+
+    let mut app = App::with_config(None);
+    app.is_loading = false;
+    app.files = vec![file.clone()];
+    app.only_matching = true;
+    app.from_input = "a.*b".to_string();
+    app.to_input = "X".to_string();
+    assert_eq!(app.substitute("a\nb", &file), "a\nb");
+    assert!(app.filter_files().is_empty());
+
+    let files = app.files.clone();
+    app.handle_key_event(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::ALT), &files)
+        .unwrap();
+    assert!(app.options.dotall);
+    assert!(app.clone().options.dotall);
+    assert_eq!(app.substitute("a\nb", &file), "X");
+    assert_eq!(app.filter_files(), vec![file]);
+
+    let rows = render_rows(&mut app, &files, None, 80, 20);
+    assert!(rows.iter().any(|row| row.contains("[F]rom (s, 0 groups)")));
+}
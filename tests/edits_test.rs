@@ -0,0 +1,105 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::App;
+use ised::cli::Args;
+use ised::edits::{substitution_edits, Edit};
+use ised::utils::SubstitutionOptions;
+use std::fs;
+use std::path::PathBuf;
+use tempdir::TempDir;
+
+#[test]
+fn test_edits_carry_byte_offsets_and_expanded_replacements() {
+    let content = "let café = get_a();\nlet b = get_b();\n";
+    let edits = substitution_edits(
+        content,
+        r"get_(\w)\(\)",
+        "fetch($1)",
+        &SubstitutionOptions::default(),
+    );
+    assert_eq!(
+        edits,
+        vec![
+            Edit {
+                start_byte: 12,
+                end_byte: 19,
+                replacement: "fetch(a)".to_string(),
+            },
+            Edit {
+                start_byte: 29,
+                end_byte: 36,
+                replacement: "fetch(b)".to_string(),
+            },
+        ]
+    );
+    assert_eq!(&content[12..19], "get_a()");
+}
+
+#[test]
+fn test_edits_skip_matches_that_stay_the_same() {
+    let options = SubstitutionOptions {
+        literal: true,
+        ..SubstitutionOptions::default()
+    };
+    let edits = substitution_edits("a.b a.b", "a.b", "$1", &options);
+    assert_eq!(edits.len(), 2);
+    assert_eq!(edits[1].start_byte, 4);
+    assert_eq!(edits[1].replacement, "$1");
+
+    assert!(substitution_edits("keep", "keep", "keep", &options).is_empty());
+}
+
+#[test]
+fn test_apply_writes_edits_json_instead_of_files() {
+    let tmp_dir = TempDir::new("ised_test_edits").unwrap();
+    let file = tmp_dir.path().join("a.txt");
+    fs::write(&file, "foo bar foo\n").unwrap();
+    let path = file.display().to_string();
+    let edits_path = tmp_dir.path().join("edits.json");
+
+    let mut app = App::new();
+    app.files = vec![path.clone()];
+    app.edits_path = Some(edits_path.clone());
+    app.from_input = "foo".to_string();
+    app.to_input = "baz".to_string();
+
+    let filtered = app.filter_files();
+    app.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE), &filtered)
+        .unwrap();
+    app.handle_key_event(
+        KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE),
+        &filtered,
+    )
+    .unwrap();
+
+    assert_eq!(fs::read_to_string(&file).unwrap(), "foo bar foo\n");
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&edits_path).unwrap()).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "files": [{
+                "path": path,
+                "edits": [
+                    {"start_byte": 0, "end_byte": 3, "replacement": "baz"},
+                    {"start_byte": 8, "end_byte": 11, "replacement": "baz"},
+                ],
+            }],
+        })
+    );
+    assert!(app.status_message.unwrap().contains("2 edits in 1 files"));
+}
+
+#[test]
+fn test_edits_refuse_line_aware_options() {
+    let mut app = App::new();
+    app.from_input = "foo".to_string();
+    app.options.keep_indent = true;
+    assert!(app.collect_edits(&[]).is_err());
+}
+
+#[test]
+fn test_edits_flag() {
+    let args = Args::parse(["--edits".to_string(), "out.json".to_string()]).unwrap();
+    assert_eq!(args.edits, Some(PathBuf::from("out.json")));
+    assert!(Args::parse(["--edits".to_string()]).is_err());
+}
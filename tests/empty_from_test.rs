@@ -0,0 +1,56 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::{App, ConfirmState};
+use std::fs;
+use tempdir::TempDir;
+
+fn press(app: &mut App, code: KeyCode, modifiers: KeyModifiers, files: &[String]) {
+    app.handle_key_event(KeyEvent::new(code, modifiers), files)
+        .unwrap();
+}
+
+fn fixture(dir: &TempDir) -> String {
+    let path = dir.path().join("a.txt");
+    fs::write(&path, "abc").unwrap();
+    path.display().to_string()
+}
+
+#[test]
+fn test_empty_from_blocks_confirmation() {
+    let tmp_dir = TempDir::new("ised_test_empty_from").unwrap();
+    let files = vec![fixture(&tmp_dir)];
+
+    let mut app = App::new();
+    app.to_input = "X".to_string();
+
+    press(&mut app, KeyCode::Enter, KeyModifiers::NONE, &files);
+    assert!(matches!(app.confirm, ConfirmState::None));
+    assert!(app.status_message.is_some());
+
+    press(&mut app, KeyCode::Char('a'), KeyModifiers::CONTROL, &files);
+    assert!(matches!(app.confirm, ConfirmState::None));
+
+    let report = app.apply_all(&files);
+    assert_eq!(report.changed, 0);
+    assert_eq!(fs::read_to_string(&files[0]).unwrap(), "abc");
+}
+
+#[test]
+fn test_empty_matching_from_requires_explicit_confirmation() {
+    let tmp_dir = TempDir::new("ised_test_empty_match").unwrap();
+    let files = vec![fixture(&tmp_dir)];
+
+    let mut app = App::new();
+    app.from_input = "x*".to_string();
+    app.to_input = "-".to_string();
+
+    press(&mut app, KeyCode::Enter, KeyModifiers::NONE, &files);
+    assert!(matches!(app.confirm, ConfirmState::Confirming(_)));
+
+    press(&mut app, KeyCode::Char('y'), KeyModifiers::NONE, &files);
+    assert!(matches!(app.confirm, ConfirmState::Confirming(_)));
+    assert_eq!(fs::read_to_string(&files[0]).unwrap(), "abc");
+
+    press(&mut app, KeyCode::Char('Y'), KeyModifiers::SHIFT, &files);
+    assert!(matches!(app.confirm, ConfirmState::None));
+    assert_eq!(fs::read_to_string(&files[0]).unwrap(), "-a-b-c-");
+}
@@ -0,0 +1,99 @@
+use ised::app::App;
+use ised::config::Config;
+use ised::encoding::{detect_file_info, Encoding, FileInfo, LineEnding};
+use notify::event::{DataChange, ModifyKind};
+use notify::{Event, EventKind};
+use std::fs;
+use tempdir::TempDir;
+
+fn utf16(text: &str, little_endian: bool) -> Vec<u8> {
+    let mut bytes = if little_endian {
+        vec![0xFF, 0xFE]
+    } else {
+        vec![0xFE, 0xFF]
+    };
+    for unit in text.encode_utf16() {
+        bytes.extend(if little_endian {
+            unit.to_le_bytes()
+        } else {
+            unit.to_be_bytes()
+        });
+    }
+    bytes
+}
+
+#[test]
+fn test_detects_line_endings() {
+    let ending = |bytes: &[u8]| detect_file_info(bytes).line_ending;
+    assert_eq!(ending(b"a\nb\n"), LineEnding::Lf);
+    assert_eq!(ending(b"a\r\nb\r\n"), LineEnding::Crlf);
+    assert_eq!(ending(b"a\r\nb\n"), LineEnding::Mixed);
+    assert_eq!(ending(b"single line"), LineEnding::None);
+}
+
+#[test]
+fn test_detects_encodings() {
+    assert_eq!(
+        detect_file_info("é\n".as_bytes()),
+        FileInfo {
+            encoding: Encoding::Utf8,
+            line_ending: LineEnding::Lf,
+        }
+    );
+    assert_eq!(
+        detect_file_info(b"\xEF\xBB\xBFa\r\n"),
+        FileInfo {
+            encoding: Encoding::Utf8Bom,
+            line_ending: LineEnding::Crlf,
+        }
+    );
+    assert_eq!(
+        detect_file_info(&utf16("a\r\nb\r\n", true)),
+        FileInfo {
+            encoding: Encoding::Utf16Le,
+            line_ending: LineEnding::Crlf,
+        }
+    );
+    assert_eq!(
+        detect_file_info(&utf16("a\nb\r\n", false)).line_ending,
+        LineEnding::Mixed
+    );
+    assert_eq!(detect_file_info(b"\xff\x00ab").encoding, Encoding::Unknown);
+    assert_eq!(detect_file_info(b"ab\xC3").encoding, Encoding::Utf8);
+}
+
+#[test]
+fn test_file_info_label() {
+    let tmp_dir = TempDir::new("ised_test_file_info").unwrap();
+    let path = tmp_dir.path().join("a.txt");
+    fs::write(&path, b"\xEF\xBB\xBFa\r\nb\n").unwrap();
+
+    let config: Config = toml::from_str("[display]\nfile_info = true").unwrap();
+    let app = App::with_config(Some(config));
+    assert!(app.show_file_info);
+    assert_eq!(
+        app.file_info(&path.display().to_string()).unwrap().label(),
+        "UTF-8 with BOM, mixed line endings"
+    );
+}
+
+#[test]
+fn test_file_info_is_cached_until_the_file_changes() {
+    let tmp_dir = TempDir::new("ised_test_file_info_cache").unwrap();
+    let path = tmp_dir.path().join("a.txt");
+    fs::write(&path, "a\r\nb\r\n").unwrap();
+    let path_str = path.display().to_string();
+
+    let app = App::with_config(None);
+    let label = |app: &App| app.file_info(&path_str).unwrap().label();
+    assert_eq!(label(&app), "UTF-8, CRLF");
+
+    fs::write(&path, "a\nb\n").unwrap();
+    assert_eq!(label(&app), "UTF-8, CRLF");
+
+    app.handle_watch_event(
+        &Event::new(EventKind::Modify(ModifyKind::Data(DataChange::Content)))
+            .add_path(path.clone()),
+    );
+    assert_eq!(label(&app), "UTF-8, LF");
+}
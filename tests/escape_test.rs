@@ -0,0 +1,98 @@
+use ised::app::App;
+use ised::utils::{
+    apply_substitution_partial, apply_substitution_with, unescape_replacement, SubstitutionOptions,
+};
+
+fn unescaped(text: &str) -> String {
+    unescape_replacement(text).0
+}
+
+fn invalid(text: &str) -> Vec<String> {
+    unescape_replacement(text).1
+}
+
+#[test]
+fn test_valid_escapes() {
+    assert_eq!(unescaped(r"a\nb\tc\r\0"), "a\nb\tc\r\0");
+    assert_eq!(unescaped(r"\\n"), r"\n");
+    assert_eq!(unescaped(r"\x41\x7e"), "A~");
+    assert_eq!(unescaped(r"\u{e9}\u{1F600}"), "é😀");
+    assert!(invalid(r"\n\x41\u{e9}").is_empty());
+}
+
+#[test]
+fn test_unknown_escapes_are_kept() {
+    assert_eq!(unescaped(r"C:\Users\file \d"), r"C:\Users\file \d");
+    assert!(invalid(r"\q\file").is_empty());
+}
+
+#[test]
+fn test_hex_escape_without_digits() {
+    assert_eq!(unescaped(r"a\x"), r"a\x");
+    assert_eq!(unescaped(r"\xg1"), r"\xg1");
+    assert_eq!(unescaped(r"\x4"), r"\x4");
+    assert_eq!(invalid(r"a\x b\x4"), vec![r"\x", r"\x4"]);
+}
+
+#[test]
+fn test_hex_escape_outside_ascii() {
+    assert_eq!(unescaped(r"\xff"), r"\xff");
+    assert_eq!(invalid(r"\x80"), vec![r"\x80"]);
+}
+
+#[test]
+fn test_malformed_unicode_escapes() {
+    for text in [
+        r"\u{}",
+        r"\u{d800}",
+        r"\u{110000}",
+        r"\u{1234567}",
+        r"\u{zz}",
+    ] {
+        assert_eq!(unescaped(text), text);
+        assert_eq!(invalid(text), vec![text]);
+    }
+    assert!(invalid(r"\u41").is_empty());
+    assert_eq!(unescaped(r"\u{41"), r"\u{41");
+}
+
+#[test]
+fn test_trailing_backslash() {
+    assert_eq!(unescaped("end\\"), "end\\");
+    assert_eq!(invalid("end\\"), vec!["\\"]);
+}
+
+#[test]
+fn test_escapes_in_substitution() {
+    assert_eq!(
+        apply_substitution_partial("a,b", "(\\w),(\\w)", r"$1\n$2"),
+        "a\nb"
+    );
+    assert_eq!(apply_substitution_partial("a,b", ",", r"\x"), r"a\xb");
+
+    let literal = SubstitutionOptions {
+        literal: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        apply_substitution_with("a,b", ",", r"\n", &literal),
+        r"a\nb"
+    );
+}
+
+#[test]
+fn test_escape_warning() {
+    let mut app = App::with_config(None);
+    app.to_input = r"\u{d800} and \x".to_string();
+    assert_eq!(
+        app.to_escape_warning().as_deref(),
+        Some(r"Invalid escape \u{d800}, \x in To is inserted as written")
+    );
+
+    app.options.literal = true;
+    assert_eq!(app.to_escape_warning(), None);
+
+    app.options.literal = false;
+    app.to_input = r"\t$1".to_string();
+    assert_eq!(app.to_escape_warning(), None);
+}
@@ -0,0 +1,154 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::App;
+use ised::config::read_config;
+use std::fs;
+use tempdir::TempDir;
+
+fn session_app() -> App {
+    let mut app = App::with_config(None);
+    app.filter_input = "!**/target/**,*.rs".to_string();
+    app.content_filter_input = "fn ".to_string();
+    app.from_input = "foo(\\d)".to_string();
+    app.to_input = "bar$1".to_string();
+    app.options.keep_indent = true;
+    app.group_by_dir = true;
+    app
+}
+
+#[test]
+fn test_exported_config_round_trips() {
+    let tmp_dir = TempDir::new("ised_test_export").unwrap();
+    let path = tmp_dir.path().join("ised.config.toml");
+    let app = session_app();
+
+    let message = app.export_config(path.to_str().unwrap()).unwrap();
+    assert!(message.starts_with("Exported session to"));
+
+    let mut reloaded = App::with_config(Some(read_config(&path).unwrap()));
+    assert_eq!(reloaded.filter_input, app.filter_input);
+    assert!(reloaded.group_by_dir);
+    assert!(!reloaded.options.keep_indent);
+
+    reloaded.load_preset(0);
+    assert_eq!(reloaded.presets[0].name, "session");
+    assert_eq!(reloaded.from_input, app.from_input);
+    assert_eq!(reloaded.to_input, app.to_input);
+    assert_eq!(reloaded.content_filter_input, app.content_filter_input);
+    assert!(reloaded.options.keep_indent);
+}
+
+#[test]
+fn test_named_preset_keeps_the_rest_of_the_config() {
+    let tmp_dir = TempDir::new("ised_test_export_preset").unwrap();
+    let path = tmp_dir.path().join("ised.config.toml");
+    fs::write(
+        &path,
+        r#"
+        [behavior]
+        poll_ms = 50
+
+        [[presets]]
+        name = "rename"
+        from = "old"
+        to = "new"
+        "#,
+    )
+    .unwrap();
+
+    let app = session_app();
+    app.export_config(&format!("{}#rename", path.display()))
+        .unwrap();
+    app.export_config(&format!("{}#other", path.display()))
+        .unwrap();
+
+    let config = read_config(&path).unwrap();
+    assert_eq!(config.behavior.unwrap().poll_ms, Some(50));
+    assert!(config.files.is_none());
+
+    let presets = config.presets.unwrap();
+    assert_eq!(
+        presets.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(),
+        vec!["rename", "other"]
+    );
+    assert_eq!(presets[0].from, "foo(\\d)");
+    assert_eq!(presets[0].filter.as_deref(), Some("!**/target/**,*.rs"));
+}
+
+#[test]
+fn test_export_prompt_writes_on_enter() {
+    let tmp_dir = TempDir::new("ised_test_export_prompt").unwrap();
+    let path = tmp_dir.path().join("out.toml");
+    let mut app = session_app();
+
+    app.handle_key_event(
+        KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL),
+        &[],
+    )
+    .unwrap();
+    assert_eq!(app.export_prompt.as_deref(), Some("ised.config.toml"));
+
+    app.export_prompt = Some(String::new());
+    for c in path.to_str().unwrap().chars() {
+        app.handle_key_event(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE), &[])
+            .unwrap();
+    }
+    app.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE), &[])
+        .unwrap();
+
+    assert!(app.export_prompt.is_none());
+    assert!(path.exists());
+    assert_eq!(app.from_input, "foo(\\d)");
+}
+
+#[test]
+fn test_export_refuses_to_overwrite_invalid_config() {
+    let tmp_dir = TempDir::new("ised_test_export_invalid").unwrap();
+    let path = tmp_dir.path().join("ised.config.toml");
+    fs::write(&path, "not = [valid").unwrap();
+
+    assert!(session_app().export_config(path.to_str().unwrap()).is_err());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "not = [valid");
+}
+
+#[test]
+fn test_export_keeps_comments_and_layout() {
+    let tmp_dir = TempDir::new("ised_test_export_comments").unwrap();
+    let path = tmp_dir.path().join("ised.config.toml");
+    let original = r#"# Shared settings for this repo
+[behavior]
+poll_ms = 50 # fast disk
+
+[display]
+# Keep the tree view
+group_by_dir = false
+tab_width = 8
+
+[[presets]]
+# Keep this one around
+name = "rename"
+from = "old"
+to = "new"
+"#;
+    fs::write(&path, original).unwrap();
+
+    let app = session_app();
+    app.export_config(path.to_str().unwrap()).unwrap();
+    app.export_config(&format!("{}#rename", path.display()))
+        .unwrap();
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert!(content
+        .starts_with("# Shared settings for this repo\n[behavior]\npoll_ms = 50 # fast disk\n"));
+    assert!(content.contains("# Keep the tree view\ngroup_by_dir = true\ntab_width = 8\n"));
+    assert!(content.contains("# Keep this one around\nname = \"rename\"\nfrom = 'foo(\\d)'"));
+    assert!(content.contains("[files]\nglob_filter = [\"!**/target/**\", \"*.rs\"]"));
+    assert!(content.find("[display]").unwrap() < content.find("[[presets]]").unwrap());
+
+    let config = read_config(&path).unwrap();
+    assert_eq!(config.display.unwrap().tab_width, Some(8));
+    let presets = config.presets.unwrap();
+    assert_eq!(
+        presets.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(),
+        vec!["rename", "session"]
+    );
+}
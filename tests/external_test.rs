@@ -0,0 +1,68 @@
+use ised::external::open_directory_command;
+use std::ffi::OsStr;
+use std::path::Path;
+
+fn expected_program() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(target_os = "windows") {
+        "explorer"
+    } else {
+        "xdg-open"
+    }
+}
+
+#[test]
+fn test_open_directory_command_per_platform() {
+    let command = open_directory_command(Path::new("./src"));
+
+    assert_eq!(command.get_program(), OsStr::new(expected_program()));
+    assert_eq!(
+        command.get_args().collect::<Vec<_>>(),
+        vec![OsStr::new("./src")]
+    );
+}
+
+#[test]
+fn test_clipboard_command_per_platform() {
+    let command = ised::external::clipboard_command();
+    let program = command.get_program().to_string_lossy().to_string();
+
+    if cfg!(target_os = "macos") {
+        assert_eq!(program, "pbcopy");
+    } else if cfg!(target_os = "windows") {
+        assert_eq!(program, "clip");
+    } else {
+        assert!(program == "wl-copy" || program == "xclip");
+    }
+}
+
+#[test]
+fn test_pager_command_runs_pager_through_shell() {
+    let command = ised::external::pager_command(Some("less -R"));
+    assert_eq!(command.get_args().last(), Some(OsStr::new("less -R")));
+
+    for pager in [None, Some(""), Some("  ")] {
+        let command = ised::external::pager_command(pager);
+        assert_eq!(command.get_args().last(), Some(OsStr::new("less")));
+    }
+}
+
+#[test]
+fn test_f3_requests_pager_with_substituted_content() {
+    use crossterm::event::{KeyCode, KeyEvent};
+
+    let tmp_dir = tempdir::TempDir::new("ised_test_pager").unwrap();
+    let path = tmp_dir.path().join("a.txt");
+    std::fs::write(&path, "foo a\n").unwrap();
+    let files = vec![path.display().to_string()];
+
+    let mut app = ised::app::App::new();
+    app.from_input = "foo".to_string();
+    app.to_input = "bar".to_string();
+
+    app.handle_key_event(KeyEvent::from(KeyCode::F(3)), &files)
+        .unwrap();
+    assert_eq!(app.pager_request.as_deref(), Some("bar a\n"));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "foo a\n");
+}
@@ -0,0 +1,47 @@
+use ised::git::staged_files;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempdir::TempDir;
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
+#[test]
+fn test_staged_files_lists_only_staged() {
+    let tmp_dir = TempDir::new("ised_test_staged").unwrap();
+    let dir = tmp_dir.path();
+    git(dir, &["init", "-q"]);
+
+    fs::write(dir.join("staged.txt"), "staged").unwrap();
+    fs::write(dir.join("unstaged.txt"), "unstaged").unwrap();
+    git(dir, &["add", "staged.txt"]);
+
+    let files = staged_files(dir).unwrap();
+
+    assert_eq!(files, vec![dir.join("staged.txt").display().to_string()]);
+}
+
+#[test]
+fn test_staged_files_errors_when_nothing_staged() {
+    let tmp_dir = TempDir::new("ised_test_nothing_staged").unwrap();
+    let dir = tmp_dir.path();
+    git(dir, &["init", "-q"]);
+    fs::write(dir.join("file.txt"), "content").unwrap();
+
+    assert!(staged_files(dir).is_err());
+}
+
+#[test]
+fn test_staged_files_errors_outside_git_repo() {
+    let tmp_dir = TempDir::new("ised_test_no_repo").unwrap();
+
+    assert!(staged_files(tmp_dir.path()).is_err());
+}
@@ -0,0 +1,123 @@
+use ised::app::App;
+use ised::utils::file_set_delta;
+
+fn app_with_files() -> App {
+    let mut app = App::with_config(None);
+    app.files = ["src/main.rs", "src/ui.rs", "README.md", "docs/guide.md"]
+        .iter()
+        .map(|f| f.to_string())
+        .collect();
+    app
+}
+
+#[test]
+fn test_commented_patterns_are_ignored() {
+    let mut app = app_with_files();
+
+    app.filter_input = "*.rs, #!src/ui.rs".to_string();
+    assert_eq!(app.filter_files(), vec!["src/main.rs", "src/ui.rs"]);
+
+    app.filter_input = "*.rs, !src/ui.rs".to_string();
+    assert_eq!(app.filter_files(), vec!["src/main.rs"]);
+}
+
+#[test]
+fn test_only_comments_match_everything() {
+    let mut app = app_with_files();
+    app.filter_input = "#*.rs,#docs/**".to_string();
+
+    assert_eq!(app.filter_files(), app.files);
+}
+
+#[test]
+fn test_regex_path_filter_includes_and_excludes() {
+    let mut app = app_with_files();
+    app.path_regex = true;
+
+    app.filter_input = r"\.md$".to_string();
+    assert_eq!(app.filter_files(), vec!["README.md", "docs/guide.md"]);
+
+    app.filter_input = r"^src/, !ui\.rs$".to_string();
+    assert_eq!(app.filter_files(), vec!["src/main.rs"]);
+
+    app.filter_input = r"!^docs/".to_string();
+    assert_eq!(
+        app.filter_files(),
+        vec!["src/main.rs", "src/ui.rs", "README.md"]
+    );
+}
+
+#[test]
+fn test_toggling_path_regex_reinterprets_filter() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    let mut app = app_with_files();
+    app.filter_input = "*.rs".to_string();
+    assert_eq!(app.filter_files().len(), 2);
+
+    app.handle_key_event(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::ALT), &[])
+        .unwrap();
+    assert!(app.path_regex);
+    assert!(app.filter_files().is_empty());
+
+    app.filter_input = r"main\.rs".to_string();
+    assert_eq!(app.filter_files(), vec!["src/main.rs"]);
+}
+
+fn hint_for(filter: &str) -> Option<String> {
+    let mut app = app_with_files();
+    app.is_loading = false;
+    app.filter_input = filter.to_string();
+    let filtered = app.filter_files();
+    app.filter_hint(&filtered)
+}
+
+#[test]
+fn test_filter_hint_explains_empty_list() {
+    assert_eq!(
+        hint_for("*.rs,!**"),
+        Some("Exclude pattern !** matches every file".to_string())
+    );
+    assert_eq!(
+        hint_for("*.py"),
+        Some("Include patterns *.py match no files".to_string())
+    );
+    assert_eq!(
+        hint_for("*.rs,!src/**"),
+        Some("Exclude patterns remove every file the include patterns match".to_string())
+    );
+    assert_eq!(hint_for("*.rs"), None);
+    assert_eq!(hint_for("*.rs,!src/ui.rs"), None);
+}
+
+#[test]
+fn test_file_set_delta() {
+    let files = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+    assert_eq!(
+        file_set_delta(&files(&["a", "b", "c"]), &files(&["b", "d"])),
+        (2, 1)
+    );
+    assert_eq!(file_set_delta(&files(&["a"]), &files(&["a"])), (0, 0));
+    assert_eq!(file_set_delta(&[], &files(&["a", "b"])), (0, 2));
+}
+
+#[test]
+fn test_filter_change_reports_delta() {
+    let mut app = app_with_files();
+    app.is_loading = false;
+
+    let filtered = app.filter_files();
+    app.note_filter_change(&filtered);
+    assert_eq!(app.status_message, None);
+
+    app.filter_input = "*.rs".to_string();
+    let filtered = app.filter_files();
+    app.note_filter_change(&filtered);
+    assert_eq!(app.status_message.as_deref(), Some("-2 +0 files"));
+
+    app.status_message = None;
+    app.filter_input = "*.md".to_string();
+    let filtered = app.filter_files();
+    app.note_filter_change(&filtered);
+    assert_eq!(app.status_message.as_deref(), Some("-2 +2 files"));
+}
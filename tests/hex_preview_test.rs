@@ -0,0 +1,64 @@
+use ised::app::App;
+use ised::config::Config;
+use ised::utils::hex_dump;
+use notify::event::{DataChange, ModifyKind};
+use notify::{Event, EventKind};
+use std::fs;
+use tempdir::TempDir;
+
+#[test]
+fn test_hex_dump_formats_offset_hex_and_ascii() {
+    let dump = hex_dump(b"Hello, world!\x00\x01\xffnext line");
+
+    assert_eq!(
+        dump,
+        "00000000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 00 01 ff  |Hello, world!...|\n\
+         00000010  6e 65 78 74 20 6c 69 6e  65                       |next line|\n"
+    );
+}
+
+#[test]
+fn test_hex_dump_of_empty_buffer() {
+    assert_eq!(hex_dump(&[]), "");
+}
+
+#[test]
+fn test_binary_files_get_hex_preview_and_never_match() {
+    let tmp_dir = TempDir::new("ised_test_hex").unwrap();
+    let path = tmp_dir.path().join("blob.bin");
+    fs::write(&path, b"foo\x00bar").unwrap();
+    let path = path.display().to_string();
+
+    let config: Config = toml::from_str("[display]\nhex_preview = true").unwrap();
+    let mut app = App::with_config(Some(config));
+    app.from_input = "foo".to_string();
+    app.to_input = "baz".to_string();
+
+    let preview = app.preview(&path).unwrap();
+    assert!(preview.hex);
+    assert!(preview.content.starts_with("00000000  66 6f 6f 00"));
+
+    assert!(app.affected_files(std::slice::from_ref(&path)).is_empty());
+    assert_eq!(app.apply_all(std::slice::from_ref(&path)).errors, 1);
+    assert_eq!(fs::read(&path).unwrap(), b"foo\x00bar");
+}
+
+#[test]
+fn test_text_detection_is_cached_until_the_file_changes() {
+    let tmp_dir = TempDir::new("ised_test_hex_cache").unwrap();
+    let path = tmp_dir.path().join("blob");
+    fs::write(&path, b"foo\x00bar").unwrap();
+    let path_str = path.display().to_string();
+
+    let config: Config = toml::from_str("[display]\nhex_preview = true").unwrap();
+    let app = App::with_config(Some(config));
+    assert!(app.preview(&path_str).unwrap().hex);
+
+    fs::write(&path, "foo bar").unwrap();
+    assert!(app.preview(&path_str).unwrap().hex);
+
+    app.handle_watch_event(
+        &Event::new(EventKind::Modify(ModifyKind::Data(DataChange::Content))).add_path(path),
+    );
+    assert!(!app.preview(&path_str).unwrap().hex);
+}
@@ -1,4 +1,6 @@
-use ised::utils::highlight_diff_lines;
+use ised::theme::DiffTheme;
+use ised::utils::{highlight_diff_lines, highlight_diff_lines_with};
+use ratatui::style::Color;
 use ratatui::text::Line;
 
 fn line_to_string(line: &Line) -> String {
@@ -39,7 +41,8 @@ fn test_diff_with_removed_line() {
     let result = highlight_diff_lines(original, replaced);
     let lines: Vec<String> = result.iter().map(line_to_string).collect();
     assert!(lines.iter().any(|line| line.contains("- to be removed")));
-    assert_eq!(lines.iter().filter(|l| l.contains("- ")).count(), 2);
+    assert_eq!(lines.iter().filter(|l| l.contains("- ")).count(), 1);
+    assert!(!lines.iter().any(|line| line.contains("+ ")));
 }
 
 #[test]
@@ -51,3 +54,72 @@ fn test_diff_with_added_line() {
     let lines: Vec<String> = result.iter().map(line_to_string).collect();
     assert!(lines.iter().any(|line| line.contains("+ new line")));
 }
+
+#[test]
+fn test_diff_with_custom_markers_and_colors() {
+    let theme = DiffTheme {
+        removed_marker: "< ".to_string(),
+        added_marker: "> ".to_string(),
+        removed_color: Color::Magenta,
+        added_color: Color::Blue,
+        ..DiffTheme::default()
+    };
+
+    let result = highlight_diff_lines_with("old".to_string(), "new".to_string(), &theme);
+
+    assert_eq!(result.len(), 2);
+    assert_eq!(line_to_string(&result[0]), "< old");
+    assert_eq!(line_to_string(&result[1]), "> new");
+    assert!(result[0]
+        .spans
+        .iter()
+        .all(|span| span.style.fg == Some(Color::Magenta)));
+    assert!(result[1]
+        .spans
+        .iter()
+        .all(|span| span.style.fg == Some(Color::Blue)));
+}
+
+#[test]
+fn test_diff_with_hunk_headers() {
+    let theme = DiffTheme {
+        hunk_headers: true,
+        ..DiffTheme::default()
+    };
+    let original = "a\nb\nc\nd\ne".to_string();
+    let replaced = "a\nB\nc\nD\nE\nF".to_string();
+
+    let result = highlight_diff_lines_with(original, replaced, &theme);
+    let lines: Vec<String> = result.iter().map(line_to_string).collect();
+
+    assert_eq!(
+        lines,
+        vec![
+            "a",
+            "@@ -2,1 +2,1 @@",
+            "- b",
+            "+ B",
+            "c",
+            "@@ -4,2 +4,3 @@",
+            "- d",
+            "+ D",
+            "- e",
+            "+ E",
+            "+ F",
+        ]
+    );
+    assert_eq!(result[1].spans[0].style.fg, Some(Color::Cyan));
+}
+
+#[test]
+fn test_diff_with_line_expanded_into_several() {
+    let original = "before\nfoo\nafter".to_string();
+    let replaced = "before\none\ntwo\nthree\nafter".to_string();
+
+    let result = highlight_diff_lines(original, replaced);
+    let lines: Vec<String> = result.iter().map(line_to_string).collect();
+    assert_eq!(
+        lines,
+        vec!["before", "- foo", "+ one", "+ two", "+ three", "after"]
+    );
+}
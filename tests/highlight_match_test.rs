@@ -1,4 +1,5 @@
-use ised::utils::highlight_match;
+use ised::utils::{emphasize_basename, highlight_match};
+use ratatui::style::{Color, Modifier};
 use ratatui::text::Line;
 
 fn line_to_string(line: &Line) -> String {
@@ -64,3 +65,43 @@ fn test_highlight_partial_match() {
         .iter()
         .any(|s| s.content.as_ref() == "part" && s.style.fg == Some(ratatui::style::Color::Green)));
 }
+
+#[test]
+fn test_emphasize_basename_dims_directories() {
+    let path = "src/deep/mod.rs";
+    let line = emphasize_basename(highlight_match(path, "nothing").remove(0), path);
+
+    let spans = line
+        .spans
+        .iter()
+        .map(|s| (s.content.as_ref(), s.style.add_modifier))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        spans,
+        vec![("src/deep/", Modifier::DIM), ("mod.rs", Modifier::BOLD)]
+    );
+}
+
+#[test]
+fn test_emphasize_basename_keeps_match_highlight() {
+    let path = "src/deep/mod.rs";
+    let line = emphasize_basename(highlight_match(path, "p/mo").remove(0), path);
+
+    assert_eq!(line_to_string(&line), path);
+    let spans = line
+        .spans
+        .iter()
+        .map(|s| (s.content.as_ref(), s.style.fg, s.style.add_modifier))
+        .collect::<Vec<_>>();
+    assert_eq!(spans[0], ("src/dee", None, Modifier::DIM));
+    assert_eq!(spans[1].0, "p/");
+    assert_eq!(spans[1].1, Some(Color::Green));
+    assert!(!spans[1].2.contains(Modifier::DIM));
+    assert_eq!(spans[2].0, "mo");
+    assert_eq!(spans[2].1, Some(Color::Green));
+    assert!(spans[2].2.contains(Modifier::BOLD));
+    assert_eq!(spans[3], ("d.rs", None, Modifier::BOLD));
+
+    let bare = emphasize_basename(Line::from("README.md"), "README.md");
+    assert_eq!(bare.spans[0].style.add_modifier, Modifier::BOLD);
+}
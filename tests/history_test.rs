@@ -0,0 +1,100 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::{App, Focus};
+use ised::history::{History, HistorySearch};
+
+fn history(entries: &[&str]) -> History {
+    let mut history = History::default();
+    for entry in entries {
+        history.push(entry);
+    }
+    history
+}
+
+fn press(app: &mut App, code: KeyCode, modifiers: KeyModifiers) {
+    app.handle_key_event(KeyEvent::new(code, modifiers), &[])
+        .unwrap();
+}
+
+#[test]
+fn test_history_keeps_latest_unique_entries() {
+    let history = history(&["a", "b", "", "a"]);
+    assert_eq!(history.entries(), ["b", "a"]);
+}
+
+#[test]
+fn test_search_prefers_recent_matches() {
+    let history = history(&[r"fn (\w+)", "let x", r"fn main", "foo"]);
+    assert_eq!(history.search("fn", 0), Some("fn main"));
+    assert_eq!(history.search("fn", 1), Some(r"fn (\w+)"));
+    assert_eq!(history.search("fn", 2), None);
+    assert_eq!(history.search("", 0), Some("foo"));
+    assert_eq!(history.search("zzz", 0), None);
+}
+
+#[test]
+fn test_repeated_search_cycles_through_matches() {
+    let history = history(&["fn a", "fn b", "other"]);
+    let mut search = HistorySearch {
+        query: "fn".to_string(),
+        skip: 0,
+    };
+    search.next(&history);
+    assert_eq!(history.search(&search.query, search.skip), Some("fn a"));
+    search.next(&history);
+    assert_eq!(history.search(&search.query, search.skip), Some("fn b"));
+}
+
+#[test]
+fn test_ctrl_r_recalls_from_pattern() {
+    let mut app = App::new();
+    app.from_history = history(&[r"old_(\d+)", "unrelated", r"new_(\d+)"]);
+    app.focus = Focus::From;
+    app.from_input = "draft".to_string();
+
+    press(&mut app, KeyCode::Char('r'), KeyModifiers::CONTROL);
+    for c in "_(".chars() {
+        press(&mut app, KeyCode::Char(c), KeyModifiers::NONE);
+    }
+    assert_eq!(
+        app.history_search_view(Focus::From),
+        Some(("search: _(".to_string(), r"new_(\d+)".to_string()))
+    );
+    assert_eq!(app.from_input, "draft");
+
+    press(&mut app, KeyCode::Char('r'), KeyModifiers::CONTROL);
+    press(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+    assert!(app.history_search.is_none());
+    assert_eq!(app.from_input, r"old_(\d+)");
+    assert_eq!(app.from_cursor, r"old_(\d+)".len());
+}
+
+#[test]
+fn test_escape_cancels_search() {
+    let mut app = App::new();
+    app.to_history = history(&["replacement"]);
+    app.focus = Focus::To;
+    app.to_input = "keep".to_string();
+
+    press(&mut app, KeyCode::Char('r'), KeyModifiers::CONTROL);
+    press(&mut app, KeyCode::Char('x'), KeyModifiers::NONE);
+    assert_eq!(
+        app.history_search_view(Focus::To),
+        Some(("failed search: x".to_string(), "keep".to_string()))
+    );
+    press(&mut app, KeyCode::Esc, KeyModifiers::NONE);
+    assert!(app.history_search.is_none());
+    assert_eq!(app.to_input, "keep");
+}
+
+#[test]
+fn test_apply_records_history() {
+    let mut app = App::new();
+    app.files = Vec::new();
+    app.from_input = "foo".to_string();
+    app.to_input = "bar".to_string();
+    app.confirm = ised::app::ConfirmState::ConfirmingAll(Vec::new());
+    press(&mut app, KeyCode::Char('y'), KeyModifiers::NONE);
+
+    assert_eq!(app.from_history.entries(), ["foo"]);
+    assert_eq!(app.to_history.entries(), ["bar"]);
+}
@@ -0,0 +1,146 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::{App, Focus};
+use ised::hunk::{apply_hunks, change_lines, hunks, step_wrapping};
+use std::collections::HashSet;
+use std::fs;
+use tempdir::TempDir;
+
+const ORIGINAL: &str = "foo 1\nkeep\nfoo 2\nfoo 3\nkeep\nfoo 4\n";
+const REPLACED: &str = "bar 1\nkeep\nbar 2\nbar 3\nkeep\nbar 4\n";
+
+#[test]
+fn test_hunks_group_consecutive_changes() {
+    let found = hunks(ORIGINAL, REPLACED, false);
+    let ranges = found.iter().map(|h| h.pairs.clone()).collect::<Vec<_>>();
+    assert_eq!(ranges, vec![0..1, 2..4, 5..6]);
+
+    let lines = found.iter().map(|h| h.line).collect::<Vec<_>>();
+    assert_eq!(lines, vec![0, 3, 8]);
+
+    let with_headers = hunks(ORIGINAL, REPLACED, true);
+    let lines = with_headers.iter().map(|h| h.line).collect::<Vec<_>>();
+    assert_eq!(lines, vec![0, 4, 10]);
+}
+
+#[test]
+fn test_apply_subset_of_hunks() {
+    let none = HashSet::new();
+    assert_eq!(apply_hunks(ORIGINAL, REPLACED, &none), REPLACED);
+
+    let all = HashSet::from([0, 1, 2]);
+    assert_eq!(apply_hunks(ORIGINAL, REPLACED, &all), ORIGINAL);
+
+    let middle = HashSet::from([1]);
+    assert_eq!(
+        apply_hunks(ORIGINAL, REPLACED, &middle),
+        "bar 1\nkeep\nfoo 2\nfoo 3\nkeep\nbar 4\n"
+    );
+}
+
+#[test]
+fn test_apply_hunks_with_changed_line_count() {
+    let original = "a\nb\nc";
+    let replaced = "a\nx\ny\nz\n";
+    let rejected = HashSet::from([0]);
+    assert_eq!(apply_hunks(original, replaced, &rejected), original);
+}
+
+#[test]
+fn test_multi_line_replacement_stays_one_hunk() {
+    let original = "keep\nfoo\nkeep\nfoo\n";
+    let replaced = "keep\na\nb\nc\nkeep\na\nb\nc\n";
+
+    let found = hunks(original, replaced, false);
+    let ranges = found.iter().map(|h| h.pairs.clone()).collect::<Vec<_>>();
+    assert_eq!(ranges, vec![1..4, 5..8]);
+    assert_eq!(
+        change_lines(original, replaced, false),
+        vec![1, 3, 4, 6, 8, 9]
+    );
+
+    let rejected = HashSet::from([1]);
+    assert_eq!(
+        apply_hunks(original, replaced, &rejected),
+        "keep\na\nb\nc\nkeep\nfoo\n"
+    );
+}
+
+#[test]
+fn test_apply_writes_only_accepted_hunks() {
+    let tmp_dir = TempDir::new("ised_test_hunks").unwrap();
+    let path = tmp_dir.path().join("a.txt");
+    fs::write(&path, ORIGINAL).unwrap();
+    let path = path.display().to_string();
+    let files = vec![path.clone()];
+
+    let mut app = App::new();
+    app.from_input = "foo".to_string();
+    app.to_input = "bar".to_string();
+    app.focus = Focus::DiffView;
+
+    let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+    app.handle_key_event(key(']'), &files).unwrap();
+    assert_eq!(app.hunk_cursor, 1);
+    assert_eq!(app.diff_scroll, 3);
+    app.handle_key_event(key(' '), &files).unwrap();
+    assert_eq!(
+        app.hunk_status(&path),
+        Some("hunk 2/3, 1 skipped".to_string())
+    );
+
+    app.focus = Focus::FileList;
+    app.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE), &files)
+        .unwrap();
+    app.handle_key_event(key('y'), &files).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "bar 1\nkeep\nfoo 2\nfoo 3\nkeep\nbar 4\n"
+    );
+    assert!(app.rejected_hunks.is_empty());
+}
+
+#[test]
+fn test_change_lines_point_at_rendered_diff_rows() {
+    assert_eq!(change_lines(ORIGINAL, REPLACED, false), vec![0, 3, 5, 8]);
+    assert_eq!(change_lines(ORIGINAL, REPLACED, true), vec![1, 5, 7, 11]);
+    assert_eq!(change_lines("a\n", "a\nb\n", false), vec![1]);
+    assert!(change_lines(ORIGINAL, ORIGINAL, false).is_empty());
+}
+
+#[test]
+fn test_step_wrapping() {
+    assert_eq!(step_wrapping(None, 0, true), None);
+    assert_eq!(step_wrapping(None, 4, true), Some(0));
+    assert_eq!(step_wrapping(None, 4, false), Some(3));
+    assert_eq!(step_wrapping(Some(1), 4, true), Some(2));
+    assert_eq!(step_wrapping(Some(3), 4, true), Some(0));
+    assert_eq!(step_wrapping(Some(0), 4, false), Some(3));
+    assert_eq!(step_wrapping(Some(9), 4, true), Some(0));
+}
+
+#[test]
+fn test_next_change_scrolls_the_diff() {
+    let tmp_dir = TempDir::new("ised_test_changes").unwrap();
+    let path = tmp_dir.path().join("a.txt");
+    fs::write(&path, ORIGINAL).unwrap();
+    let path = path.display().to_string();
+    let files = vec![path.clone()];
+
+    let mut app = App::new();
+    app.from_input = "foo".to_string();
+    app.to_input = "bar".to_string();
+    app.focus = Focus::DiffView;
+    assert_eq!(app.change_status(&path), Some("4 changes".to_string()));
+
+    let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+    app.handle_key_event(key('n'), &files).unwrap();
+    app.handle_key_event(key('n'), &files).unwrap();
+    assert_eq!(app.diff_scroll, 3);
+    assert_eq!(app.change_status(&path), Some("change 2/4".to_string()));
+
+    app.handle_key_event(key('N'), &files).unwrap();
+    app.handle_key_event(key('N'), &files).unwrap();
+    assert_eq!(app.diff_scroll, 8);
+    assert_eq!(app.change_status(&path), Some("change 4/4".to_string()));
+}
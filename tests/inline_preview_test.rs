@@ -0,0 +1,55 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::App;
+use ised::utils::first_changed_line;
+use std::fs;
+use tempdir::TempDir;
+
+#[test]
+fn test_first_changed_line() {
+    assert_eq!(
+        first_changed_line("a\n  let foo = 1;\nfoo\n", "a\n  let bar = 1;\nbar\n"),
+        Some(("let foo = 1;".to_string(), "let bar = 1;".to_string()))
+    );
+    assert_eq!(
+        first_changed_line("a\n", "a\nb\n"),
+        Some((String::new(), "b".to_string()))
+    );
+    assert_eq!(first_changed_line("same\n", "same\n"), None);
+}
+
+#[test]
+fn test_inline_preview_for_known_file() {
+    let tmp_dir = TempDir::new("ised_test_inline_preview").unwrap();
+    let changed = tmp_dir.path().join("changed.rs");
+    let untouched = tmp_dir.path().join("untouched.rs");
+    fs::write(&changed, "fn main() {\n    let foo = 1;\n}\n").unwrap();
+    fs::write(&untouched, "fn main() {}\n").unwrap();
+    let changed = changed.display().to_string();
+    let untouched = untouched.display().to_string();
+
+    let mut app = App::with_config(None);
+    app.files = vec![changed.clone(), untouched.clone()];
+    app.from_input = "foo".to_string();
+    app.to_input = "bar".to_string();
+
+    app.filter_files();
+    assert_eq!(app.inline_preview(&changed), None);
+
+    app.handle_key_event(KeyEvent::new(KeyCode::Char('q'), KeyModifiers::ALT), &[])
+        .unwrap();
+    assert!(app.show_inline_preview);
+
+    app.filter_files();
+    assert_eq!(
+        app.inline_preview(&changed),
+        Some(("let foo = 1;".to_string(), "let bar = 1;".to_string()))
+    );
+    assert_eq!(app.inline_preview(&untouched), None);
+
+    app.to_input = "baz".to_string();
+    app.filter_files();
+    assert_eq!(
+        app.inline_preview(&changed).map(|(_, after)| after),
+        Some("let baz = 1;".to_string())
+    );
+}
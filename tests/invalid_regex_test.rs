@@ -0,0 +1,73 @@
+mod common;
+
+use common::rows;
+use ised::app::App;
+use ratatui::style::Color;
+
+fn render(app: &mut App) -> (Vec<String>, Option<Color>) {
+    let buffer = common::render(app, &[], None, 80, 20);
+    let rows = rows(&buffer);
+    let color = rows.iter().enumerate().find_map(|(y, row)| {
+        row.find("Invalid regex").map(|byte| {
+            let x = row[..byte].chars().count() as u16;
+            buffer[(x, y as u16)].fg
+        })
+    });
+    (rows, color)
+}
+
+#[test]
+fn test_invalid_from_shows_error_until_fixed() {
+    let mut app = App::with_config(None);
+    app.is_loading = false;
+    app.from_input = "(foo".to_string();
+    app.update_from_error();
+    assert_eq!(app.from_error.as_deref(), Some("unclosed group"));
+
+    let (rows, color) = render(&mut app);
+    assert!(rows
+        .iter()
+        .any(|row| row.contains("Invalid regex: unclosed group")));
+    assert_eq!(color, Some(Color::Red));
+
+    app.from_input = "(foo)".to_string();
+    app.update_from_error();
+    assert_eq!(app.from_error, None);
+    let (rows, _) = render(&mut app);
+    assert!(!rows.iter().any(|row| row.contains("Invalid regex")));
+}
+
+#[test]
+fn test_literal_and_empty_from_have_no_error() {
+    let mut app = App::with_config(None);
+    app.update_from_error();
+    assert_eq!(app.from_error, None);
+
+    app.from_input = "a[".to_string();
+    app.update_from_error();
+    assert!(app.from_error.is_some());
+
+    app.options.literal = true;
+    app.update_from_error();
+    assert_eq!(app.from_error, None);
+}
+
+#[test]
+fn test_from_error_is_only_rechecked_when_inputs_change() {
+    let mut app = App::with_config(None);
+    app.from_input = "a[".to_string();
+    app.update_from_error();
+    assert!(app.from_error.is_some());
+
+    app.from_error = None;
+    app.update_from_error();
+    assert_eq!(app.from_error, None);
+
+    app.options.case_insensitive = true;
+    app.update_from_error();
+    assert!(app.from_error.is_some());
+
+    app.from_input = "a[b]".to_string();
+    app.update_from_error();
+    assert_eq!(app.from_error, None);
+}
@@ -0,0 +1,83 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::App;
+use ised::utils::{apply_substitution_with, SubstitutionOptions};
+use std::fs;
+use tempdir::TempDir;
+
+fn keep_indent() -> SubstitutionOptions {
+    SubstitutionOptions {
+        keep_indent: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_indentation_is_preserved() {
+    let content = "fn main() {\n    let x = 1;\n\tlet y = 2;\n}\n";
+    let result = apply_substitution_with(
+        content,
+        "^let (\\w+) = (\\d+);$",
+        "const $1: i32 = $2;",
+        &keep_indent(),
+    );
+
+    assert_eq!(
+        result,
+        "fn main() {\n    const x: i32 = 1;\n\tconst y: i32 = 2;\n}\n"
+    );
+}
+
+#[test]
+fn test_whole_line_replacement_keeps_indent() {
+    let content = "  old\n    old";
+    let result = apply_substitution_with(content, ".*", "new", &keep_indent());
+
+    assert_eq!(result, "  new\n    new");
+}
+
+#[test]
+fn test_multiline_replacement_is_reindented() {
+    let content = "    call(a, b);\n";
+    let result = apply_substitution_with(
+        content,
+        "call\\((\\w), (\\w)\\);",
+        "first($1);\nsecond($2);",
+        &keep_indent(),
+    );
+
+    assert_eq!(result, "    first(a);\n    second(b);\n");
+}
+
+#[test]
+fn test_without_the_mode_indentation_is_matched_normally() {
+    let content = "    old\n";
+    let result =
+        apply_substitution_with(content, "^\\s*old", "new", &SubstitutionOptions::default());
+
+    assert_eq!(result, "new\n");
+}
+
+#[test]
+fn test_file_list_matches_indented_lines() {
+    let tmp_dir = TempDir::new("ised_test_keep_indent_filter").unwrap();
+    let path = tmp_dir.path().join("main.rs");
+    fs::write(&path, "fn main() {\n    let x = 1;\n}\n").unwrap();
+    let files = vec![path.display().to_string()];
+
+    let mut app = App::with_config(None);
+    app.files = files.clone();
+    app.is_loading = false;
+    app.from_input = "^let (\\w+) = (\\d+);$".to_string();
+    assert!(app.filter_files().is_empty());
+
+    app.options.keep_indent = true;
+    assert_eq!(app.filter_files(), files);
+}
+
+#[test]
+fn test_alt_k_toggles_keep_indent() {
+    let mut app = App::with_config(None);
+    app.handle_key_event(KeyEvent::new(KeyCode::Char('k'), KeyModifiers::ALT), &[])
+        .unwrap();
+    assert!(app.options.keep_indent);
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use common::render_rows;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::App;
+use ised::utils::{apply_substitution_with, SubstitutionOptions};
+use ratatui::text::Line;
+
+fn line_to_string(line: &Line) -> String {
+    line.iter().map(|s| s.content.as_ref()).collect::<String>()
+}
+
+#[test]
+fn test_literal_mode_escapes_metacharacters() {
+    let options = SubstitutionOptions {
+        literal: true,
+        ..Default::default()
+    };
+    let result = apply_substitution_with("a.b axb (c)", "a.b", "X", &options);
+
+    assert_eq!(result, "X axb (c)");
+}
+
+#[test]
+fn test_literal_mode_does_not_expand_groups() {
+    let options = SubstitutionOptions {
+        literal: true,
+        ..Default::default()
+    };
+    let result = apply_substitution_with("(c)", "(c)", "$1", &options);
+
+    assert_eq!(result, "$1");
+}
+
+#[test]
+fn test_literal_mode_preview_matches_literal_replacement() {
+    let mut app = App::new();
+    app.options.literal = true;
+    app.from_input = "f(x)".to_string();
+    app.to_input = "g(x)".to_string();
+
+    let lines: Vec<String> = app
+        .diff_lines("a.rs", "f(x)\nfx\n".to_string())
+        .iter()
+        .map(line_to_string)
+        .collect();
+
+    assert_eq!(lines, vec!["- f(x)", "+ g(x)", "fx"]);
+}
+
+#[test]
+fn test_literal_mode_is_shown_in_the_field_titles() {
+    let render = |app: &mut App| render_rows(app, &[], None, 80, 20);
+
+    let mut app = App::with_config(None);
+    app.is_loading = false;
+    assert!(render(&mut app).iter().all(|row| !row.contains("literal")));
+
+    app.handle_key_event(KeyEvent::new(KeyCode::Char('l'), KeyModifiers::ALT), &[])
+        .unwrap();
+    assert!(app.options.literal);
+    assert!(app.clone().options.literal);
+    let rows = render(&mut app);
+    assert!(rows.iter().any(|row| row.contains("[F]rom (literal")));
+    assert!(rows.iter().any(|row| row.contains("[T]o (literal)")));
+}
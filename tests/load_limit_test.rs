@@ -0,0 +1,90 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::{App, Focus};
+use ised::config::Config;
+use std::fs;
+use tempdir::TempDir;
+
+#[tokio::test]
+async fn test_walk_pauses_past_max_files() {
+    let tmp_dir = TempDir::new("ised_test_load_limit").unwrap();
+    for i in 0..5 {
+        fs::write(tmp_dir.path().join(format!("{}.txt", i)), "text").unwrap();
+    }
+
+    let config: Config = toml::from_str("[files]\nmax_files = 3").unwrap();
+    let mut app = App::with_config(Some(config));
+    app.set_root(tmp_dir.path());
+    app.load_files().await;
+
+    assert_eq!(app.load_limit_hit, Some(3));
+    assert_eq!(app.files.len(), 3);
+    app.focus = Focus::FileList;
+
+    app.handle_key_event(
+        KeyEvent::new(KeyCode::Char('a'), KeyModifiers::CONTROL),
+        &[],
+    )
+    .unwrap();
+    assert_eq!(app.load_limit_hit, Some(3));
+
+    app.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE), &[])
+        .unwrap();
+    assert_eq!(app.load_limit_hit, None);
+    assert!(app.rescan_requested);
+
+    let mut scanner = app.clone();
+    scanner.load_files().await;
+    assert_eq!(scanner.load_limit_hit, None);
+    assert_eq!(scanner.files.len(), 5);
+
+    let config: Config = toml::from_str("[files]\nmax_files = 5").unwrap();
+    let mut app = App::with_config(Some(config));
+    app.set_root(tmp_dir.path());
+    app.load_files().await;
+    assert_eq!(app.load_limit_hit, None);
+    assert_eq!(app.files.len(), 5);
+
+    let config: Config = toml::from_str("[files]\nmax_files = 0").unwrap();
+    let mut app = App::with_config(Some(config));
+    app.set_root(tmp_dir.path());
+    app.load_files().await;
+    assert_eq!(app.load_limit_hit, None);
+}
+
+#[test]
+fn test_declining_keeps_partial_list() {
+    let mut app = App::with_config(None);
+    app.load_limit_hit = Some(10);
+    app.focus = Focus::FileList;
+
+    app.handle_key_event(KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE), &[])
+        .unwrap();
+
+    assert_eq!(app.load_limit_hit, None);
+    assert!(!app.rescan_requested);
+    assert!(!app.load_everything);
+    assert!(app.status_message.is_some());
+}
+
+#[test]
+fn test_prompt_leaves_other_input_alone() {
+    let mut app = App::with_config(None);
+    app.load_limit_hit = Some(10);
+    app.focus = Focus::From;
+
+    for c in "yn".chars() {
+        app.handle_key_event(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE), &[])
+            .unwrap();
+    }
+    assert_eq!(app.from_input, "yn");
+    assert_eq!(app.load_limit_hit, Some(10));
+
+    app.focus = Focus::FileList;
+    let quit = app
+        .handle_key_event(
+            KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL),
+            &[],
+        )
+        .unwrap();
+    assert!(quit);
+}
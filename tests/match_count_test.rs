@@ -0,0 +1,80 @@
+mod common;
+
+use common::render_rows;
+use crossterm::event::{KeyCode, KeyEvent};
+use ised::app::{App, Focus};
+use std::fs;
+use tempdir::TempDir;
+
+fn diff_title(app: &mut App, files: &[String]) -> String {
+    render_rows(app, files, None, 120, 20)
+        .into_iter()
+        .find_map(|row| row.find("[D]iff").map(|start| row[start..].to_string()))
+        .unwrap()
+}
+
+#[test]
+fn test_match_count_in_diff_title() {
+    let tmp_dir = TempDir::new("ised_test_match_count").unwrap();
+    let path = tmp_dir.path().join("a.txt");
+    fs::write(&path, "foo foo\nbar foo\n").unwrap();
+    let files = vec![path.display().to_string()];
+
+    let mut app = App::new();
+    app.files = files.clone();
+    app.is_loading = false;
+    assert_eq!(app.match_count_status(&files[0]), None);
+    assert!(!diff_title(&mut app, &files).contains("match"));
+
+    app.focus = Focus::From;
+    for c in "fo".chars() {
+        app.handle_key_event(KeyEvent::from(KeyCode::Char(c)), &files)
+            .unwrap();
+    }
+    assert!(diff_title(&mut app, &files).starts_with("[D]iff (3 matches"));
+
+    app.handle_key_event(KeyEvent::from(KeyCode::Char('x')), &files)
+        .unwrap();
+    assert!(diff_title(&mut app, &files).starts_with("[D]iff (0 matches"));
+
+    app.from_input = "bar".to_string();
+    assert_eq!(
+        app.match_count_status(&files[0]).as_deref(),
+        Some("1 match")
+    );
+
+    app.from_input = "(".to_string();
+    assert_eq!(app.match_count_status(&files[0]), None);
+}
+
+#[test]
+fn test_diff_notes_follow_preview_limit() {
+    let tmp_dir = TempDir::new("ised_test_match_count_limit").unwrap();
+    let path = tmp_dir.path().join("a.txt");
+    fs::write(&path, "foo\n".repeat(1000)).unwrap();
+    let files = vec![path.display().to_string()];
+
+    let mut app = App::new();
+    app.files = files.clone();
+    app.is_loading = false;
+    app.preview_limit = 1024;
+    app.from_input = "foo".to_string();
+    app.to_input = "ba".to_string();
+
+    assert_eq!(
+        app.match_count_status(&files[0]).as_deref(),
+        Some("256 matches")
+    );
+    assert_eq!(
+        app.byte_delta_status(&files[0]).as_deref(),
+        Some("-256 bytes")
+    );
+    assert_eq!(app.file_hunks(&files[0]).len(), 1);
+
+    app.to_input = "bar".to_string();
+    assert_eq!(app.byte_delta_status(&files[0]).as_deref(), Some("0 bytes"));
+    assert_eq!(
+        app.match_count_status(&files[0]).as_deref(),
+        Some("256 matches")
+    );
+}
@@ -0,0 +1,58 @@
+use ised::app::App;
+use ised::utils::{match_summary, MatchSummary};
+use regex::Regex;
+use std::fs;
+use tempdir::TempDir;
+
+#[test]
+fn test_match_summary_first_line_and_count() {
+    let content = "one\ntwo foo\nthree\nfoo four foo\n";
+    let re = Regex::new("foo").unwrap();
+
+    assert_eq!(
+        match_summary(content, &re),
+        Some(MatchSummary {
+            first_line: 2,
+            count: 3
+        })
+    );
+}
+
+#[test]
+fn test_match_summary_none_without_match() {
+    let re = Regex::new("foo").unwrap();
+
+    assert_eq!(match_summary("bar\nbaz", &re), None);
+}
+
+#[test]
+fn test_match_summary_label() {
+    let single = MatchSummary {
+        first_line: 42,
+        count: 1,
+    };
+    let many = MatchSummary {
+        first_line: 7,
+        count: 3,
+    };
+
+    assert_eq!(single.label(), "L42");
+    assert_eq!(many.label(), "L7, 3 matches");
+}
+
+#[test]
+fn test_filter_files_records_match_summaries() {
+    let tmp_dir = TempDir::new("ised_test_match_summary").unwrap();
+    let path = tmp_dir.path().join("a.txt");
+    fs::write(&path, "a\nb\nfoo\n").unwrap();
+    let path = path.display().to_string();
+
+    let mut app = App::new();
+    app.files = vec![path.clone()];
+    app.from_input = "foo".to_string();
+    app.show_match_lines = true;
+
+    app.filter_files();
+
+    assert_eq!(app.match_summary(&path).map(|s| s.first_line), Some(3));
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use common::{preview, render, rows};
+use ised::app::App;
+use ised::utils::highlight_captures;
+use ratatui::style::{Color, Modifier};
+use ratatui::text::Line;
+use regex::Regex;
+
+fn spans(line: &Line) -> Vec<(String, Option<Color>)> {
+    line.spans
+        .iter()
+        .map(|span| (span.content.to_string(), span.style.fg))
+        .collect()
+}
+
+#[test]
+fn test_highlights_match_and_capture_groups() {
+    let re = Regex::new(r"(\w+)=(\d+)").unwrap();
+    let lines = highlight_captures("a x=1 b\nnone\r\ny=22\n", &re);
+
+    assert_eq!(lines.len(), 3);
+    assert_eq!(
+        spans(&lines[0]),
+        vec![
+            ("a ".to_string(), None),
+            ("x".to_string(), Some(Color::Yellow)),
+            ("=".to_string(), Some(Color::Green)),
+            ("1".to_string(), Some(Color::Cyan)),
+            (" b".to_string(), None),
+        ]
+    );
+    assert_eq!(spans(&lines[1]), vec![("none".to_string(), None)]);
+    assert_eq!(
+        spans(&lines[2]),
+        vec![
+            ("y".to_string(), Some(Color::Yellow)),
+            ("=".to_string(), Some(Color::Green)),
+            ("22".to_string(), Some(Color::Cyan)),
+        ]
+    );
+    assert!(lines[0].spans[1]
+        .style
+        .add_modifier
+        .contains(Modifier::BOLD));
+}
+
+#[test]
+fn test_nested_groups_take_the_inner_color() {
+    let re = Regex::new(r"((a)b)c").unwrap();
+    let lines = highlight_captures("abc", &re);
+
+    assert_eq!(
+        spans(&lines[0]),
+        vec![
+            ("a".to_string(), Some(Color::Cyan)),
+            ("b".to_string(), Some(Color::Yellow)),
+            ("c".to_string(), Some(Color::Green)),
+        ]
+    );
+}
+
+#[test]
+fn test_match_view_replaces_the_diff() {
+    let files = vec!["a.txt".to_string()];
+    let mut app = App::with_config(None);
+    app.is_loading = false;
+    app.from_input = "(fo)o".to_string();
+    app.match_view = true;
+
+    let buffer = render(&mut app, &files, Some(preview("foo bar\n")), 80, 20);
+    let rows = rows(&buffer);
+    let title = rows[0].chars().skip(40).collect::<String>();
+    let row = rows[1].chars().skip(41).take(7).collect::<String>();
+    assert!(title.starts_with("┌[D]iff (matches)"));
+    assert_eq!(row, "foo bar");
+    assert_eq!(buffer[(41, 1)].fg, Color::Yellow);
+    assert_eq!(buffer[(43, 1)].fg, Color::Green);
+}
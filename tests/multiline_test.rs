@@ -0,0 +1,49 @@
+mod common;
+
+use common::render_rows;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::App;
+use ised::utils::{apply_substitution_with, effective_pattern, SubstitutionOptions};
+
+fn multiline() -> SubstitutionOptions {
+    SubstitutionOptions {
+        multiline: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_multiline_anchors_match_every_line() {
+    assert_eq!(
+        apply_substitution_with("a\na\n", "^a", "X", &SubstitutionOptions::default()),
+        "X\na\n"
+    );
+    assert_eq!(
+        apply_substitution_with("a\na\n", "^a", "X", &multiline()),
+        "X\nX\n"
+    );
+    assert_eq!(effective_pattern("^a", &multiline()), "(?m)^a");
+    let both = SubstitutionOptions {
+        case_insensitive: true,
+        ..multiline()
+    };
+    assert_eq!(effective_pattern("^a$", &both), "(?im)^a$");
+}
+
+#[test]
+fn test_alt_m_toggles_multiline_in_preview_and_title() {
+    let mut app = App::with_config(None);
+    app.is_loading = false;
+    app.from_input = "^a".to_string();
+    app.to_input = "X".to_string();
+    assert_eq!(app.substitute("a\na\n", "a.txt"), "X\na\n");
+
+    app.handle_key_event(KeyEvent::new(KeyCode::Char('m'), KeyModifiers::ALT), &[])
+        .unwrap();
+    assert!(app.options.multiline);
+    assert!(app.clone().options.multiline);
+    assert_eq!(app.substitute("a\na\n", "a.txt"), "X\nX\n");
+
+    let rows = render_rows(&mut app, &[], None, 80, 20);
+    assert!(rows.iter().any(|row| row.contains("[F]rom (m, 0 groups)")));
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use common::{preview, render};
+use ised::app::App;
+use ised::theme::{no_color, DiffTheme};
+use ised::utils::{highlight_diff_lines_with, highlight_match, Preview};
+use ratatui::style::{Color, Modifier};
+
+fn set_no_color() {
+    std::env::set_var("NO_COLOR", "1");
+    assert!(no_color());
+}
+
+#[test]
+fn test_highlight_helpers_drop_colors() {
+    set_no_color();
+
+    let matched = highlight_match("src/main.rs", "main");
+    let diff = highlight_diff_lines_with(
+        "old".to_string(),
+        "new".to_string(),
+        &DiffTheme {
+            hunk_headers: true,
+            ..DiffTheme::default()
+        },
+    );
+
+    for line in matched.iter().chain(diff.iter()) {
+        assert!(line.spans.iter().all(|span| span.style.fg.is_none()));
+    }
+    assert!(matched[0].spans[1]
+        .style
+        .add_modifier
+        .contains(Modifier::UNDERLINED));
+}
+
+#[test]
+fn test_rendered_frame_has_no_foreground_colors() {
+    set_no_color();
+
+    let mut app = App::with_config(None);
+    app.is_loading = false;
+    app.from_input = "old".to_string();
+    app.to_input = "new".to_string();
+    let files = vec!["a.txt".to_string()];
+
+    let preview = Preview {
+        truncated: true,
+        ..preview("old line")
+    };
+    let buffer = render(&mut app, &files, Some(preview), 80, 24);
+    assert!(buffer.content().iter().all(|cell| cell.fg == Color::Reset));
+}
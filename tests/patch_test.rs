@@ -0,0 +1,151 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::App;
+use ised::cli::Args;
+use ised::patch::{apply_patch, parse_patch, PatchLine};
+use std::fs;
+use std::path::PathBuf;
+use tempdir::TempDir;
+
+const PATCH: &str = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -2,3 +2,3 @@ fn main() {
+ let a = 1;
+-let b = 2;
++let b = 3;
+ let c = 4;
+";
+
+#[test]
+fn test_parse_unified_diff_hunk() {
+    let patches = parse_patch(PATCH).unwrap();
+    assert_eq!(patches.len(), 1);
+    assert_eq!(patches[0].path, "src/lib.rs");
+    assert_eq!(patches[0].hunks.len(), 1);
+    assert_eq!(patches[0].hunks[0].old_start, 2);
+    assert_eq!(
+        patches[0].hunks[0].lines,
+        vec![
+            PatchLine::Context("let a = 1;".to_string()),
+            PatchLine::Remove("let b = 2;".to_string()),
+            PatchLine::Add("let b = 3;".to_string()),
+            PatchLine::Context("let c = 4;".to_string()),
+        ]
+    );
+
+    assert!(parse_patch("just some text\n").is_err());
+    assert!(parse_patch("+++ b/a.txt\n@@ -1,2 +1,2 @@\n-a\n").is_err());
+}
+
+#[test]
+fn test_apply_hunk_with_context() {
+    let patch = &parse_patch(PATCH).unwrap()[0];
+    let content = "fn main() {\nlet a = 1;\nlet b = 2;\nlet c = 4;\n}\n";
+    assert_eq!(
+        apply_patch(content, patch).unwrap(),
+        "fn main() {\nlet a = 1;\nlet b = 3;\nlet c = 4;\n}\n"
+    );
+
+    let shifted = format!("// header\n// more\n{}", content);
+    assert_eq!(
+        apply_patch(&shifted, patch).unwrap(),
+        "// header\n// more\nfn main() {\nlet a = 1;\nlet b = 3;\nlet c = 4;\n}\n"
+    );
+}
+
+#[test]
+fn test_apply_hunk_context_mismatch() {
+    let patch = &parse_patch(PATCH).unwrap()[0];
+    let content = "fn main() {\nlet a = 10;\nlet b = 2;\nlet c = 4;\n}\n";
+    assert_eq!(
+        apply_patch(content, patch).unwrap_err(),
+        "hunk 1 doesn't match src/lib.rs"
+    );
+}
+
+#[test]
+fn test_apply_hunk_keeps_missing_trailing_newline() {
+    let patches =
+        parse_patch("--- a/a.txt\n+++ b/a.txt\n@@ -1 +1 @@\n-old\n\\ No newline at end of file\n+new\n\\ No newline at end of file\n")
+            .unwrap();
+    assert_eq!(apply_patch("old", &patches[0]).unwrap(), "new");
+}
+
+#[test]
+fn test_patch_previews_and_applies_through_confirm() {
+    let tmp_dir = TempDir::new("ised_test_patch").unwrap();
+    let file = tmp_dir.path().join("lib.rs");
+    fs::write(
+        &file,
+        "fn main() {\nlet a = 1;\nlet b = 2;\nlet c = 4;\n}\n",
+    )
+    .unwrap();
+    let path = file.display().to_string();
+
+    let mut patches = parse_patch(PATCH).unwrap();
+    patches[0].path = path.clone();
+    let mut app = App::new();
+    app.files = vec![path.clone()];
+    app.patches = patches;
+
+    let filtered = app.filter_files();
+    assert_eq!(filtered, vec![path.clone()]);
+    assert_eq!(app.patch_error(&path), None);
+    assert_eq!(app.file_hunks(&path).len(), 1);
+
+    app.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE), &filtered)
+        .unwrap();
+    app.handle_key_event(
+        KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE),
+        &filtered,
+    )
+    .unwrap();
+    assert_eq!(
+        fs::read_to_string(&file).unwrap(),
+        "fn main() {\nlet a = 1;\nlet b = 3;\nlet c = 4;\n}\n"
+    );
+}
+
+#[test]
+fn test_patch_mismatch_is_reported_and_not_written() {
+    let tmp_dir = TempDir::new("ised_test_patch").unwrap();
+    let file = tmp_dir.path().join("lib.rs");
+    fs::write(&file, "let a = 1;\nlet b = 5;\n").unwrap();
+    let path = file.display().to_string();
+
+    let mut patches = parse_patch(PATCH).unwrap();
+    patches[0].path = path.clone();
+    let mut app = App::new();
+    app.files = vec![path.clone()];
+    app.patches = patches;
+
+    assert!(app.patch_error(&path).unwrap().contains("doesn't match"));
+
+    let filtered = app.filter_files();
+    app.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE), &filtered)
+        .unwrap();
+    app.handle_key_event(
+        KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE),
+        &filtered,
+    )
+    .unwrap();
+    assert_eq!(
+        fs::read_to_string(&file).unwrap(),
+        "let a = 1;\nlet b = 5;\n"
+    );
+}
+
+#[test]
+fn test_patch_flag() {
+    let args = Args::parse(["--patch".to_string(), "fix.diff".to_string()]).unwrap();
+    assert_eq!(args.patch, Some(PathBuf::from("fix.diff")));
+    assert!(Args::parse(["--patch".to_string()]).is_err());
+    assert!(Args::parse([
+        "--staged".to_string(),
+        "--patch".to_string(),
+        "fix.diff".to_string()
+    ])
+    .is_err());
+}
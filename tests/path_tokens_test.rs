@@ -0,0 +1,87 @@
+use ised::app::App;
+use ised::utils::{expand_path_tokens, expand_path_tokens_escaped};
+use std::fs;
+use tempdir::TempDir;
+
+#[test]
+fn test_expand_file_token() {
+    assert_eq!(
+        expand_path_tokens("// \\file", "./src/app.rs"),
+        "// ./src/app.rs"
+    );
+}
+
+#[test]
+fn test_expand_filestem_token() {
+    assert_eq!(
+        expand_path_tokens("mod \\filestem;", "./src/app.rs"),
+        "mod app;"
+    );
+}
+
+#[test]
+fn test_expand_dir_token() {
+    assert_eq!(
+        expand_path_tokens("package \\dir", "./src/app.rs"),
+        "package ./src"
+    );
+}
+
+#[test]
+fn test_expand_leaves_other_text_untouched() {
+    assert_eq!(
+        expand_path_tokens("\\d+ $1 \\files", "./a.txt"),
+        "\\d+ $1 ./a.txts"
+    );
+}
+
+#[test]
+fn test_escaped_expansion_protects_backslashes_and_dollars() {
+    assert_eq!(
+        expand_path_tokens_escaped("\\dir/\\file $1", ".\\tests/a$1.txt"),
+        ".\\\\tests/.\\\\tests/a$$1.txt $1"
+    );
+}
+
+#[test]
+fn test_path_values_are_inserted_literally() {
+    let tmp_dir = TempDir::new("ised_test_path_tokens_literal").unwrap();
+    let dir = tmp_dir.path().join(".\\tests");
+    fs::create_dir(&dir).unwrap();
+    let path = dir.join("a$1.txt");
+    fs::write(&path, "NAME").unwrap();
+    let path = path.display().to_string();
+
+    let mut app = App::new();
+    app.from_input = "(NAME)".to_string();
+    app.to_input = "\\dir/\\filestem".to_string();
+    app.apply_all(std::slice::from_ref(&path));
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        format!("{}/a$1", dir.display())
+    );
+
+    fs::write(&path, "NAME").unwrap();
+    app.options.literal = true;
+    app.from_input = "NAME".to_string();
+    app.to_input = "\\filestem".to_string();
+    app.apply_all(std::slice::from_ref(&path));
+    assert_eq!(fs::read_to_string(&path).unwrap(), "a$1");
+}
+
+#[test]
+fn test_apply_expands_tokens_per_file() {
+    let tmp_dir = TempDir::new("ised_test_path_tokens").unwrap();
+    let a = tmp_dir.path().join("alpha.txt");
+    let b = tmp_dir.path().join("beta.txt");
+    fs::write(&a, "NAME").unwrap();
+    fs::write(&b, "NAME").unwrap();
+
+    let mut app = App::new();
+    app.from_input = "NAME".to_string();
+    app.to_input = "\\filestem".to_string();
+    app.apply_all(&[a.display().to_string(), b.display().to_string()]);
+
+    assert_eq!(fs::read_to_string(&a).unwrap(), "alpha");
+    assert_eq!(fs::read_to_string(&b).unwrap(), "beta");
+}
@@ -0,0 +1,59 @@
+#![cfg(unix)]
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::App;
+use ised::cli::Args;
+use ised::external::pipe_through;
+use std::fs;
+use tempdir::TempDir;
+
+#[test]
+fn test_pipe_through_cat_round_trips() {
+    let input = "first line\nsecond line\n";
+    assert_eq!(pipe_through("cat", input).unwrap(), input);
+}
+
+#[test]
+fn test_pipe_through_reports_failure() {
+    let output = pipe_through("echo oops >&2; exit 3", "").unwrap();
+    assert!(output.starts_with("oops\n"));
+    assert!(output.contains('3'));
+}
+
+#[test]
+fn test_pipe_substituted_files() {
+    let tmp_dir = TempDir::new("ised_test_pipe").unwrap();
+    let a = tmp_dir.path().join("a.txt");
+    let b = tmp_dir.path().join("b.txt");
+    fs::write(&a, "foo a\n").unwrap();
+    fs::write(&b, "foo b\n").unwrap();
+    let files = vec![a.display().to_string(), b.display().to_string()];
+
+    let mut app = App::new();
+    app.from_input = "foo".to_string();
+    app.to_input = "bar".to_string();
+    app.pipe_command = Some("cat".to_string());
+
+    app.handle_key_event(KeyEvent::new(KeyCode::Char('x'), KeyModifiers::ALT), &files)
+        .unwrap();
+    assert_eq!(app.pipe_output.as_deref(), Some("bar a\n"));
+
+    app.handle_key_event(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE), &files)
+        .unwrap();
+    assert_eq!(app.pipe_output, None);
+
+    app.handle_key_event(
+        KeyEvent::new(KeyCode::Char('x'), KeyModifiers::CONTROL),
+        &files,
+    )
+    .unwrap();
+    assert_eq!(app.pipe_output.as_deref(), Some("bar a\nbar b\n"));
+    assert_eq!(fs::read_to_string(&a).unwrap(), "foo a\n");
+}
+
+#[test]
+fn test_pipe_flag() {
+    let args = Args::parse(["--pipe".to_string(), "wc -l".to_string()]).unwrap();
+    assert_eq!(args.pipe.as_deref(), Some("wc -l"));
+    assert!(Args::parse(["--pipe".to_string()]).is_err());
+}
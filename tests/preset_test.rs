@@ -0,0 +1,82 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::App;
+use ised::config::Config;
+
+fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+    KeyEvent::new(code, modifiers)
+}
+
+fn app_with_presets() -> App {
+    let config: Config = toml::from_str(
+        r#"
+        [[presets]]
+        name = "first"
+        from = "alpha"
+        to = "beta"
+
+        [[presets]]
+        name = "second"
+        from = "fn (\\w+)"
+        to = "pub fn $1"
+        filter = "*.rs"
+    "#,
+    )
+    .unwrap();
+    App::with_config(Some(config))
+}
+
+#[test]
+fn test_selecting_preset_populates_fields() {
+    let mut app = app_with_presets();
+
+    app.handle_key_event(key(KeyCode::Char('p'), KeyModifiers::CONTROL), &[])
+        .unwrap();
+    assert_eq!(app.preset_picker, Some(0));
+
+    app.handle_key_event(key(KeyCode::Down, KeyModifiers::NONE), &[])
+        .unwrap();
+    app.handle_key_event(key(KeyCode::Enter, KeyModifiers::NONE), &[])
+        .unwrap();
+
+    assert_eq!(app.preset_picker, None);
+    assert_eq!(app.from_input, "fn (\\w+)");
+    assert_eq!(app.from_cursor, app.from_input.chars().count());
+    assert_eq!(app.to_input, "pub fn $1");
+    assert_eq!(app.filter_input, "*.rs");
+}
+
+#[test]
+fn test_preset_without_filter_keeps_glob() {
+    let mut app = app_with_presets();
+    app.filter_input = "src/**".to_string();
+
+    app.load_preset(0);
+
+    assert_eq!(app.from_input, "alpha");
+    assert_eq!(app.to_input, "beta");
+    assert_eq!(app.filter_input, "src/**");
+}
+
+#[test]
+fn test_escape_closes_picker_without_loading() {
+    let mut app = app_with_presets();
+
+    app.handle_key_event(key(KeyCode::Char('p'), KeyModifiers::CONTROL), &[])
+        .unwrap();
+    app.handle_key_event(key(KeyCode::Esc, KeyModifiers::NONE), &[])
+        .unwrap();
+
+    assert_eq!(app.preset_picker, None);
+    assert!(app.from_input.is_empty());
+}
+
+#[test]
+fn test_picker_does_not_open_without_presets() {
+    let mut app = App::with_config(None);
+
+    app.handle_key_event(key(KeyCode::Char('p'), KeyModifiers::CONTROL), &[])
+        .unwrap();
+
+    assert_eq!(app.preset_picker, None);
+    assert!(app.status_message.is_some());
+}
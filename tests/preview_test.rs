@@ -0,0 +1,56 @@
+mod common;
+
+use common::render_rows;
+use ised::app::App;
+use ised::config::Config;
+use ised::utils::read_preview;
+use std::fs;
+use tempdir::TempDir;
+
+fn write_fixture(dir: &TempDir, name: &str, content: &str) -> String {
+    let path = dir.path().join(name);
+    fs::write(&path, content).unwrap();
+    path.display().to_string()
+}
+
+#[test]
+fn test_small_files_are_read_fully() {
+    let tmp_dir = TempDir::new("ised_test_preview_small").unwrap();
+    let path = write_fixture(&tmp_dir, "a.txt", "one\ntwo\n");
+
+    let preview = read_preview(&path, 1024).unwrap();
+
+    assert_eq!(preview.content, "one\ntwo\n");
+    assert!(!preview.truncated);
+}
+
+#[test]
+fn test_oversized_files_are_truncated_at_a_line_boundary() {
+    let tmp_dir = TempDir::new("ised_test_preview_large").unwrap();
+    let path = write_fixture(&tmp_dir, "a.txt", "first\nsecond\nthird\n");
+
+    let preview = read_preview(&path, 10).unwrap();
+
+    assert_eq!(preview.content, "first\n");
+    assert!(preview.truncated);
+}
+
+#[test]
+fn test_truncated_preview_is_marked_in_diff() {
+    let tmp_dir = TempDir::new("ised_test_preview_marker").unwrap();
+    let path = write_fixture(&tmp_dir, "a.txt", &"foo line\n".repeat(400));
+
+    let config: Config = toml::from_str("[behavior]\npreview_kb = 1").unwrap();
+    let mut app = App::with_config(Some(config));
+    app.is_loading = false;
+    app.from_input = "foo".to_string();
+    app.to_input = "bar".to_string();
+    app.diff_scroll = 220;
+    let files = vec![path.clone()];
+    let preview = read_preview(&path, app.preview_limit).unwrap();
+    assert!(preview.truncated);
+
+    let rendered = render_rows(&mut app, &files, Some(preview), 120, 30).concat();
+    assert!(rendered.contains("preview truncated at 1 KB"));
+    assert!(rendered.contains("truncated preview"));
+}
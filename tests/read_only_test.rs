@@ -0,0 +1,55 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::{App, ConfirmState};
+use ised::cli::Args;
+use ised::report::Outcome;
+use std::fs;
+use tempdir::TempDir;
+
+fn press(app: &mut App, code: KeyCode, modifiers: KeyModifiers, files: &[String]) {
+    app.handle_key_event(KeyEvent::new(code, modifiers), files)
+        .unwrap();
+}
+
+#[test]
+fn test_read_only_mode_never_writes() {
+    let tmp_dir = TempDir::new("ised_test_read_only").unwrap();
+    let file = tmp_dir.path().join("a.txt");
+    fs::write(&file, "foo\n").unwrap();
+    let path = file.display().to_string();
+
+    let mut app = App::new();
+    app.read_only = true;
+    app.files = vec![path.clone()];
+    app.from_input = "foo".to_string();
+    app.to_input = "bar".to_string();
+    let files = app.filter_files();
+    assert_eq!(files, vec![path.clone()]);
+
+    press(&mut app, KeyCode::Enter, KeyModifiers::NONE, &files);
+    assert!(matches!(app.confirm, ConfirmState::None));
+    assert_eq!(
+        app.status_message.as_deref(),
+        Some("Read-only mode: files are never written")
+    );
+
+    app.status_message = None;
+    press(&mut app, KeyCode::Char('a'), KeyModifiers::CONTROL, &files);
+    assert!(matches!(app.confirm, ConfirmState::None));
+    assert!(app.status_message.is_some());
+
+    press(&mut app, KeyCode::Char('d'), KeyModifiers::ALT, &files);
+    press(&mut app, KeyCode::Char('s'), KeyModifiers::CONTROL, &files);
+    assert!(app.export_prompt.is_none());
+
+    let report = app.apply_all(&files);
+    assert_eq!(report.files[0].outcome, Outcome::Error);
+
+    assert_eq!(fs::read_to_string(&file).unwrap(), "foo\n");
+    assert_eq!(fs::read_dir(tmp_dir.path()).unwrap().count(), 1);
+}
+
+#[test]
+fn test_read_only_flag() {
+    assert!(Args::parse(["--read-only".to_string()]).unwrap().read_only);
+    assert!(!Args::parse([]).unwrap().read_only);
+}
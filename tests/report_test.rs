@@ -0,0 +1,169 @@
+use ised::app::App;
+use std::fs;
+use tempdir::TempDir;
+
+#[test]
+fn test_apply_all_report_json_structure() {
+    let tmp_dir = TempDir::new("ised_test_report").unwrap();
+    let changed = tmp_dir.path().join("changed.txt");
+    let unchanged = tmp_dir.path().join("unchanged.txt");
+    let missing = tmp_dir.path().join("missing.txt");
+    fs::write(&changed, "foo").unwrap();
+    fs::write(&unchanged, "bar").unwrap();
+
+    let mut app = App::new();
+    app.from_input = "foo".to_string();
+    app.to_input = "baz".to_string();
+
+    let paths = vec![
+        changed.display().to_string(),
+        unchanged.display().to_string(),
+        missing.display().to_string(),
+    ];
+    let report = app.apply_all(&paths);
+
+    let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+    assert_eq!(json["changed"], 1);
+    assert_eq!(json["unchanged"], 1);
+    assert_eq!(json["errors"], 1);
+
+    let files = json["files"].as_array().unwrap();
+    assert_eq!(files.len(), 3);
+    assert_eq!(files[0]["path"], paths[0].as_str());
+    assert_eq!(files[0]["outcome"], "changed");
+    assert_eq!(files[1]["outcome"], "unchanged");
+    assert_eq!(files[2]["outcome"], "error");
+    assert!(files[2]["error"].is_string());
+    assert!(files[0].get("error").is_none());
+
+    assert_eq!(fs::read_to_string(&changed).unwrap(), "baz");
+}
+
+#[test]
+fn test_report_written_to_file() {
+    let tmp_dir = TempDir::new("ised_test_report_file").unwrap();
+    let target = tmp_dir.path().join("a.txt");
+    let report_path = tmp_dir.path().join("report.json");
+    fs::write(&target, "foo").unwrap();
+
+    let mut app = App::new();
+    app.from_input = "foo".to_string();
+    app.to_input = "bar".to_string();
+
+    let report = app.apply_all(&[target.display().to_string()]);
+    report.write_json(&report_path).unwrap();
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&report_path).unwrap()).unwrap();
+    assert_eq!(json["changed"], 1);
+}
+
+#[test]
+fn test_report_lists_changed_lines() {
+    let tmp_dir = TempDir::new("ised_test_report_lines").unwrap();
+    let target = tmp_dir.path().join("a.txt");
+    fs::write(&target, "foo\nbar\nbaz foo\nqux\nfoo\n").unwrap();
+
+    let mut app = App::new();
+    app.from_input = "foo".to_string();
+    app.to_input = "FOO".to_string();
+
+    let path = target.display().to_string();
+    let report = app.apply_all(std::slice::from_ref(&path));
+
+    assert_eq!(report.files[0].lines, vec![1, 3, 5]);
+    assert_eq!(report.line_log(), Some(format!("{} L1,L3,L5", path)));
+
+    let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+    assert_eq!(json["files"][0]["lines"], serde_json::json!([1, 3, 5]));
+}
+
+#[test]
+fn test_changed_lines_with_added_and_removed_lines() {
+    use ised::utils::changed_lines;
+
+    assert_eq!(changed_lines("a\nb\nc", "a\nB\nc"), vec![2]);
+    assert_eq!(changed_lines("a\nb", "a\nb\nc"), vec![3]);
+    assert_eq!(changed_lines("a\nb\nc", "a"), vec![2, 3]);
+    assert!(changed_lines("same", "same").is_empty());
+}
+
+#[test]
+fn test_lines_come_from_match_spans() {
+    use ised::report::format_lines;
+    use ised::utils::{substitution_lines, SubstitutionOptions};
+    use regex::Regex;
+
+    let options = SubstitutionOptions::default();
+    let content = "a\nfoo\nb\nfoo bar\n";
+    let lines = |pattern: &str, to: &str, options: &SubstitutionOptions| {
+        substitution_lines(content, &Regex::new(pattern).unwrap(), to, options)
+    };
+
+    assert_eq!(lines("foo", "baz", &options), vec![2, 4]);
+    assert_eq!(lines("o+", "oo", &options), Vec::<usize>::new());
+    assert_eq!(lines("b\nfoo", "x", &options), vec![3, 4]);
+    assert_eq!(lines("foo\n", "", &options), vec![2]);
+    assert_eq!(lines("(?m)^foo", "x", &options), vec![2, 4]);
+
+    let keep_indent = SubstitutionOptions {
+        keep_indent: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        substitution_lines(
+            "x\n    let a = 1;\n",
+            &Regex::new("^let").unwrap(),
+            "const",
+            &keep_indent
+        ),
+        vec![2]
+    );
+
+    assert_eq!(format_lines(&[2, 4]).as_deref(), Some("L2,L4"));
+    assert_eq!(format_lines(&[]), None);
+}
+
+#[test]
+fn test_batch_apply_only_writes_files_in_the_content_filtered_list() {
+    let tmp_dir = TempDir::new("ised_test_report_scoped").unwrap();
+    let kept = tmp_dir.path().join("kept.rs");
+    let skipped = tmp_dir.path().join("skipped.rs");
+    fs::write(&kept, "// TODO\nfoo\n").unwrap();
+    fs::write(&skipped, "foo\n").unwrap();
+
+    let mut app = App::new();
+    app.files = vec![kept.display().to_string(), skipped.display().to_string()];
+    app.is_loading = false;
+    app.from_input = "foo".to_string();
+    app.to_input = "bar".to_string();
+    app.content_filter_input = "TODO".to_string();
+
+    let filtered = app.filter_files();
+    assert_eq!(filtered, vec![kept.display().to_string()]);
+    let report = app.apply_all(&app.batch_files(&filtered));
+
+    assert_eq!(report.line_log(), Some(format!("{} L2", kept.display())));
+    assert_eq!(fs::read_to_string(&kept).unwrap(), "// TODO\nbar\n");
+    assert_eq!(fs::read_to_string(&skipped).unwrap(), "foo\n");
+}
+
+#[test]
+fn test_changed_and_all_paths() {
+    let tmp_dir = TempDir::new("ised_test_report_paths").unwrap();
+    let a = tmp_dir.path().join("a.txt");
+    let b = tmp_dir.path().join("b.txt");
+    fs::write(&a, "foo").unwrap();
+    fs::write(&b, "foo").unwrap();
+
+    let mut app = App::new();
+    app.from_input = "fo+".to_string();
+    app.to_input = "foo".to_string();
+    fs::write(&a, "fooo").unwrap();
+
+    let paths = vec![a.display().to_string(), b.display().to_string()];
+    let report = app.apply_all(&paths);
+
+    assert_eq!(report.changed_paths(), vec![paths[0].as_str()]);
+    assert_eq!(report.paths(), vec![paths[0].as_str(), paths[1].as_str()]);
+}
@@ -0,0 +1,57 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::App;
+use std::fs;
+use tempdir::TempDir;
+
+#[tokio::test]
+async fn test_rescan_picks_up_new_files_and_keeps_selection() {
+    let tmp_dir = TempDir::new("ised_test_rescan").unwrap();
+    let path = |name: &str| tmp_dir.path().join(name).display().to_string();
+    fs::write(path("b.txt"), "b").unwrap();
+
+    let mut app = App::with_config(None);
+    app.set_root(tmp_dir.path());
+    app.load_files().await;
+    assert_eq!(app.files, vec![path("b.txt")]);
+
+    fs::write(path("a.txt"), "a").unwrap();
+    let mut filtered_files = app.filter_files();
+    filtered_files.sort();
+    app.files = filtered_files.clone();
+
+    app.handle_key_event(
+        KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE),
+        &filtered_files,
+    )
+    .unwrap();
+    assert!(app.rescan_requested);
+    assert!(app.is_loading);
+
+    let mut scanner = app.clone();
+    scanner.load_files().await;
+    let mut files = scanner.files;
+    files.sort();
+    app.set_loaded_files(files);
+
+    assert!(!app.is_loading);
+    assert_eq!(app.filter_files(), vec![path("a.txt"), path("b.txt")]);
+    assert_eq!(app.selected, 1);
+}
+
+#[test]
+fn test_shrinking_list_clamps_selection() {
+    let mut app = App::with_config(None);
+    let files: Vec<String> = (0..5).map(|i| format!("{}.txt", i)).collect();
+    app.selected = 4;
+    app.offset = 3;
+
+    let shrunk = files[..2].to_vec();
+    app.clamp_selection(&shrunk);
+    assert_eq!(app.selected, 1);
+    assert_eq!(app.offset, 1);
+    assert_eq!(app.selected_file(&shrunk), Some("1.txt".to_string()));
+
+    app.clamp_selection(&[]);
+    assert_eq!(app.selected, 0);
+    assert_eq!(app.offset, 0);
+}
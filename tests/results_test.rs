@@ -0,0 +1,96 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::{App, Focus};
+use ised::results::match_results;
+use regex::Regex;
+use std::fs;
+use tempdir::TempDir;
+
+fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+    KeyEvent::new(code, modifiers)
+}
+
+#[test]
+fn test_match_results_with_context() {
+    let content = "one\ntwo foo\nthree\nfour\nfive foo foo\n";
+    let re = Regex::new("foo").unwrap();
+
+    let results = match_results("a.txt", content, &re, 1);
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].path, "a.txt");
+    assert_eq!(results[0].line, 1);
+    assert_eq!(
+        results[0].context,
+        vec![
+            (0, "one".to_string()),
+            (1, "two foo".to_string()),
+            (2, "three".to_string()),
+        ]
+    );
+    assert_eq!(results[1].line, 4);
+    assert_eq!(
+        results[1].context,
+        vec![(3, "four".to_string()), (4, "five foo foo".to_string())]
+    );
+}
+
+#[test]
+fn test_collects_results_across_files_and_jumps() {
+    let tmp_dir = TempDir::new("ised_test_results").unwrap();
+    let first = tmp_dir.path().join("first.txt");
+    let second = tmp_dir.path().join("second.txt");
+    let none = tmp_dir.path().join("none.txt");
+    fs::write(&first, "foo\n").unwrap();
+    fs::write(&second, "a\nb\nc\nfoo\nfoo\n").unwrap();
+    fs::write(&none, "bar\n").unwrap();
+    let files = [&first, &none, &second]
+        .iter()
+        .map(|p| p.display().to_string())
+        .collect::<Vec<_>>();
+
+    let mut app = App::with_config(None);
+    app.files = files.clone();
+    app.from_input = "foo".to_string();
+    app.to_input = "baz".to_string();
+
+    let results = app.collect_results(&files);
+    assert_eq!(
+        results
+            .iter()
+            .map(|r| (r.path.as_str(), r.line))
+            .collect::<Vec<_>>(),
+        vec![
+            (files[0].as_str(), 0),
+            (files[2].as_str(), 3),
+            (files[2].as_str(), 4),
+        ]
+    );
+
+    app.handle_key_event(key(KeyCode::Char('e'), KeyModifiers::CONTROL), &files)
+        .unwrap();
+    let view = app.results_view.clone().unwrap();
+    assert_eq!(view.results, results);
+    assert_eq!(view.file_count(), 2);
+
+    app.handle_key_event(key(KeyCode::Char('j'), KeyModifiers::NONE), &files)
+        .unwrap();
+    app.handle_key_event(key(KeyCode::Char('j'), KeyModifiers::NONE), &files)
+        .unwrap();
+    app.handle_key_event(key(KeyCode::Enter, KeyModifiers::NONE), &files)
+        .unwrap();
+
+    assert!(app.results_view.is_none());
+    assert_eq!(app.selected, 2);
+    assert!(app.focus == Focus::DiffView);
+    assert_eq!(app.diff_scroll, 5);
+}
+
+#[test]
+fn test_results_need_a_from_pattern() {
+    let mut app = App::with_config(None);
+    app.handle_key_event(key(KeyCode::Char('e'), KeyModifiers::CONTROL), &[])
+        .unwrap();
+
+    assert!(app.results_view.is_none());
+    assert!(app.status_message.is_some());
+}
@@ -0,0 +1,128 @@
+mod common;
+
+use common::{preview, render_rows};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::{App, ConfirmState, Focus, LayoutMode};
+use ised::config::Config;
+use ised::script::parse_script;
+
+fn render(app: &mut App, files: &[String]) -> Vec<String> {
+    render_rows(app, files, Some(preview("foo\n")), 60, 12)
+}
+
+fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+    KeyEvent::new(code, modifiers)
+}
+
+#[test]
+fn test_review_layout_gives_the_diff_the_full_area() {
+    let files = vec!["a.txt".to_string(), "b.txt".to_string()];
+    let mut app = App::with_config(None);
+    app.is_loading = false;
+    app.from_input = "foo".to_string();
+    app.to_input = "bar".to_string();
+
+    let normal = render(&mut app, &files);
+    assert!(normal[0].contains("File [L]ist"));
+
+    app.handle_key_event(key(KeyCode::Char('z'), KeyModifiers::ALT), &files)
+        .unwrap();
+    assert_eq!(app.layout, LayoutMode::Review);
+
+    let rows = render(&mut app, &files);
+    assert!(rows[0].starts_with("┌[D]iff (a.txt 1/2"));
+    assert!(rows[0].ends_with('┐'));
+    assert!(rows[11].starts_with('└'));
+    assert!(rows[11].ends_with('┘'));
+    assert!(rows.iter().all(|row| !row.contains("[F]rom")));
+    assert_eq!(
+        rows[1].trim_end(),
+        "│- foo                                                     │"
+    );
+    assert_eq!(
+        rows[2].trim_end(),
+        "│+ bar                                                     │"
+    );
+
+    app.handle_key_event(key(KeyCode::Esc, KeyModifiers::NONE), &files)
+        .unwrap();
+    assert_eq!(app.layout, LayoutMode::Normal);
+    assert!(render(&mut app, &files)[0].contains("File [L]ist"));
+}
+
+#[test]
+fn test_review_keys_move_files_and_apply() {
+    let files = vec!["a.txt".to_string(), "b.txt".to_string()];
+    let mut app = App::with_config(None);
+    app.from_input = "foo".to_string();
+    app.layout = LayoutMode::Review;
+
+    let plain = |c| key(KeyCode::Char(c), KeyModifiers::NONE);
+    app.handle_key_event(plain('j'), &files).unwrap();
+    assert_eq!(app.selected, 1);
+    app.handle_key_event(plain('j'), &files).unwrap();
+    assert_eq!(app.selected, 1);
+    app.handle_key_event(plain('k'), &files).unwrap();
+    assert_eq!(app.selected, 0);
+
+    app.handle_key_event(plain('x'), &files).unwrap();
+    assert_eq!(app.from_input, "foo");
+
+    app.handle_key_event(key(KeyCode::Enter, KeyModifiers::NONE), &files)
+        .unwrap();
+    assert!(matches!(&app.confirm, ConfirmState::Confirming(path) if path == "a.txt"));
+    app.handle_key_event(plain('n'), &files).unwrap();
+    assert!(matches!(app.confirm, ConfirmState::None));
+    assert_eq!(app.layout, LayoutMode::Review);
+}
+
+#[test]
+fn test_review_layout_draws_batch_confirm_and_preset_picker() {
+    let files = vec!["a.txt".to_string(), "b.txt".to_string()];
+    let config: Config = toml::from_str(
+        r#"
+        [[presets]]
+        name = "first"
+        from = "alpha"
+        to = "beta"
+    "#,
+    )
+    .unwrap();
+    let mut app = App::with_config(Some(config));
+    app.is_loading = false;
+    app.from_input = "foo".to_string();
+    app.focus = Focus::FileList;
+    app.layout = LayoutMode::Review;
+
+    app.handle_key_event(key(KeyCode::Char('a'), KeyModifiers::CONTROL), &files)
+        .unwrap();
+    assert!(matches!(app.confirm, ConfirmState::ConfirmingAll(_)));
+    assert!(render(&mut app, &files)
+        .iter()
+        .any(|row| row.contains("No files will be modified")));
+    app.handle_key_event(key(KeyCode::Char('n'), KeyModifiers::NONE), &files)
+        .unwrap();
+
+    app.handle_key_event(key(KeyCode::Char('p'), KeyModifiers::CONTROL), &files)
+        .unwrap();
+    assert_eq!(app.preset_picker, Some(0));
+    let rows = render(&mut app, &files);
+    assert!(rows.iter().any(|row| row.contains("Presets")));
+    assert!(rows.iter().any(|row| row.contains("first")));
+}
+
+#[test]
+fn test_review_layout_draws_rules_view() {
+    let files = vec!["a.txt".to_string()];
+    let mut app = App::with_config(None);
+    app.is_loading = false;
+    app.rules = parse_script("s/cat/dog/g").unwrap();
+    app.layout = LayoutMode::Review;
+
+    app.handle_key_event(key(KeyCode::Char('u'), KeyModifiers::CONTROL), &files)
+        .unwrap();
+    assert_eq!(app.rules_view, Some(0));
+    assert!(render(&mut app, &files)
+        .iter()
+        .any(|row| row.contains("1. s/cat/dog/g")));
+}
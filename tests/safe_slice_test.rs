@@ -0,0 +1,33 @@
+use ised::utils::safe_slice_chars;
+
+#[test]
+fn test_slices_ascii() {
+    assert_eq!(safe_slice_chars("hello", 1, 4), "ell");
+    assert_eq!(safe_slice_chars("hello", 0, 5), "hello");
+    assert_eq!(safe_slice_chars("", 0, 3), "");
+}
+
+#[test]
+fn test_slices_on_multibyte_boundaries() {
+    let text = "aé日本🦀z";
+    assert_eq!(safe_slice_chars(text, 1, 2), "é");
+    assert_eq!(safe_slice_chars(text, 2, 4), "日本");
+    assert_eq!(safe_slice_chars(text, 4, 5), "🦀");
+    assert_eq!(safe_slice_chars(text, 3, 6), "本🦀z");
+}
+
+#[test]
+fn test_start_beyond_length_is_empty() {
+    assert_eq!(safe_slice_chars("日本", 2, 5), "");
+    assert_eq!(safe_slice_chars("日本", 10, 20), "");
+}
+
+#[test]
+fn test_end_beyond_length_stops_at_end() {
+    assert_eq!(safe_slice_chars("日本語", 1, 100), "本語");
+}
+
+#[test]
+fn test_end_before_start_is_empty() {
+    assert_eq!(safe_slice_chars("日本語", 2, 1), "");
+}
@@ -0,0 +1,60 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::App;
+
+fn alt(app: &mut App, c: char) {
+    app.handle_key_event(KeyEvent::new(KeyCode::Char(c), KeyModifiers::ALT), &[])
+        .unwrap();
+}
+
+#[test]
+fn test_save_and_restore_preserves_inputs_and_cursors() {
+    let mut app = App::with_config(None);
+    app.filter_input = "*.rs".to_string();
+    app.filter_cursor = 2;
+    app.from_input = "foo(\\d+)".to_string();
+    app.from_cursor = 3;
+    app.to_input = "bar$1".to_string();
+    app.to_cursor = 5;
+    app.options.literal = true;
+
+    alt(&mut app, 'b');
+    assert_eq!(app.saved_states.len(), 1);
+
+    app.filter_input = "*.md".to_string();
+    app.filter_cursor = 4;
+    app.from_input = "other".to_string();
+    app.from_cursor = 0;
+    app.to_input.clear();
+    app.to_cursor = 0;
+    app.options.literal = false;
+
+    alt(&mut app, 'r');
+
+    assert!(app.saved_states.is_empty());
+    assert_eq!(app.filter_input, "*.rs");
+    assert_eq!(app.filter_cursor, 2);
+    assert_eq!(app.from_input, "foo(\\d+)");
+    assert_eq!(app.from_cursor, 3);
+    assert_eq!(app.to_input, "bar$1");
+    assert_eq!(app.to_cursor, 5);
+    assert!(app.options.literal);
+}
+
+#[test]
+fn test_restore_pops_most_recent_state() {
+    let mut app = App::with_config(None);
+    app.from_input = "first".to_string();
+    app.save_state();
+    app.from_input = "second".to_string();
+    app.save_state();
+    app.from_input = "third".to_string();
+
+    app.restore_state();
+    assert_eq!(app.from_input, "second");
+    app.restore_state();
+    assert_eq!(app.from_input, "first");
+
+    app.restore_state();
+    assert_eq!(app.from_input, "first");
+    assert_eq!(app.status_message.as_deref(), Some("No saved states"));
+}
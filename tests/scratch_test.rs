@@ -0,0 +1,62 @@
+mod common;
+
+use common::render_rows;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::App;
+
+fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+    KeyEvent::new(code, modifiers)
+}
+
+#[test]
+fn test_scratch_substitution_uses_from_and_to() {
+    let files: Vec<String> = Vec::new();
+    let mut app = App::new();
+    app.from_input = r"(\w+)@(\w+)".to_string();
+    app.to_input = "$2 at $1".to_string();
+    assert_eq!(app.scratch_result(), None);
+
+    app.handle_key_event(key(KeyCode::Char('y'), KeyModifiers::CONTROL), &files)
+        .unwrap();
+    for c in "me@home".chars() {
+        app.handle_key_event(key(KeyCode::Char(c), KeyModifiers::NONE), &files)
+            .unwrap();
+    }
+    app.handle_key_event(key(KeyCode::Enter, KeyModifiers::NONE), &files)
+        .unwrap();
+    for c in "you@work!".chars() {
+        app.handle_key_event(key(KeyCode::Char(c), KeyModifiers::NONE), &files)
+            .unwrap();
+    }
+    app.handle_key_event(key(KeyCode::Backspace, KeyModifiers::NONE), &files)
+        .unwrap();
+
+    assert_eq!(app.scratch.as_deref(), Some("me@home\nyou@work"));
+    assert_eq!(
+        app.scratch_result().as_deref(),
+        Some("home at me\nwork at you")
+    );
+    assert_eq!(app.from_input, r"(\w+)@(\w+)");
+
+    app.from_input.clear();
+    assert_eq!(app.scratch_result().as_deref(), Some("me@home\nyou@work"));
+
+    app.handle_key_event(key(KeyCode::Esc, KeyModifiers::NONE), &files)
+        .unwrap();
+    assert_eq!(app.scratch, None);
+}
+
+#[test]
+fn test_scratch_popup_shows_input_and_result() {
+    let files: Vec<String> = Vec::new();
+    let mut app = App::with_config(None);
+    app.is_loading = false;
+    app.from_input = "cat".to_string();
+    app.to_input = "dog".to_string();
+    app.scratch = Some("a cat sat".to_string());
+
+    let rows = render_rows(&mut app, &files, None, 80, 24);
+
+    assert!(rows.iter().any(|row| row.contains("a cat sat█")));
+    assert!(rows.iter().any(|row| row.contains("a dog sat")));
+}
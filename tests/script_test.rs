@@ -0,0 +1,192 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::App;
+use ised::cli::Args;
+use ised::script::{parse_script, Rule, Scope};
+use std::fs;
+use tempdir::TempDir;
+
+#[test]
+fn test_parses_multiple_rules_with_flags() {
+    let rules = parse_script(
+        r#"
+        # rename helpers
+        s/foo/bar/g
+        s|Hello|Bye|I
+
+        s#a(\d)#b\1#
+    "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        rules,
+        vec![
+            Rule {
+                from: "foo".to_string(),
+                to: "bar".to_string(),
+                global: true,
+                case_insensitive: false,
+                scope: None,
+            },
+            Rule {
+                from: "Hello".to_string(),
+                to: "Bye".to_string(),
+                global: false,
+                case_insensitive: true,
+                scope: None,
+            },
+            Rule {
+                from: "a(\\d)".to_string(),
+                to: "b${1}".to_string(),
+                global: false,
+                case_insensitive: false,
+                scope: None,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_escaped_delimiters() {
+    let rules = parse_script(r"s/src\/old/src\/new/g").unwrap();
+    assert_eq!(rules[0].from, "src/old");
+    assert_eq!(rules[0].to, "src/new");
+    assert_eq!(rules[0].apply("src/old/x"), "src/new/x");
+
+    let rules = parse_script(r"s|a\|b|[&]|g").unwrap();
+    assert_eq!(rules[0].apply("a|b ab"), "[a|b] ab");
+}
+
+#[test]
+fn test_global_flag_and_case_insensitive_flag() {
+    let rules = parse_script("s/o/0/\ns/HELLO/hi/gi").unwrap();
+
+    assert_eq!(rules[0].apply("foo\nboo"), "f0o\nb0o");
+    assert_eq!(rules[1].apply("hello Hello"), "hi hi");
+}
+
+#[test]
+fn test_parse_errors_report_line_numbers() {
+    assert_eq!(
+        parse_script("s/a/b/\ny/a/b/").unwrap_err(),
+        "line 2: expected s/from/to/flags"
+    );
+    assert_eq!(
+        parse_script("s/a/b").unwrap_err(),
+        "line 1: unterminated substitution"
+    );
+    assert_eq!(
+        parse_script("s/a/b/x").unwrap_err(),
+        "line 1: unknown flag 'x'"
+    );
+}
+
+#[test]
+fn test_rules_apply_sequentially() {
+    let tmp_dir = TempDir::new("ised_test_script").unwrap();
+    let path = tmp_dir.path().join("a.txt");
+    fs::write(&path, "cat dog").unwrap();
+    let path = path.display().to_string();
+
+    let mut app = App::with_config(None);
+    app.rules = parse_script("s/cat/dog/g\ns/dog/bird/g").unwrap();
+
+    assert_eq!(
+        app.affected_files(std::slice::from_ref(&path)),
+        vec![path.clone()]
+    );
+    let report = app.apply_all(std::slice::from_ref(&path));
+
+    assert_eq!(report.changed, 1);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "bird bird");
+}
+
+#[test]
+fn test_scoped_rules_only_touch_matching_files() {
+    let rules = parse_script("[*.rs] s|#|//|g\n[ *.py ] s|//|#|g\ns/x/y/g").unwrap();
+    assert_eq!(rules[0].scope.as_ref().map(Scope::glob), Some("*.rs"));
+    assert_eq!(rules[1].scope.as_ref().map(Scope::glob), Some("*.py"));
+    assert_eq!(rules[2].scope, None);
+    assert_eq!(
+        parse_script("[*.rs s/a/b/").unwrap_err(),
+        "line 1: unterminated [glob] scope"
+    );
+    assert!(parse_script("[a{b] s/a/b/").is_err());
+
+    let tmp_dir = TempDir::new("ised_test_scoped_rules").unwrap();
+    let rs = tmp_dir.path().join("lib.rs").display().to_string();
+    let py = tmp_dir.path().join("main.py").display().to_string();
+    let txt = tmp_dir.path().join("notes.txt").display().to_string();
+    for path in [&rs, &py, &txt] {
+        fs::write(path, "# x //").unwrap();
+    }
+
+    let mut app = App::with_config(None);
+    app.rules = rules;
+    assert_eq!(app.substitute("# x //", &rs), "// y //");
+    assert_eq!(app.substitute("# x //", &py), "# y #");
+    assert_eq!(app.substitute("# x //", &txt), "# y //");
+
+    app.rules.pop();
+    assert_eq!(
+        app.affected_files(&[rs.clone(), py.clone(), txt.clone()]),
+        vec![rs.clone(), py.clone()]
+    );
+    app.apply_all(&[rs.clone(), py.clone(), txt.clone()]);
+    assert_eq!(fs::read_to_string(&rs).unwrap(), "// x //");
+    assert_eq!(fs::read_to_string(&py).unwrap(), "# x #");
+    assert_eq!(fs::read_to_string(&txt).unwrap(), "# x //");
+}
+
+#[test]
+fn test_reordering_rules_changes_the_output() {
+    let mut app = App::with_config(None);
+    app.rules = parse_script("s/cat/dog/g\ns/dog/bird/g").unwrap();
+    assert_eq!(app.substitute("cat dog", "a.txt"), "bird bird");
+
+    let press = |app: &mut App, code: KeyCode| {
+        app.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE), &[])
+            .unwrap();
+    };
+    press(&mut app, KeyCode::Char('u'));
+    assert_eq!(app.rules_view, None);
+    app.handle_key_event(
+        KeyEvent::new(KeyCode::Char('u'), KeyModifiers::CONTROL),
+        &[],
+    )
+    .unwrap();
+    assert_eq!(app.rules_view, Some(0));
+
+    press(&mut app, KeyCode::Char('J'));
+    assert_eq!(app.rules_view, Some(1));
+    assert_eq!(app.rules[0].from, "dog");
+    assert_eq!(app.substitute("cat dog", "a.txt"), "dog bird");
+
+    press(&mut app, KeyCode::Char('J'));
+    assert_eq!(app.rules_view, Some(1));
+    press(&mut app, KeyCode::Char('K'));
+    assert_eq!(app.rules_view, Some(0));
+    assert_eq!(app.substitute("cat dog", "a.txt"), "bird bird");
+
+    press(&mut app, KeyCode::Esc);
+    assert_eq!(app.rules_view, None);
+}
+
+#[test]
+fn test_script_flag() {
+    let args = Args::parse(["--script".to_string(), "rules.sed".to_string()]).unwrap();
+    assert_eq!(args.script.unwrap().to_str(), Some("rules.sed"));
+
+    assert!(Args::parse(["--script".to_string()]).is_err());
+}
+
+#[test]
+fn test_scopes_ignore_a_leading_dot_slash() {
+    let rules = parse_script("[src/*.rs] s/a/b/\n[./docs/*.md] s/a/b/").unwrap();
+    assert!(rules[0].applies_to("./src/app.rs"));
+    assert!(rules[0].applies_to("src/app.rs"));
+    assert!(!rules[0].applies_to("./tests/app.rs"));
+    assert!(rules[1].applies_to("docs/a.md"));
+    assert!(rules[1].applies_to("./docs/a.md"));
+    assert_eq!(rules[1].scope.as_ref().map(Scope::glob), Some("docs/*.md"));
+}
@@ -0,0 +1,75 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::App;
+use ised::config::Config;
+use std::fs;
+use tempdir::TempDir;
+
+fn fixture(dir: &TempDir) -> Vec<String> {
+    let deep = (0..40)
+        .map(|i| {
+            if i == 30 {
+                "foo".to_string()
+            } else {
+                format!("line {}", i)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    [("a_deep.txt", deep.as_str()), ("b_top.txt", "foo\nrest")]
+        .iter()
+        .map(|(name, content)| {
+            let path = dir.path().join(name);
+            fs::write(&path, content).unwrap();
+            path.display().to_string()
+        })
+        .collect()
+}
+
+fn press(app: &mut App, code: KeyCode, modifiers: KeyModifiers, files: &[String]) {
+    app.handle_key_event(KeyEvent::new(code, modifiers), files)
+        .unwrap();
+    app.follow_selection(files);
+}
+
+#[test]
+fn test_selection_scrolls_diff_to_first_change() {
+    let tmp_dir = TempDir::new("ised_test_scroll_change").unwrap();
+    let config: Config =
+        toml::from_str("[display]\nscroll_to_first_change = true\nfull_diff = true").unwrap();
+    let mut app = App::with_config(Some(config));
+    app.files = fixture(&tmp_dir);
+    app.from_input = "foo".to_string();
+    app.to_input = "bar".to_string();
+
+    let files = app.filter_files();
+    app.follow_selection(&files);
+    assert_eq!(app.diff_scroll, 30);
+
+    app.diff_scroll = 5;
+    app.follow_selection(&files);
+    assert_eq!(app.diff_scroll, 5);
+
+    press(&mut app, KeyCode::Down, KeyModifiers::NONE, &files);
+    assert_eq!(app.diff_scroll, 0);
+    press(&mut app, KeyCode::Up, KeyModifiers::NONE, &files);
+    assert_eq!(app.diff_scroll, 30);
+}
+
+#[test]
+fn test_toggle_keeps_diff_at_the_top() {
+    let tmp_dir = TempDir::new("ised_test_scroll_toggle").unwrap();
+    let mut app = App::with_config(None);
+    app.full_diff = true;
+    assert!(!app.scroll_to_first_change);
+    app.files = fixture(&tmp_dir);
+    app.from_input = "foo".to_string();
+    app.to_input = "bar".to_string();
+
+    let files = app.filter_files();
+    app.follow_selection(&files);
+    assert_eq!(app.diff_scroll, 0);
+
+    press(&mut app, KeyCode::Char('f'), KeyModifiers::ALT, &files);
+    assert!(app.scroll_to_first_change);
+    assert_eq!(app.diff_scroll, 30);
+}
@@ -0,0 +1,65 @@
+use ised::app::App;
+use ised::watch::RecentWrites;
+use notify::event::{DataChange, ModifyKind};
+use notify::{Event, EventKind};
+use std::fs;
+use std::path::Path;
+use std::thread::sleep;
+use std::time::Duration;
+use tempdir::TempDir;
+
+fn modify_event(path: &Path) -> Event {
+    Event::new(EventKind::Modify(ModifyKind::Data(DataChange::Content))).add_path(path.into())
+}
+
+#[test]
+fn test_recent_writes_match_relative_and_absolute_paths() {
+    let writes = RecentWrites::default();
+    writes.record(Path::new("./some/file.txt"));
+
+    let absolute = std::env::current_dir().unwrap().join("some/file.txt");
+    assert!(writes.contains(&absolute));
+    assert!(writes.contains(Path::new("some/file.txt")));
+    assert!(!writes.contains(Path::new("some/other.txt")));
+}
+
+#[test]
+fn test_recent_writes_expire() {
+    let writes = RecentWrites::with_window(Duration::from_millis(50));
+    writes.record(Path::new("/tmp/a.txt"));
+    assert!(writes.contains(Path::new("/tmp/a.txt")));
+    sleep(Duration::from_millis(80));
+    assert!(!writes.contains(Path::new("/tmp/a.txt")));
+}
+
+#[test]
+fn test_only_events_for_unchanged_self_written_files_are_ignored() {
+    let tmp_dir = TempDir::new("ised_test_self_write").unwrap();
+    let own = tmp_dir.path().join("own.txt");
+    let other = tmp_dir.path().join("other.txt");
+    fs::write(&own, "foo").unwrap();
+    fs::write(&other, "foo").unwrap();
+
+    let mut app = App::new();
+    app.toggle_watcher();
+    app.files = vec![own.display().to_string(), other.display().to_string()];
+    app.from_input = "foo".to_string();
+    app.to_input = "bar".to_string();
+    assert_eq!(app.filter_files().len(), 2);
+
+    app.apply_all(&[own.display().to_string()]);
+    assert!(app.recent_writes().contains(&own));
+    assert_eq!(app.filter_files(), vec![other.display().to_string()]);
+
+    app.handle_watch_event(&modify_event(&own));
+    assert_eq!(app.filter_files(), vec![other.display().to_string()]);
+
+    fs::write(&own, "foo again").unwrap();
+    assert!(!app.recent_writes().contains(&own));
+    app.handle_watch_event(&modify_event(&own));
+    assert!(app.filter_files().contains(&own.display().to_string()));
+
+    fs::write(&other, "changed elsewhere").unwrap();
+    app.handle_watch_event(&modify_event(&other));
+    assert!(!app.filter_files().contains(&other.display().to_string()));
+}
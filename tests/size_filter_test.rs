@@ -0,0 +1,66 @@
+use ised::app::App;
+use ised::size::{parse_size, parse_size_filter, SizeRange};
+use std::fs;
+use tempdir::TempDir;
+
+#[test]
+fn test_parse_size() {
+    assert_eq!(parse_size("0"), Some(0));
+    assert_eq!(parse_size("512"), Some(512));
+    assert_eq!(parse_size("2k"), Some(2048));
+    assert_eq!(parse_size("1MB"), Some(1 << 20));
+    assert_eq!(parse_size("3g"), Some(3 << 30));
+    assert_eq!(parse_size("big"), None);
+}
+
+#[test]
+fn test_parse_size_filter() {
+    let range = |min, max| Some(SizeRange { min, max });
+    assert_eq!(parse_size_filter("size:0"), range(Some(0), Some(0)));
+    assert_eq!(
+        parse_size_filter("size:>1M"),
+        range(Some((1 << 20) + 1), None)
+    );
+    assert_eq!(parse_size_filter("size:>=1k"), range(Some(1024), None));
+    assert_eq!(parse_size_filter("size:<10"), range(None, Some(9)));
+    assert_eq!(
+        parse_size_filter("size:1k..2k"),
+        range(Some(1024), Some(2048))
+    );
+    assert_eq!(parse_size_filter("size:..5"), range(None, Some(5)));
+    assert_eq!(parse_size_filter("size:<0"), None);
+    assert_eq!(parse_size_filter("*.rs"), None);
+}
+
+#[tokio::test]
+async fn test_filters_files_by_size_range() {
+    let tmp_dir = TempDir::new("ised_test_size").unwrap();
+    let path = |name: &str| tmp_dir.path().join(name).display().to_string();
+    fs::write(path("empty.txt"), "").unwrap();
+    fs::write(path("small.txt"), "x".repeat(100)).unwrap();
+    fs::write(path("large.txt"), "x".repeat(5000)).unwrap();
+
+    let mut app = App::with_config(None);
+    app.set_root(tmp_dir.path());
+    app.load_files().await;
+
+    let filtered = |app: &mut App, filter: &str| {
+        app.filter_input = filter.to_string();
+        let mut files = app.filter_files();
+        files.sort();
+        files
+    };
+
+    assert_eq!(filtered(&mut app, "size:0"), vec![path("empty.txt")]);
+    assert_eq!(filtered(&mut app, "size:>1k"), vec![path("large.txt")]);
+    assert_eq!(filtered(&mut app, "size:1..4k"), vec![path("small.txt")]);
+    assert_eq!(
+        filtered(&mut app, "size:<1k, !**/empty*"),
+        vec![path("small.txt")]
+    );
+    assert_eq!(
+        filtered(&mut app, "*.txt, size:>0"),
+        vec![path("large.txt"), path("small.txt")]
+    );
+    assert_eq!(app.size_filters(), vec!["size:>0"]);
+}
@@ -0,0 +1,75 @@
+use ised::app::App;
+use ised::config::read_config;
+use ised::utils::{already_applied, SubstitutionOptions};
+use regex::Regex;
+use std::fs;
+use tempdir::TempDir;
+
+#[test]
+fn test_already_applied_finds_to_where_from_matches() {
+    let options = SubstitutionOptions::default();
+    let re = Regex::new("foo").unwrap();
+    assert!(already_applied("let foobar = 1;", &re, "foobar", &options));
+    assert!(!already_applied("let foo = 1;", &re, "foobar", &options));
+
+    let re = Regex::new("x").unwrap();
+    assert!(already_applied("call(yx)", &re, "yx", &options));
+    assert!(!already_applied("call(x)", &re, "yx", &options));
+
+    let re = Regex::new(r"(\w+)\.unwrap\(\)").unwrap();
+    assert!(already_applied(
+        "a.unwrap().expect()",
+        &re,
+        "$1.unwrap().expect()",
+        &options
+    ));
+
+    let re = Regex::new("old").unwrap();
+    assert!(!already_applied("old new", &re, "new", &options));
+    assert!(!already_applied("new", &re, "new", &options));
+}
+
+#[test]
+fn test_partially_applied_files_are_kept() {
+    let options = SubstitutionOptions::default();
+    let re = Regex::new("foo").unwrap();
+    assert!(already_applied("foo_v2 foo_v2", &re, "foo_v2", &options));
+    assert!(!already_applied("foo_v2 foo", &re, "foo_v2", &options));
+}
+
+#[test]
+fn test_skip_applied_leaves_out_transformed_files() {
+    let tmp_dir = TempDir::new("ised_test_skip_applied").unwrap();
+    let fresh = tmp_dir.path().join("fresh.rs");
+    let done = tmp_dir.path().join("done.rs");
+    fs::write(&fresh, "use foo;\n").unwrap();
+    fs::write(&done, "use foo_v2;\n").unwrap();
+    let paths = vec![fresh.display().to_string(), done.display().to_string()];
+
+    let mut app = App::new();
+    app.files = paths.clone();
+    app.only_matching = true;
+    app.from_input = "foo".to_string();
+    app.to_input = "foo_v2".to_string();
+    assert_eq!(app.filter_files(), paths);
+
+    app.skip_applied = true;
+    assert_eq!(app.filter_files(), vec![paths[0].clone()]);
+
+    app.to_input = "foo_v3".to_string();
+    assert_eq!(app.filter_files(), paths);
+}
+
+#[test]
+fn test_skip_applied_config() {
+    let tmp_dir = TempDir::new("ised_test_skip_applied").unwrap();
+    let path = tmp_dir.path().join("ised.config.toml");
+    fs::write(&path, "[behavior]\nskip_applied = true\n").unwrap();
+
+    let app = App::with_config(Some(read_config(&path).unwrap()));
+    assert!(app.skip_applied);
+    assert_eq!(
+        app.effective_config().behavior.and_then(|b| b.skip_applied),
+        Some(true)
+    );
+}
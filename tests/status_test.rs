@@ -0,0 +1,127 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::{App, ConfirmState, Focus, NoChangeReason};
+use std::fs;
+use tempdir::TempDir;
+
+fn fixture_app(dir: &TempDir) -> App {
+    let a = dir.path().join("a.txt");
+    let b = dir.path().join("b.txt");
+    fs::write(&a, "hello world").unwrap();
+    fs::write(&b, "goodbye world").unwrap();
+
+    let mut app = App::new();
+    app.files = vec![a.display().to_string(), b.display().to_string()];
+    app.is_loading = false;
+    app
+}
+
+#[test]
+fn test_reports_no_matching_files() {
+    let tmp_dir = TempDir::new("ised_test_no_match").unwrap();
+    let mut app = fixture_app(&tmp_dir);
+    app.from_input = "missing".to_string();
+    app.to_input = "found".to_string();
+
+    let filtered = app.filter_files();
+
+    assert_eq!(
+        app.no_change_reason(&filtered),
+        Some(NoChangeReason::NoMatchingFiles)
+    );
+}
+
+#[test]
+fn test_reports_no_effective_change() {
+    let tmp_dir = TempDir::new("ised_test_no_change").unwrap();
+    let mut app = fixture_app(&tmp_dir);
+    app.from_input = "world".to_string();
+    app.to_input = "world".to_string();
+
+    let filtered = app.filter_files();
+
+    assert_eq!(filtered.len(), 2);
+    assert_eq!(
+        app.no_change_reason(&filtered),
+        Some(NoChangeReason::NoEffectiveChange)
+    );
+}
+
+#[test]
+fn test_no_reason_when_substitution_changes_files() {
+    let tmp_dir = TempDir::new("ised_test_changes").unwrap();
+    let mut app = fixture_app(&tmp_dir);
+    app.from_input = "hello".to_string();
+    app.to_input = "hi".to_string();
+
+    let filtered = app.filter_files();
+
+    assert_eq!(app.no_change_reason(&filtered), None);
+}
+
+#[test]
+fn test_no_change_reason_follows_inputs_and_applied_files() {
+    let tmp_dir = TempDir::new("ised_test_no_change_cache").unwrap();
+    let mut app = fixture_app(&tmp_dir);
+    app.from_input = "world".to_string();
+    app.to_input = "world".to_string();
+    let filtered = app.filter_files();
+    assert_eq!(
+        app.no_change_reason(&filtered),
+        Some(NoChangeReason::NoEffectiveChange)
+    );
+
+    app.to_input = "there".to_string();
+    assert_eq!(app.no_change_reason(&filtered), None);
+
+    app.apply_all(&filtered);
+    assert_eq!(
+        app.no_change_reason(&filtered),
+        Some(NoChangeReason::NoMatchingFiles)
+    );
+}
+
+#[test]
+fn test_warns_when_from_matches_empty_string() {
+    let mut app = App::new();
+
+    app.from_input = "a*".to_string();
+    assert!(app.from_matches_empty());
+
+    app.from_input = "^".to_string();
+    assert!(app.from_matches_empty());
+
+    app.from_input = "a+".to_string();
+    assert!(!app.from_matches_empty());
+
+    app.from_input = String::new();
+    assert!(!app.from_matches_empty());
+}
+
+#[test]
+fn test_content_scan_times_out() {
+    let tmp_dir = TempDir::new("ised_test_scan_timeout").unwrap();
+    let mut app = fixture_app(&tmp_dir);
+    app.match_timeout = std::time::Duration::ZERO;
+    app.from_input = "world".to_string();
+
+    let files = app.filter_files();
+    assert!(files.is_empty());
+    assert!(app.scan_timed_out());
+
+    app.focus = Focus::FileList;
+    app.handle_key_event(
+        KeyEvent::new(KeyCode::Char('a'), KeyModifiers::CONTROL),
+        &files,
+    )
+    .unwrap();
+    assert!(matches!(app.confirm, ConfirmState::None));
+    assert!(app
+        .status_message
+        .as_deref()
+        .is_some_and(|message| message.contains("batch apply is disabled")));
+
+    app.match_timeout = std::time::Duration::from_secs(5);
+
+    assert_eq!(app.filter_files().len(), 2);
+    assert!(!app.scan_timed_out());
+}
@@ -0,0 +1,54 @@
+#![cfg(unix)]
+
+use ised::app::App;
+use ised::config::Config;
+use std::fs;
+use std::os::unix::fs::symlink;
+use tempdir::TempDir;
+
+fn fixture(dir: &TempDir) -> (String, String) {
+    let target = dir.path().join("target.txt");
+    let link = dir.path().join("link.txt");
+    fs::write(&target, "foo").unwrap();
+    symlink(&target, &link).unwrap();
+    (target.display().to_string(), link.display().to_string())
+}
+
+#[test]
+fn test_symlinks_are_not_written_by_default() {
+    let tmp_dir = TempDir::new("ised_test_symlink").unwrap();
+    let (target, link) = fixture(&tmp_dir);
+
+    let mut app = App::with_config(None);
+    app.from_input = "foo".to_string();
+    app.to_input = "bar".to_string();
+
+    let report = app.apply_all(std::slice::from_ref(&link));
+
+    assert_eq!(report.errors, 1);
+    assert_eq!(fs::read_to_string(&target).unwrap(), "foo");
+    assert!(fs::symlink_metadata(&link)
+        .unwrap()
+        .file_type()
+        .is_symlink());
+}
+
+#[test]
+fn test_follow_symlinks_writes_target() {
+    let tmp_dir = TempDir::new("ised_test_symlink_follow").unwrap();
+    let (target, link) = fixture(&tmp_dir);
+
+    let config: Config = toml::from_str("[behavior]\nfollow_symlinks = true").unwrap();
+    let mut app = App::with_config(Some(config));
+    app.from_input = "foo".to_string();
+    app.to_input = "bar".to_string();
+
+    let report = app.apply_all(std::slice::from_ref(&link));
+
+    assert_eq!(report.changed, 1);
+    assert_eq!(fs::read_to_string(&target).unwrap(), "bar");
+    assert!(fs::symlink_metadata(&link)
+        .unwrap()
+        .file_type()
+        .is_symlink());
+}
@@ -0,0 +1,38 @@
+mod common;
+
+use common::{preview, render_rows};
+use ised::app::App;
+use ised::config::Config;
+
+fn render_diff_rows(app: &mut App, content: &str) -> Vec<String> {
+    let files = vec!["a.txt".to_string()];
+    render_rows(app, &files, Some(preview(content)), 80, 20)[1..4]
+        .iter()
+        .map(|row| row.chars().skip(41).take(38).collect())
+        .collect()
+}
+
+#[test]
+fn test_tabs_expand_to_configured_width() {
+    let config: Config = toml::from_str("[display]\ntab_width = 8").unwrap();
+    let mut app = App::with_config(Some(config));
+    app.is_loading = false;
+
+    let rows = render_diff_rows(&mut app, "\tx\nab\ty");
+
+    assert_eq!(rows[0].trim_end(), "        x");
+    assert_eq!(rows[1].trim_end(), "ab      y");
+}
+
+#[test]
+fn test_tabs_in_changed_lines_align_after_marker() {
+    let mut app = App::with_config(None);
+    app.is_loading = false;
+    app.from_input = "old".to_string();
+    app.to_input = "new".to_string();
+
+    let rows = render_diff_rows(&mut app, "\told");
+
+    assert_eq!(rows[0].trim_end(), "-   old");
+    assert_eq!(rows[1].trim_end(), "+   new");
+}
@@ -0,0 +1,85 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::App;
+use ised::template::parse_template;
+use ised::utils::apply_substitution_partial;
+
+fn expand(template: &str, content: &str) -> String {
+    let (from, to) = parse_template(template).unwrap();
+    apply_substitution_partial(content, &from, &to)
+}
+
+#[test]
+fn test_parses_literals_and_placeholders() {
+    assert_eq!(
+        parse_template("get{name}()").unwrap(),
+        (r"get(\w+)\(\)".to_string(), "get${1}()".to_string())
+    );
+    assert_eq!(
+        parse_template(r"{get=>}{name:[A-Z]\w*}").unwrap(),
+        (r"get([A-Z]\w*)".to_string(), "${1}".to_string())
+    );
+    assert_eq!(
+        parse_template(r"{{{n:\d{2}}}}").unwrap(),
+        (r"\{(\d{2})\}".to_string(), "{${1}}".to_string())
+    );
+    assert_eq!(
+        parse_template(r"{key:(\w+)=(\w+)}, {rest}").unwrap(),
+        (
+            r"((\w+)=(\w+)), (\w+)".to_string(),
+            "${1}, ${4}".to_string()
+        )
+    );
+}
+
+#[test]
+fn test_literal_text_is_escaped_in_to() {
+    assert_eq!(
+        parse_template("${name}").unwrap(),
+        (r"\$(\w+)".to_string(), "$$${1}".to_string())
+    );
+    assert_eq!(expand("${name}", "$abc"), "$abc");
+    assert_eq!(expand(r"{a\b=>c\d}{n:\d}", r"a\b1"), r"c\d1");
+    assert_eq!(expand(r"{id}\n", r"x\n"), r"x\n");
+    assert_eq!(expand("{n:\\d}0", "70"), "70");
+}
+
+#[test]
+fn test_rejects_invalid_templates() {
+    assert!(parse_template("{name").is_err());
+    assert!(parse_template("name}").is_err());
+    assert!(parse_template("{a}{a}").is_err());
+    assert!(parse_template("{a b}").is_err());
+    assert!(parse_template("{n:(}").is_err());
+}
+
+#[test]
+fn test_template_renames_accessors() {
+    let content = "getFoo(); setFoo(1); getBar();";
+    assert_eq!(
+        expand("{get=>}{name:[A-Z]\\w*}()", content),
+        "Foo(); setFoo(1); Bar();"
+    );
+    assert_eq!(
+        expand("{set=>}{name:[A-Z]\\w*}{(=>=(}", content),
+        "getFoo(); Foo=(1); getBar();"
+    );
+}
+
+#[test]
+fn test_alt_e_expands_from_into_from_and_to() {
+    let mut app = App::new();
+    app.from_input = "{old=>new}_{id}".to_string();
+    app.options.literal = true;
+
+    app.handle_key_event(KeyEvent::new(KeyCode::Char('e'), KeyModifiers::ALT), &[])
+        .unwrap();
+    assert_eq!(app.from_input, r"old_(\w+)");
+    assert_eq!(app.to_input, "new_${1}");
+    assert!(!app.options.literal);
+
+    app.from_input = "{broken".to_string();
+    app.handle_key_event(KeyEvent::new(KeyCode::Char('e'), KeyModifiers::ALT), &[])
+        .unwrap();
+    assert_eq!(app.from_input, "{broken");
+    assert!(app.status_message.unwrap().starts_with("Template error"));
+}
@@ -0,0 +1,86 @@
+use ised::app::App;
+use ised::config::{Config, DEFAULT_DETECT_SAMPLE_BYTES};
+use ised::utils::is_text_file;
+use std::fs;
+use tempdir::TempDir;
+
+fn text_then_binary(text_len: usize, binary_len: usize) -> Vec<u8> {
+    let mut bytes = "text\n".repeat(text_len / 5).into_bytes();
+    bytes.extend(std::iter::repeat_n(0u8, binary_len));
+    bytes
+}
+
+#[test]
+fn test_detects_binary_in_the_middle_of_large_files() {
+    let tmp_dir = TempDir::new("ised_test_detect").unwrap();
+    let path = tmp_dir.path().join("mixed.bin");
+    fs::write(&path, text_then_binary(10_000, 10_000)).unwrap();
+
+    assert!(!is_text_file(&path, DEFAULT_DETECT_SAMPLE_BYTES));
+}
+
+#[test]
+fn test_sample_size_controls_detection() {
+    let tmp_dir = TempDir::new("ised_test_detect").unwrap();
+    let path = tmp_dir.path().join("mixed.bin");
+    fs::write(&path, text_then_binary(5_000, 1_000)).unwrap();
+
+    assert!(is_text_file(&path, 4096));
+    assert!(!is_text_file(&path, 8192));
+
+    let text = tmp_dir.path().join("plain.txt");
+    fs::write(&text, "text\n".repeat(10_000)).unwrap();
+    assert!(is_text_file(&text, 4096));
+}
+
+#[test]
+fn test_detect_sample_bytes_from_config() {
+    let config: Config = toml::from_str(
+        r#"
+        [files]
+        detect_sample_bytes = 8192
+    "#,
+    )
+    .unwrap();
+    assert_eq!(App::with_config(Some(config)).detect_sample_bytes, 8192);
+    assert_eq!(
+        App::with_config(None).detect_sample_bytes,
+        DEFAULT_DETECT_SAMPLE_BYTES
+    );
+}
+
+#[tokio::test]
+async fn test_configured_extensions_override_content_probing() {
+    let tmp_dir = TempDir::new("ised_test_detect").unwrap();
+    let forced_text = tmp_dir.path().join("data.pak");
+    let forced_binary = tmp_dir.path().join("notes.GEN");
+    let probed = tmp_dir.path().join("plain.txt");
+    fs::write(&forced_text, b"header\0with a nul\n").unwrap();
+    fs::write(&forced_binary, "looks like text\n").unwrap();
+    fs::write(&probed, "text\n").unwrap();
+
+    let config: Config = toml::from_str(
+        r#"
+        [files]
+        text_extensions = ["pak"]
+        binary_extensions = [".gen"]
+    "#,
+    )
+    .unwrap();
+    let mut app = App::with_config(Some(config));
+    assert!(app.is_text(&forced_text));
+    assert!(!app.is_text(&forced_binary));
+    assert!(app.is_text(&probed));
+
+    let paths = [&forced_text, &forced_binary, &probed]
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>();
+    app.staged_files = Some(paths.clone());
+    app.load_files().await;
+    assert_eq!(app.files, vec![paths[0].clone(), paths[2].clone()]);
+
+    let effective = app.effective_config().files.unwrap();
+    assert_eq!(effective.text_extensions, Some(vec!["pak".to_string()]));
+    assert_eq!(effective.binary_extensions, Some(vec!["gen".to_string()]));
+}
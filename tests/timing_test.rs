@@ -0,0 +1,46 @@
+use ised::app::App;
+use ised::cli::Args;
+use ised::config::Config;
+use ised::utils::format_duration;
+use std::fs;
+use std::time::Duration;
+use tempdir::TempDir;
+
+#[tokio::test]
+async fn test_load_and_filter_durations_are_recorded() {
+    let tmp_dir = TempDir::new("ised_test_timing").unwrap();
+    fs::write(tmp_dir.path().join("a.txt"), "foo").unwrap();
+    fs::write(tmp_dir.path().join("b.txt"), "bar").unwrap();
+
+    let config: Config = toml::from_str("[display]\ntiming = true").unwrap();
+    let mut app = App::with_config(Some(config));
+    app.set_root(tmp_dir.path());
+    assert!(app.show_timing);
+    assert_eq!(app.timing_status(), None);
+
+    app.load_files().await;
+    assert!(app.load_duration.is_some_and(|d| d > Duration::ZERO));
+    assert!(app.timing_status().unwrap().starts_with("Loaded in "));
+
+    app.from_input = "foo".to_string();
+    assert_eq!(app.filter_files().len(), 1);
+    assert!(app.filter_duration().is_some_and(|d| d > Duration::ZERO));
+    assert!(app.clone().filter_duration().is_some());
+    assert!(app.timing_status().unwrap().contains(", filtered in "));
+
+    app.show_timing = false;
+    assert_eq!(app.timing_status(), None);
+}
+
+#[test]
+fn test_format_duration() {
+    assert_eq!(format_duration(Duration::from_micros(250)), "250 µs");
+    assert_eq!(format_duration(Duration::from_millis(42)), "42 ms");
+    assert_eq!(format_duration(Duration::from_millis(1500)), "1.5 s");
+}
+
+#[test]
+fn test_timing_flag() {
+    assert!(Args::parse(["--timing".to_string()]).unwrap().timing);
+    assert!(!Args::parse([]).unwrap().timing);
+}
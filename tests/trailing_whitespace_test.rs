@@ -0,0 +1,77 @@
+use ised::app::App;
+use ised::config::Config;
+use ised::utils::{
+    added_trailing_whitespace, apply_substitution_with, highlight_diff_lines,
+    strip_changed_trailing_whitespace, SubstitutionOptions,
+};
+use ratatui::style::Color;
+
+#[test]
+fn test_detects_lines_that_gain_trailing_whitespace() {
+    let original = "a = 1\nkeep  \nb = 2\n";
+    let replaced = "a = 1 \nkeep  \nb = 2\n";
+    assert_eq!(added_trailing_whitespace(original, replaced), vec![1]);
+
+    let expanded = "a = 1\nx\ny\t\nkeep  \nb = 2\n";
+    assert_eq!(added_trailing_whitespace(original, expanded), vec![3]);
+
+    assert!(added_trailing_whitespace(original, original).is_empty());
+}
+
+#[test]
+fn test_diff_marks_added_trailing_whitespace() {
+    let lines = highlight_diff_lines("a\nb".to_string(), "a \nb".to_string());
+    let added = &lines[1];
+    assert_eq!(added.spans.len(), 3);
+    assert_eq!(added.spans[1].content, "a");
+    assert_eq!(added.spans[2].content, " ");
+    assert_eq!(added.spans[2].style.bg, Some(Color::Red));
+
+    let lines = highlight_diff_lines("a \nb".to_string(), "c \nb".to_string());
+    assert_eq!(lines[1].spans.len(), 2);
+}
+
+#[test]
+fn test_strip_only_touches_changed_lines() {
+    let original = "keep  \nfoo\r\nlast";
+    let replaced = "keep  \nbar \t\r\nlast ";
+    assert_eq!(
+        strip_changed_trailing_whitespace(original, replaced),
+        "keep  \nbar\r\nlast"
+    );
+}
+
+#[test]
+fn test_substitution_strips_when_enabled() {
+    let content = "x = foo\nother  \n";
+    let mut options = SubstitutionOptions::default();
+    assert_eq!(
+        apply_substitution_with(content, "foo", "bar  ", &options),
+        "x = bar  \nother  \n"
+    );
+
+    options.strip_trailing_whitespace = true;
+    assert_eq!(
+        apply_substitution_with(content, "foo", "bar  ", &options),
+        "x = bar\nother  \n"
+    );
+    assert_eq!(
+        apply_substitution_with(content, "foo", "1 \\n2 ", &options),
+        "x = 1\n2\nother  \n"
+    );
+}
+
+#[test]
+fn test_strip_is_read_from_config() {
+    assert!(!App::with_config(None).options.strip_trailing_whitespace);
+
+    let config: Config = toml::from_str("[behavior]\nstrip_trailing_whitespace = true").unwrap();
+    let app = App::with_config(Some(config));
+    assert!(app.options.strip_trailing_whitespace);
+    assert_eq!(
+        app.effective_config()
+            .behavior
+            .and_then(|b| b.strip_trailing_whitespace),
+        Some(true)
+    );
+}
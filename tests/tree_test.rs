@@ -0,0 +1,100 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::App;
+use ised::tree::{group_rows, ListRow};
+use std::collections::HashSet;
+
+fn files() -> Vec<String> {
+    [
+        "./src/ui.rs",
+        "./README.md",
+        "./src/app/mod.rs",
+        "./src/main.rs",
+        "./src/app/state.rs",
+        "./docs/guide.md",
+    ]
+    .iter()
+    .map(|f| f.to_string())
+    .collect()
+}
+
+fn dir(path: &str, collapsed: bool, files: usize) -> ListRow {
+    ListRow::Dir {
+        path: path.to_string(),
+        collapsed,
+        files,
+    }
+}
+
+fn file(path: &str) -> ListRow {
+    ListRow::File(path.to_string())
+}
+
+#[test]
+fn test_groups_files_under_sorted_directory_headers() {
+    let rows = group_rows(&files(), &HashSet::new());
+
+    assert_eq!(
+        rows,
+        vec![
+            dir(".", false, 1),
+            file("./README.md"),
+            dir("./docs", false, 1),
+            file("./docs/guide.md"),
+            dir("./src", false, 2),
+            file("./src/main.rs"),
+            file("./src/ui.rs"),
+            dir("./src/app", false, 2),
+            file("./src/app/mod.rs"),
+            file("./src/app/state.rs"),
+        ]
+    );
+}
+
+#[test]
+fn test_collapsing_hides_the_whole_subtree() {
+    let collapsed = HashSet::from(["./src".to_string()]);
+    let rows = group_rows(&files(), &collapsed);
+
+    assert_eq!(
+        rows,
+        vec![
+            dir(".", false, 1),
+            file("./README.md"),
+            dir("./docs", false, 1),
+            file("./docs/guide.md"),
+            dir("./src", true, 4),
+        ]
+    );
+}
+
+#[test]
+fn test_navigation_skips_collapsed_contents() {
+    let files = files();
+    let mut app = App::with_config(None);
+    let press = |app: &mut App, code: KeyCode, modifiers: KeyModifiers| {
+        app.handle_key_event(KeyEvent::new(code, modifiers), &files)
+            .unwrap();
+    };
+
+    press(&mut app, KeyCode::Char('g'), KeyModifiers::ALT);
+    assert!(app.group_by_dir);
+    assert_eq!(app.selected_file(&files).as_deref(), Some("./src/ui.rs"));
+
+    app.selected = 4;
+    press(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+    assert!(app.collapsed_dirs.contains("./src"));
+
+    press(&mut app, KeyCode::Down, KeyModifiers::NONE);
+    assert_eq!(app.selected, 4);
+
+    press(&mut app, KeyCode::Up, KeyModifiers::NONE);
+    assert_eq!(
+        app.selected_file(&files).as_deref(),
+        Some("./docs/guide.md")
+    );
+
+    press(&mut app, KeyCode::Down, KeyModifiers::NONE);
+    press(&mut app, KeyCode::Char(' '), KeyModifiers::NONE);
+    assert!(app.collapsed_dirs.is_empty());
+    assert!(app.deselected.is_empty());
+}
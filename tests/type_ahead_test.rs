@@ -0,0 +1,50 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::App;
+
+fn type_str(app: &mut App, text: &str, files: &[String]) {
+    for c in text.chars() {
+        app.handle_key_event(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE), files)
+            .unwrap();
+    }
+}
+
+fn files() -> Vec<String> {
+    [
+        "./Cargo.toml",
+        "./src/app.rs",
+        "./src/main.rs",
+        "./src/ui.rs",
+    ]
+    .iter()
+    .map(|f| f.to_string())
+    .collect()
+}
+
+#[test]
+fn test_typing_in_file_list_jumps_to_match() {
+    let files = files();
+    let mut app = App::with_config(None);
+
+    type_str(&mut app, "mai", &files);
+
+    assert_eq!(app.selected, 2);
+    assert_eq!(app.type_ahead(), Some("mai"));
+    assert!(app.filter_input.is_empty());
+    assert!(app.from_input.is_empty());
+}
+
+#[test]
+fn test_type_ahead_refines_and_clears_on_escape() {
+    let files = files();
+    let mut app = App::with_config(None);
+
+    type_str(&mut app, "ui", &files);
+    assert_eq!(app.selected, 3);
+
+    app.handle_key_event(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE), &files)
+        .unwrap();
+    assert_eq!(app.type_ahead(), None);
+
+    type_str(&mut app, "app", &files);
+    assert_eq!(app.selected, 1);
+}
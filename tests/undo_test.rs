@@ -0,0 +1,123 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::App;
+use std::fs;
+use tempdir::TempDir;
+
+fn press(app: &mut App, code: KeyCode, modifiers: KeyModifiers, files: &[String]) {
+    app.handle_key_event(KeyEvent::new(code, modifiers), files)
+        .unwrap();
+}
+
+fn write_fixture(dir: &TempDir, name: &str, content: &str) -> String {
+    let path = dir.path().join(name);
+    fs::write(&path, content).unwrap();
+    path.display().to_string()
+}
+
+fn substitution_app() -> App {
+    let mut app = App::with_config(None);
+    app.from_input = "foo".to_string();
+    app.to_input = "bar".to_string();
+    app
+}
+
+#[test]
+fn test_undo_reverts_a_whole_batch_in_one_step() {
+    let tmp_dir = TempDir::new("ised_test_undo_batch").unwrap();
+    let a = write_fixture(&tmp_dir, "a.txt", "foo\n");
+    let b = write_fixture(&tmp_dir, "b.txt", "x foo y\n");
+    let c = write_fixture(&tmp_dir, "c.txt", "nothing\n");
+    let files = vec![a.clone(), b.clone(), c.clone()];
+
+    let mut app = substitution_app();
+    press(&mut app, KeyCode::Char('a'), KeyModifiers::CONTROL, &files);
+    press(&mut app, KeyCode::Char('y'), KeyModifiers::NONE, &files);
+    assert_eq!(fs::read_to_string(&a).unwrap(), "bar\n");
+    assert_eq!(fs::read_to_string(&b).unwrap(), "x bar y\n");
+
+    press(&mut app, KeyCode::Char('z'), KeyModifiers::CONTROL, &files);
+    assert_eq!(fs::read_to_string(&a).unwrap(), "foo\n");
+    assert_eq!(fs::read_to_string(&b).unwrap(), "x foo y\n");
+    assert_eq!(fs::read_to_string(&c).unwrap(), "nothing\n");
+    assert_eq!(
+        app.status_message.as_deref(),
+        Some("Undid changes to 2 files")
+    );
+    assert!(!app.is_modified(&a));
+
+    press(&mut app, KeyCode::Char('z'), KeyModifiers::CONTROL, &files);
+    assert_eq!(app.status_message.as_deref(), Some("Nothing to undo"));
+}
+
+#[test]
+fn test_single_applies_are_undone_one_at_a_time() {
+    let tmp_dir = TempDir::new("ised_test_undo_single").unwrap();
+    let a = write_fixture(&tmp_dir, "a.txt", "foo");
+    let b = write_fixture(&tmp_dir, "b.txt", "foo");
+    let files = vec![a.clone(), b.clone()];
+
+    let mut app = substitution_app();
+    for _ in &files {
+        press(&mut app, KeyCode::Enter, KeyModifiers::NONE, &files);
+        press(&mut app, KeyCode::Char('y'), KeyModifiers::NONE, &files);
+        press(&mut app, KeyCode::Down, KeyModifiers::NONE, &files);
+    }
+    assert_eq!(fs::read_to_string(&a).unwrap(), "bar");
+    assert_eq!(fs::read_to_string(&b).unwrap(), "bar");
+
+    press(&mut app, KeyCode::Char('z'), KeyModifiers::CONTROL, &files);
+    assert_eq!(fs::read_to_string(&a).unwrap(), "bar");
+    assert_eq!(fs::read_to_string(&b).unwrap(), "foo");
+
+    press(&mut app, KeyCode::Char('z'), KeyModifiers::CONTROL, &files);
+    assert_eq!(fs::read_to_string(&a).unwrap(), "foo");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_batch_undo_only_restores_written_files() {
+    let tmp_dir = TempDir::new("ised_test_undo_partial").unwrap();
+    let a = write_fixture(&tmp_dir, "a.txt", "foo");
+    let target = write_fixture(&tmp_dir, "target.txt", "foo");
+    let link = tmp_dir.path().join("link.txt");
+    std::os::unix::fs::symlink(&target, &link).unwrap();
+    let link = link.display().to_string();
+    let files = vec![a.clone(), link.clone()];
+
+    let mut app = substitution_app();
+    press(&mut app, KeyCode::Char('a'), KeyModifiers::CONTROL, &files);
+    press(&mut app, KeyCode::Char('y'), KeyModifiers::NONE, &files);
+    assert_eq!(fs::read_to_string(&a).unwrap(), "bar");
+    assert_eq!(fs::read_to_string(&target).unwrap(), "foo");
+
+    fs::write(&target, "edited elsewhere").unwrap();
+    press(&mut app, KeyCode::Char('z'), KeyModifiers::CONTROL, &files);
+    assert_eq!(fs::read_to_string(&a).unwrap(), "foo");
+    assert_eq!(fs::read_to_string(&target).unwrap(), "edited elsewhere");
+    assert_eq!(
+        app.status_message.as_deref(),
+        Some("Undid changes to 1 files")
+    );
+}
+
+#[test]
+fn test_undo_skips_files_changed_since_apply() {
+    let tmp_dir = TempDir::new("ised_test_undo_changed").unwrap();
+    let a = write_fixture(&tmp_dir, "a.txt", "foo");
+    let b = write_fixture(&tmp_dir, "b.txt", "foo");
+    let files = vec![a.clone(), b.clone()];
+
+    let mut app = substitution_app();
+    press(&mut app, KeyCode::Char('a'), KeyModifiers::CONTROL, &files);
+    press(&mut app, KeyCode::Char('y'), KeyModifiers::NONE, &files);
+    fs::write(&b, "bar, edited").unwrap();
+
+    press(&mut app, KeyCode::Char('z'), KeyModifiers::CONTROL, &files);
+    assert_eq!(fs::read_to_string(&a).unwrap(), "foo");
+    assert_eq!(fs::read_to_string(&b).unwrap(), "bar, edited");
+    assert!(app
+        .status_message
+        .as_deref()
+        .unwrap()
+        .starts_with("Undid changes to 1 files, 1 failed"));
+}
@@ -0,0 +1,45 @@
+use ised::app::App;
+use std::fs;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+use tempdir::TempDir;
+
+fn wait_until(condition: impl Fn() -> bool) -> bool {
+    let deadline = Instant::now() + Duration::from_secs(2);
+    while Instant::now() < deadline {
+        if condition() {
+            return true;
+        }
+        sleep(Duration::from_millis(50));
+    }
+    false
+}
+
+#[test]
+fn test_toggling_watcher_off_stops_cache_invalidation() {
+    let tmp_dir = TempDir::new("ised_test_watcher").unwrap();
+    let path = tmp_dir.path().join("a.txt");
+    fs::write(&path, "foo").unwrap();
+
+    let mut app = App::new();
+    app.set_root(tmp_dir.path());
+    app.files = vec![path.display().to_string()];
+    app.from_input = "foo".to_string();
+    assert!(app.is_watching());
+    assert_eq!(app.filter_files().len(), 1);
+
+    fs::write(&path, "bar").unwrap();
+    assert!(wait_until(|| app.filter_files().is_empty()));
+    sleep(Duration::from_millis(300));
+    assert!(app.filter_files().is_empty());
+
+    app.toggle_watcher();
+    assert!(!app.is_watching());
+    sleep(Duration::from_millis(100));
+
+    fs::write(&path, "foo").unwrap();
+    assert!(!wait_until(|| !app.filter_files().is_empty()));
+
+    app.toggle_watcher();
+    assert!(app.is_watching());
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use common::{preview, render_rows};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::App;
+use ised::config::Config;
+
+fn render(app: &mut App) -> Vec<String> {
+    let files = vec!["long.txt".to_string()];
+    let content = format!("{}\nshort\n", "x".repeat(60));
+    render_rows(app, &files, Some(preview(&content)), 80, 20)[0..4]
+        .iter()
+        .map(|row| row.chars().skip(41).take(38).collect())
+        .collect()
+}
+
+#[test]
+fn test_toggle_wraps_long_diff_lines() {
+    let mut app = App::with_config(None);
+    app.is_loading = false;
+    assert!(!app.wrap_diff);
+
+    let rows = render(&mut app);
+    assert!(rows[1].trim_end().chars().all(|c| c == 'x'));
+    assert!(rows[2].contains("short"));
+
+    app.handle_key_event(KeyEvent::new(KeyCode::Char('w'), KeyModifiers::ALT), &[])
+        .unwrap();
+    assert!(app.wrap_diff);
+
+    let rows = render(&mut app);
+    assert!(rows[0].contains("(wrap)"));
+    assert!(rows[2].trim_end().chars().all(|c| c == 'x'));
+    assert!(!rows[2].trim_end().is_empty());
+    assert!(rows[3].contains("short"));
+}
+
+#[test]
+fn test_wrapped_diff_scrolls_by_line() {
+    let mut app = App::with_config(None);
+    app.is_loading = false;
+    app.wrap_diff = true;
+    app.diff_scroll = 1;
+
+    let rows = render(&mut app);
+    assert!(rows[1].contains("short"));
+}
+
+#[test]
+fn test_wrap_from_config() {
+    let config: Config = toml::from_str("[display]\nwrap_diff = true").unwrap();
+    assert!(App::with_config(Some(config)).wrap_diff);
+}
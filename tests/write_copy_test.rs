@@ -0,0 +1,50 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ised::app::App;
+use std::fs;
+use tempdir::TempDir;
+
+#[test]
+fn test_writes_result_to_a_new_file() {
+    let tmp_dir = TempDir::new("ised_test_copy").unwrap();
+    let path = tmp_dir.path().join("a.txt");
+    fs::write(&path, "foo bar\n").unwrap();
+    let path = path.display().to_string();
+    let files = vec![path.clone()];
+
+    let mut app = App::new();
+    app.from_input = "foo".to_string();
+    app.to_input = "baz".to_string();
+
+    app.handle_key_event(KeyEvent::new(KeyCode::Char('d'), KeyModifiers::ALT), &files)
+        .unwrap();
+
+    let target = format!("{}.new", path);
+    assert_eq!(fs::read_to_string(&target).unwrap(), "baz bar\n");
+    assert_eq!(fs::read_to_string(&path).unwrap(), "foo bar\n");
+    assert_eq!(
+        app.status_message,
+        Some(format!("Wrote result to {}", target))
+    );
+    assert!(!app.is_modified(&path));
+}
+
+#[test]
+fn test_existing_targets_are_not_overwritten() {
+    let tmp_dir = TempDir::new("ised_test_copy").unwrap();
+    let path = tmp_dir.path().join("a.txt");
+    fs::write(&path, "foo\n").unwrap();
+    let path = path.display().to_string();
+    fs::write(format!("{}.new", path), "keep").unwrap();
+
+    let mut app = App::new();
+    app.from_input = "foo".to_string();
+    app.to_input = "bar".to_string();
+
+    let first = app.write_copy(&path).unwrap();
+    let second = app.write_copy(&path).unwrap();
+
+    assert_eq!(first.display().to_string(), format!("{}.new.1", path));
+    assert_eq!(second.display().to_string(), format!("{}.new.2", path));
+    assert_eq!(fs::read_to_string(format!("{}.new", path)).unwrap(), "keep");
+    assert_eq!(fs::read_to_string(&first).unwrap(), "bar\n");
+}